@@ -0,0 +1,88 @@
+// Download the C++ dependencies of a vendordep with one progress bar per dependency.
+//
+// ```sh
+// cargo run --example indicatif_progress --features download -- Phoenix6.json out
+// ```
+
+use futures::StreamExt;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use vendordeps::{BinaryPlatform, DownloadConfig, DownloadEvent, VendorDep};
+
+fn main() -> vendordeps::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let (Some(json), Some(out)) = (args.next(), args.next()) else {
+        eprintln!("usage: indicatif_progress <vendordep.json> <out dir>");
+        std::process::exit(2);
+    };
+    let dep: VendorDep = serde_json::from_slice(&std::fs::read(json)?)?;
+    let config = DownloadConfig::default();
+
+    let multi = MultiProgress::new();
+    let style = ProgressStyle::with_template("{prefix:>24} [{bar:30}] {bytes}/{total_bytes} {msg}")
+        .unwrap()
+        .progress_chars("=> ");
+    let spinner = ProgressStyle::with_template("{prefix:>24} {spinner} {bytes} {msg}").unwrap();
+    let mut bars = Vec::new();
+
+    let mut events = std::pin::pin!(dep.download_all_cpp_deps_stream(
+        out,
+        BinaryPlatform::LinuxX86_64,
+        false,
+        false,
+        true,
+        &config,
+    ));
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(async {
+            while let Some(event) = events.next().await {
+                match event? {
+                    DownloadEvent::Started { total_artifacts } => {
+                        bars = dep
+                            .cpp_dependencies
+                            .iter()
+                            .take(total_artifacts)
+                            .map(|x| {
+                                let bar = multi.add(ProgressBar::new(0));
+                                bar.set_style(style.clone());
+                                bar.set_prefix(x.artifact_id.clone());
+                                bar
+                            })
+                            .collect();
+                    }
+                    DownloadEvent::Progress {
+                        index,
+                        bytes_downloaded,
+                        total_bytes,
+                        ..
+                    } => {
+                        let bar = &bars[index];
+                        match total_bytes {
+                            Some(total) => bar.set_length(total),
+                            None => bar.set_style(spinner.clone()),
+                        }
+                        bar.set_position(bytes_downloaded);
+                    }
+                    DownloadEvent::Extracting { index, entry, .. } => {
+                        bars[index].set_message(format!("extracting {}", entry));
+                    }
+                    DownloadEvent::DownloadedLib { artifact, .. } => {
+                        if let Some(bar) = bars.iter().find(|x| x.prefix() == artifact.artifact_id)
+                        {
+                            bar.finish_with_message("done");
+                        }
+                    }
+                    DownloadEvent::Skipped { artifact, .. } => {
+                        if let Some(bar) = bars.iter().find(|x| x.prefix() == artifact.artifact_id)
+                        {
+                            bar.abandon_with_message("skipped");
+                        }
+                    }
+                    DownloadEvent::Finished(_) => multi.println("finished").unwrap(),
+                    _ => {}
+                }
+            }
+            Ok(())
+        })
+}
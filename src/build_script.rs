@@ -0,0 +1,113 @@
+use std::path::Path;
+
+use crate::{error, BinaryPlatform, CppInfo, Result, VendorDep};
+
+// Read by reqwest when choosing a proxy, so they change what a download does.
+const PROXY_VARIABLES: &[&str] = &["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY", "NO_PROXY"];
+
+#[doc = "Download the shared release C++ libraries of `dep` for `platform` into `out_dir` from a `build.rs`, without setting up an async runtime. "]
+#[doc = "On success the link directives of the returned [`CppInfo`] are printed (see [`CppInfo::emit_cargo_directives`]); on failure, "]
+#[doc = "`cargo:warning` lines describe what went wrong before the error is returned. Either way, the build script is rerun when the proxy environment variables change. "]
+#[doc = "Must not be called from within an async runtime."]
+pub fn install_vendordep_blocking<P: AsRef<Path>>(
+    dep: &VendorDep,
+    out_dir: P,
+    platform: BinaryPlatform,
+) -> Result<CppInfo> {
+    for variable in PROXY_VARIABLES {
+        println!("cargo:rerun-if-env-changed={}", variable);
+    }
+    let res = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(error::Error::from)
+        .and_then(|runtime| {
+            runtime.block_on(dep.download_all_cpp_deps_to_folder(
+                out_dir.as_ref(),
+                platform,
+                false,
+                false,
+                false,
+            ))
+        });
+    match res {
+        Ok(info) => {
+            info.emit_cargo_directives();
+            Ok(info)
+        }
+        Err(e) => {
+            for warning in failure_warnings(dep, &e) {
+                println!("{}", warning);
+            }
+            Err(e)
+        }
+    }
+}
+
+#[doc = "The `cargo:warning` lines printed when installing `dep` fails with `err`."]
+fn failure_warnings(dep: &VendorDep, err: &error::Error) -> Vec<String> {
+    let mut warnings = vec![format!(
+        "cargo:warning=Could not install vendordep {} {}: {}",
+        dep.name, dep.version, err
+    )];
+    if let error::Error::NotFoundError { tried_urls, .. } = err {
+        warnings.extend(
+            tried_urls
+                .iter()
+                .map(|x| format!("cargo:warning=  tried {}", x)),
+        );
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::{failure_warnings, install_vendordep_blocking};
+    use crate::{
+        error::Error,
+        test_util::{cpp_dep, Mirror},
+        BinaryPlatform, VendorDep,
+    };
+
+    #[test]
+    fn warnings() {
+        let dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let err = Error::NotFoundError {
+            group_id: "com.ctre.phoenix6".to_string(),
+            artifact_id: "wpiapi-cpp".to_string(),
+            version: "24.3.0".to_string(),
+            tried_urls: vec!["https://maven.example.com/wpiapi-cpp-24.3.0-headers.zip".to_string()],
+        };
+        assert_eq!(
+            failure_warnings(&dep, &err),
+            vec![
+                "cargo:warning=Could not install vendordep CTRE-Phoenix (v6) 24.3.0: Could not find Maven artifact com.ctre.phoenix6:wpiapi-cpp:24.3.0.",
+                "cargo:warning=  tried https://maven.example.com/wpiapi-cpp-24.3.0-headers.zip",
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn install_blocking() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(cpp_dep("example-cpp"))
+            .build();
+        mirror.publish(
+            &dep.cpp_dependencies[0].get_url(&mirror.maven_url, "linuxx86-64", false, false),
+            include_bytes!("../tests/fixtures/executable.zip"),
+        );
+
+        let out = tempdir().unwrap();
+        let info =
+            install_vendordep_blocking(&dep, out.path(), BinaryPlatform::LinuxX86_64).unwrap();
+        assert_eq!(info.libraries, vec!["example"]);
+        assert!(install_vendordep_blocking(&dep, out.path(), BinaryPlatform::LinuxAthena).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{
+    CppDependency, FrcYear, JavaDependency, JniDependency, PackageSpec, VendorDep,
+    WPILIB_LATEST_VERSION, WPILIB_LATEST_YEAR,
+};
+
+#[doc = "Builder for a [`VendorDep`], so that generators don't have to spell out every field. Created with [`VendorDep::builder`]."]
+#[derive(Debug)]
+pub struct VendorDepBuilder {
+    inner: VendorDep,
+}
+
+impl VendorDep {
+    #[doc = "Start building a [`VendorDep`] named `name` with the given `uuid`. "]
+    #[doc = "The file name defaults to [`VendorDep::canonical_file_name`], the version to [`WPILIB_LATEST_VERSION`] and the year to [`WPILIB_LATEST_YEAR`]. Everything else starts out empty."]
+    pub fn builder(name: impl Into<String>, uuid: impl Into<String>) -> VendorDepBuilder {
+        let name = name.into();
+        VendorDepBuilder {
+            inner: VendorDep {
+                file_name: VendorDep::canonical_file_name(&name),
+                name,
+                version: WPILIB_LATEST_VERSION.to_string(),
+                frc_year: FrcYear(WPILIB_LATEST_YEAR),
+                uuid: uuid.into(),
+                maven_urls: vec![],
+                json_url: String::new(),
+                date: None,
+                conflicts_with: vec![],
+                requires: vec![],
+                java_dependencies: vec![],
+                jni_dependencies: vec![],
+                cpp_dependencies: vec![],
+            },
+        }
+    }
+}
+
+impl VendorDepBuilder {
+    #[doc = "Set [`VendorDep::file_name`]."]
+    pub fn file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.inner.file_name = file_name.into();
+        self
+    }
+
+    #[doc = "Set [`VendorDep::version`]."]
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.inner.version = version.into();
+        self
+    }
+
+    #[doc = "Set [`VendorDep::frc_year`]."]
+    pub fn frc_year(mut self, frc_year: impl Into<FrcYear>) -> Self {
+        self.inner.frc_year = frc_year.into();
+        self
+    }
+
+    #[doc = "Set [`VendorDep::json_url`]."]
+    pub fn json_url(mut self, json_url: impl Into<String>) -> Self {
+        self.inner.json_url = json_url.into();
+        self
+    }
+
+    #[doc = "Set [`VendorDep::date`]."]
+    pub fn date(mut self, date: impl Into<String>) -> Self {
+        self.inner.date = Some(date.into());
+        self
+    }
+
+    #[doc = "Add a repository to [`VendorDep::maven_urls`]."]
+    pub fn add_maven_url(mut self, maven_url: impl Into<String>) -> Self {
+        self.inner.maven_urls.push(maven_url.into());
+        self
+    }
+
+    #[doc = "Add an entry to [`VendorDep::conflicts_with`]."]
+    pub fn add_conflict(mut self, conflict: PackageSpec) -> Self {
+        self.inner.conflicts_with.push(conflict);
+        self
+    }
+
+    #[doc = "Add an entry to [`VendorDep::requires`]."]
+    pub fn add_requirement(mut self, requirement: PackageSpec) -> Self {
+        self.inner.requires.push(requirement);
+        self
+    }
+
+    #[doc = "Add an entry to [`VendorDep::java_dependencies`]."]
+    pub fn add_java_dependency(mut self, dep: JavaDependency) -> Self {
+        self.inner.java_dependencies.push(dep);
+        self
+    }
+
+    #[doc = "Add an entry to [`VendorDep::jni_dependencies`]."]
+    pub fn add_jni_dependency(mut self, dep: JniDependency) -> Self {
+        self.inner.jni_dependencies.push(dep);
+        self
+    }
+
+    #[doc = "Add an entry to [`VendorDep::cpp_dependencies`]."]
+    pub fn add_cpp_dependency(mut self, dep: CppDependency) -> Self {
+        self.inner.cpp_dependencies.push(dep);
+        self
+    }
+
+    #[doc = "Finish building. The result is not validated; see [`VendorDep::validate`]."]
+    pub fn build(self) -> VendorDep {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{JavaDependency, VendorDep, WPILIB_LATEST_VERSION, WPILIB_LATEST_YEAR};
+
+    #[test]
+    fn builder() {
+        let dep = VendorDep::builder("wpilib-wpimath", "6a4ac3f1-4a2b-4d3c-9e5f-0b1a2c3d4e5f")
+            .add_maven_url("https://frcmaven.wpi.edu/artifactory/release/")
+            .add_java_dependency(JavaDependency {
+                group_id: "edu.wpi.first.wpimath".to_string(),
+                artifact_id: "wpimath-java".to_string(),
+                version: WPILIB_LATEST_VERSION.to_string(),
+                classifier: None,
+                packaging: "jar".to_string(),
+                maven_urls: None,
+            })
+            .build();
+        assert_eq!(dep.file_name, "wpilib-wpimath.json");
+        assert_eq!(dep.version, WPILIB_LATEST_VERSION);
+        assert_eq!(dep.frc_year, WPILIB_LATEST_YEAR);
+        assert_eq!(dep.java_dependencies.len(), 1);
+        assert!(dep.conflicts_with.is_empty() && dep.cpp_dependencies.is_empty());
+        assert_eq!(dep.validate(WPILIB_LATEST_YEAR), Ok(()));
+    }
+}
@@ -0,0 +1,266 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::CppInfo;
+#[cfg(feature = "download")]
+use crate::{BinaryPlatform, DownloadConfig, Result, VendorDep};
+
+#[doc = "Name of the manifest file written to the root of an offline bundle and to each of its builds."]
+pub(crate) const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+#[doc = "Libraries recorded in a [`BundleBuild`]. Paths are relative to the build's directory."]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleLibraries {
+    #[doc = "Root directories containing headers."]
+    pub include_dirs: Vec<PathBuf>,
+    #[doc = "Directories containing libraries to link against."]
+    pub library_search_paths: Vec<PathBuf>,
+    #[doc = "Directories containing libraries loaded at runtime."]
+    #[serde(default)]
+    pub runtime_search_paths: Vec<PathBuf>,
+    #[doc = "Library names."]
+    pub libraries: Vec<String>,
+    #[doc = "The names in [`Self::libraries`] that are static archives."]
+    #[serde(default)]
+    pub static_libraries: Vec<String>,
+}
+
+impl BundleLibraries {
+    #[doc = "Record `info`, making its paths relative to `base`. Paths outside of `base` are kept as they are."]
+    pub fn from_cpp_info(info: &CppInfo, base: &Path) -> Self {
+        let relative = |x: &PathBuf| x.strip_prefix(base).unwrap_or(x).to_path_buf();
+        Self {
+            include_dirs: info.include_dirs.iter().map(relative).collect(),
+            library_search_paths: info.library_search_paths.iter().map(relative).collect(),
+            runtime_search_paths: info.runtime_search_paths.iter().map(relative).collect(),
+            libraries: info.libraries.clone(),
+            static_libraries: info.static_libraries.clone(),
+        }
+    }
+
+    #[doc = "Turn the recorded libraries back into a [`CppInfo`] for a build stored in `base`."]
+    pub fn to_cpp_info(&self, base: &Path) -> CppInfo {
+        CppInfo {
+            include_dirs: self.include_dirs.iter().map(|x| base.join(x)).collect(),
+            library_search_paths: self
+                .library_search_paths
+                .iter()
+                .map(|x| base.join(x))
+                .collect(),
+            runtime_search_paths: self
+                .runtime_search_paths
+                .iter()
+                .map(|x| base.join(x))
+                .collect(),
+            libraries: self.libraries.clone(),
+            static_libraries: self.static_libraries.clone(),
+        }
+    }
+}
+
+#[doc = "One platform and variant of an offline bundle. Also written to `manifest.json` inside the build's directory."]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleBuild {
+    #[doc = "Platform name, as in [`BinaryPlatform::to_str`](crate::BinaryPlatform::to_str)."]
+    pub platform: String,
+    #[doc = "Whether the C++ libraries are static."]
+    pub is_static: bool,
+    #[doc = "Whether the libraries are debug builds."]
+    pub is_debug: bool,
+    #[doc = "Directory of this build, relative to the bundle root."]
+    pub dir: PathBuf,
+    #[doc = "C++ headers and libraries, laid out like [`CppInfo::from_existing`] expects under `<dir>/cpp`."]
+    pub cpp: BundleLibraries,
+    #[doc = "JNI libraries, stored under `<dir>/jni`."]
+    pub jni: BundleLibraries,
+}
+
+#[doc = "Everything an offline bundle contains, written to `manifest.json` in the bundle root. See [`VendorDep::download_offline_bundle`](crate::VendorDep::download_offline_bundle)."]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+    #[doc = "Name of the bundled vendordep."]
+    pub name: String,
+    #[doc = "Version of the bundled vendordep."]
+    pub version: String,
+    #[doc = "Java jars, relative to the bundle root."]
+    pub java_jars: Vec<PathBuf>,
+    #[doc = "Every platform and variant that was downloaded."]
+    pub builds: Vec<BundleBuild>,
+}
+
+impl CppInfo {
+    #[doc = "Create new [`CppInfo`] from the manifest of one build of an offline bundle, written by [`VendorDep::download_offline_bundle`](crate::VendorDep::download_offline_bundle). "]
+    #[doc = "`p` is either the build's directory or its `manifest.json`. Unlike [`CppInfo::from_existing`], nothing is inferred from the files on disk."]
+    pub fn from_manifest<P: AsRef<Path>>(p: P) -> crate::Result<Self> {
+        let p = p.as_ref();
+        let (dir, manifest) = if p.is_dir() {
+            (p, p.join(MANIFEST_FILE_NAME))
+        } else {
+            (p.parent().unwrap_or(Path::new("")), p.to_path_buf())
+        };
+        let build: BundleBuild = serde_json::from_slice(&std::fs::read(manifest)?)?;
+        Ok(build.cpp.to_cpp_info(dir))
+    }
+}
+
+#[cfg(feature = "download")]
+impl VendorDep {
+    #[doc = "Download everything needed to build against this vendordep without internet access: the Java jars, and the JNI and C++ "]
+    #[doc = "libraries of every variant (shared, static, release and debug) of each of `platforms`. "]
+    #[doc = "Artifacts that aren't published for a platform or variant are skipped, so the returned manifest lists exactly what was fetched. "]
+    #[doc = "The layout is `<out_dir>/java/` for jars and `<out_dir>/<platform>/<variant>/(cpp|jni)/` for each build, where `<variant>` is one of `shared`, `shareddebug`, `static` and `staticdebug`."]
+    pub async fn download_offline_bundle<P: AsRef<Path>>(
+        &self,
+        out_dir: P,
+        platforms: &[BinaryPlatform],
+    ) -> Result<BundleManifest> {
+        self.download_offline_bundle_with_config(out_dir, platforms, &DownloadConfig::default())
+            .await
+    }
+
+    #[doc = "Like [`Self::download_offline_bundle`], with additional options."]
+    pub async fn download_offline_bundle_with_config<P: AsRef<Path>>(
+        &self,
+        out_dir: P,
+        platforms: &[BinaryPlatform],
+        config: &DownloadConfig,
+    ) -> Result<BundleManifest> {
+        let out_dir = out_dir.as_ref();
+        std::fs::create_dir_all(out_dir.join("java"))?;
+        let java_jars = self
+            .download_all_java_deps_to_folder_with_config(out_dir.join("java"), true, config)
+            .await?
+            .iter()
+            .map(|x| x.strip_prefix(out_dir).unwrap_or(x).to_path_buf())
+            .collect();
+        let mut builds = Vec::new();
+        for platform in platforms {
+            for (is_static, is_debug, variant) in [
+                (false, false, "shared"),
+                (false, true, "shareddebug"),
+                (true, false, "static"),
+                (true, true, "staticdebug"),
+            ] {
+                let dir = Path::new(platform.to_str()).join(variant);
+                let build_dir = out_dir.join(&dir);
+                let cpp = self
+                    .download_all_cpp_deps_to_folder_with_config(
+                        build_dir.join("cpp"),
+                        platform.clone(),
+                        is_static,
+                        is_debug,
+                        true,
+                        config,
+                    )
+                    .await?;
+                let jni = self
+                    .download_all_jni_deps_to_folder_with_config(
+                        build_dir.join("jni"),
+                        platform.clone(),
+                        is_debug,
+                        true,
+                        config,
+                    )
+                    .await?;
+                let build = BundleBuild {
+                    platform: platform.to_str().to_string(),
+                    is_static,
+                    is_debug,
+                    dir,
+                    cpp: BundleLibraries::from_cpp_info(&cpp, &build_dir),
+                    jni: BundleLibraries::from_cpp_info(&jni, &build_dir),
+                };
+                std::fs::create_dir_all(&build_dir)?;
+                std::fs::write(
+                    build_dir.join(MANIFEST_FILE_NAME),
+                    serde_json::to_string_pretty(&build)?,
+                )?;
+                builds.push(build);
+            }
+        }
+        let manifest = BundleManifest {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            java_jars,
+            builds,
+        };
+        std::fs::write(
+            out_dir.join(MANIFEST_FILE_NAME),
+            serde_json::to_string_pretty(&manifest)?,
+        )?;
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::{BundleBuild, BundleLibraries};
+    use crate::CppInfo;
+
+    #[test]
+    fn relative_libraries() {
+        let info = CppInfo {
+            include_dirs: vec!["/bundle/linuxathena/shared/cpp/wpiutil-cpp/include".into()],
+            library_search_paths: vec![
+                "/bundle/linuxathena/shared/cpp/wpiutil-cpp/libs/linux/athena/shared".into(),
+            ],
+            runtime_search_paths: vec![],
+            libraries: vec!["wpiutil".to_string()],
+            static_libraries: vec![],
+        };
+        let recorded = BundleLibraries::from_cpp_info(
+            &info,
+            std::path::Path::new("/bundle/linuxathena/shared"),
+        );
+        assert_eq!(
+            recorded.include_dirs,
+            vec![PathBuf::from("cpp/wpiutil-cpp/include")]
+        );
+        let restored = recorded.to_cpp_info(std::path::Path::new("/offline/linuxathena/shared"));
+        assert_eq!(
+            restored.library_search_paths,
+            vec![PathBuf::from(
+                "/offline/linuxathena/shared/cpp/wpiutil-cpp/libs/linux/athena/shared"
+            )]
+        );
+        assert_eq!(restored.libraries, info.libraries);
+    }
+
+    #[test]
+    fn from_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let build = BundleBuild {
+            platform: "linuxx86-64".to_string(),
+            is_static: false,
+            is_debug: false,
+            dir: "linuxx86-64/shared".into(),
+            cpp: BundleLibraries {
+                include_dirs: vec!["cpp/wpiutil-cpp/include".into()],
+                library_search_paths: vec!["cpp/wpiutil-cpp/libs/linux/x86-64/shared".into()],
+                runtime_search_paths: vec![],
+                libraries: vec!["wpiutil".to_string()],
+                static_libraries: vec![],
+            },
+            jni: BundleLibraries::default(),
+        };
+        std::fs::write(
+            dir.path().join("manifest.json"),
+            serde_json::to_string(&build).unwrap(),
+        )
+        .unwrap();
+        for p in [dir.path().to_path_buf(), dir.path().join("manifest.json")] {
+            let info = CppInfo::from_manifest(p).unwrap();
+            assert_eq!(
+                info.include_dirs,
+                vec![dir.path().join("cpp/wpiutil-cpp/include")]
+            );
+            assert_eq!(info.libraries, vec!["wpiutil".to_string()]);
+        }
+    }
+}
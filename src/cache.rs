@@ -0,0 +1,211 @@
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::Result;
+
+#[doc = "A directory of downloaded artifacts shared by every project on a machine, as stored in [`DownloadConfig::cache`](crate::DownloadConfig::cache). "]
+#[doc = "Artifacts are looked up by the URL they are requested from, after [`DownloadConfig::url_rewriter`](crate::DownloadConfig::url_rewriter), "]
+#[doc = "and stored once per SHA-256 of their contents, which is checked again whenever one is read back, so a damaged entry is downloaded anew instead of extracted. "]
+#[doc = "Entries are written to a temporary file and renamed into place, so builds sharing the cache concurrently never see a partially written one "]
+#[doc = "and don't need to lock it. `-SNAPSHOT` artifacts, which change under the same URL, are never cached. Nothing is ever evicted; delete the directory to clear it."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadCache {
+    dir: PathBuf,
+}
+
+impl DownloadCache {
+    #[doc = "Cache artifacts in `dir`, which is created when the first one is stored."]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    #[doc = "Cache artifacts in [`Self::default_dir`], if there is one."]
+    pub fn in_default_dir() -> Option<Self> {
+        Self::default_dir().map(Self::new)
+    }
+
+    #[doc = "The `vendordeps` directory in the user's cache directory: `%LOCALAPPDATA%` on Windows, `~/Library/Caches` on macOS, "]
+    #[doc = "and `$XDG_CACHE_HOME` or `~/.cache` elsewhere. `None` if the environment doesn't say where that is."]
+    pub fn default_dir() -> Option<PathBuf> {
+        let env_dir = |name| {
+            std::env::var_os(name)
+                .map(PathBuf::from)
+                .filter(|x| x.is_absolute())
+        };
+        let base = if cfg!(windows) {
+            env_dir("LOCALAPPDATA")?
+        } else if cfg!(target_os = "macos") {
+            env_dir("HOME")?.join("Library/Caches")
+        } else {
+            env_dir("XDG_CACHE_HOME").or_else(|| Some(env_dir("HOME")?.join(".cache")))?
+        };
+        Some(base.join("vendordeps"))
+    }
+
+    #[doc = "The directory artifacts are cached in."]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // Records which blob each URL resolved to.
+    fn index_path(&self, url: &str) -> PathBuf {
+        self.dir
+            .join("urls")
+            .join(format!("{:x}", Sha256::digest(url.as_bytes())))
+    }
+
+    fn blob_path(&self, sha256: &str) -> PathBuf {
+        self.dir.join("blobs").join(sha256)
+    }
+
+    #[doc = "Open the artifact cached for `url`, if there is one and its contents still match their digest. Entries that don't are removed."]
+    pub(crate) fn get(&self, url: &str) -> Option<std::fs::File> {
+        if !cacheable(url) {
+            return None;
+        }
+        let sha256 = std::fs::read_to_string(self.index_path(url)).ok()?;
+        let blob = self.blob_path(sha256.trim());
+        let mut file = std::fs::File::open(&blob).ok()?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher).ok()?;
+        if format!("{:x}", hasher.finalize()) != sha256.trim() {
+            log_warn!("Removing {} from the download cache, it is damaged", url);
+            _ = std::fs::remove_file(&blob);
+            return None;
+        }
+        std::io::Seek::rewind(&mut file).ok()?;
+        log_debug!("Using the cached copy of {}", url);
+        Some(file)
+    }
+
+    #[doc = "Store `contents` as the artifact downloaded from `url`."]
+    pub(crate) fn put(&self, url: &str, contents: &mut dyn Read) -> Result<()> {
+        if !cacheable(url) {
+            return Ok(());
+        }
+        std::fs::create_dir_all(self.dir.join("blobs"))?;
+        std::fs::create_dir_all(self.dir.join("urls"))?;
+        let mut blob = tempfile::NamedTempFile::new_in(&self.dir)?;
+        let mut hasher = Sha256::new();
+        let mut buf = [0; 64 * 1024];
+        loop {
+            let read = contents.read(&mut buf)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
+            blob.write_all(&buf[..read])?;
+        }
+        let sha256 = format!("{:x}", hasher.finalize());
+        persist(blob, &self.blob_path(&sha256))?;
+        // Written after the blob, so the index never points at a blob that isn't there yet.
+        let mut index = tempfile::NamedTempFile::new_in(&self.dir)?;
+        index.write_all(sha256.as_bytes())?;
+        persist(index, &self.index_path(url))
+    }
+}
+
+// Snapshots are republished under the same URL, so a cached copy would go stale.
+fn cacheable(url: &str) -> bool {
+    !url.contains("-SNAPSHOT")
+}
+
+// Rename `file` to `path`. Another build may have just done the same, and as entries with the same path have the same contents, either copy will do.
+fn persist(file: tempfile::NamedTempFile, path: &Path) -> Result<()> {
+    match file.persist(path) {
+        Ok(_) => Ok(()),
+        Err(_) if path.exists() => Ok(()),
+        Err(e) => Err(e.error.into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use super::DownloadCache;
+
+    fn read(file: Option<std::fs::File>) -> Option<Vec<u8>> {
+        let mut bytes = Vec::new();
+        file?.read_to_end(&mut bytes).unwrap();
+        Some(bytes)
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::new(dir.path().join("cache"));
+        let url = "https://frcmaven.wpi.edu/a-1.0.0.jar";
+        assert_eq!(read(cache.get(url)), None);
+
+        cache.put(url, &mut b"PK\x05\x06".as_slice()).unwrap();
+        assert_eq!(read(cache.get(url)), Some(b"PK\x05\x06".to_vec()));
+        assert_eq!(
+            read(cache.get("https://frcmaven.wpi.edu/b-1.0.0.jar")),
+            None
+        );
+
+        // Identical contents are stored once.
+        cache
+            .put(
+                "https://mirror.example/a-1.0.0.jar",
+                &mut b"PK\x05\x06".as_slice(),
+            )
+            .unwrap();
+        assert_eq!(
+            std::fs::read_dir(dir.path().join("cache/blobs"))
+                .unwrap()
+                .count(),
+            1
+        );
+
+        let snapshot = "https://frcmaven.wpi.edu/a-1.0.0-SNAPSHOT.jar";
+        cache.put(snapshot, &mut b"PK\x05\x06".as_slice()).unwrap();
+        assert_eq!(read(cache.get(snapshot)), None);
+    }
+
+    #[test]
+    fn damaged_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::new(dir.path());
+        let url = "https://frcmaven.wpi.edu/a-1.0.0.jar";
+        cache.put(url, &mut b"PK\x05\x06".as_slice()).unwrap();
+        let blob = std::fs::read_dir(dir.path().join("blobs"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        std::fs::write(&blob, b"PK\x05").unwrap();
+        assert_eq!(read(cache.get(url)), None);
+        assert!(!blob.exists());
+
+        cache.put(url, &mut b"PK\x05\x06".as_slice()).unwrap();
+        assert_eq!(read(cache.get(url)), Some(b"PK\x05\x06".to_vec()));
+    }
+
+    #[test]
+    fn concurrent_writers() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::new(dir.path());
+        let url = "https://frcmaven.wpi.edu/a-1.0.0.jar";
+        let contents = vec![7; 1 << 20];
+        std::thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| cache.put(url, &mut contents.as_slice()).unwrap());
+            }
+        });
+        assert_eq!(read(cache.get(url)), Some(contents));
+        // No temporary files are left behind.
+        let mut entries = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|x| x.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        entries.sort();
+        assert_eq!(entries, ["blobs", "urls"]);
+    }
+}
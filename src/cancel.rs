@@ -0,0 +1,36 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::{error, Result};
+
+#[doc = "Signal used to cancel a download in progress, for example from a UI thread. Clones share the same state. "]
+#[doc = "Downloads check it between dependencies and between extracted files, failing with [`error::Error::Cancelled`] once it is cancelled."]
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    #[doc = "Create a token that has not been cancelled."]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[doc = "Cancel every download using this token or a clone of it."]
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    #[doc = "Whether [`Self::cancel`] has been called."]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(error::Error::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{ArtifactKind, BinaryPlatform, Coordinate, VendorDep};
+
+#[doc = "The platforms of one native dependency, from [`VendorDep::platform_coverage`]."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyCoverage {
+    #[doc = "The dependency, a [`crate::JniDependency`] or [`crate::CppDependency`]."]
+    pub artifact: Coordinate,
+    #[doc = "Platforms the dependency is published for."]
+    pub platforms: Vec<BinaryPlatform>,
+    #[doc = "Whether the dependency lists no platforms, and so counts as published for every one."]
+    pub unrestricted: bool,
+}
+
+#[doc = "Which platforms the native dependencies of a vendordep are published for. See [`VendorDep::platform_coverage`]."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlatformCoverage {
+    #[doc = "Platforms every native dependency is published for, on which the vendordep is usable."]
+    pub common: Vec<BinaryPlatform>,
+    #[doc = "Platforms at least one native dependency is published for."]
+    pub any: Vec<BinaryPlatform>,
+    #[doc = "Each JNI dependency, then each C++ dependency."]
+    pub per_dep: Vec<DependencyCoverage>,
+}
+
+impl PlatformCoverage {
+    #[doc = "Whether the vendordep is usable on `platform`."]
+    pub fn supports(&self, platform: &BinaryPlatform) -> bool {
+        self.common.contains(platform)
+    }
+}
+
+const KNOWN_PLATFORMS: [BinaryPlatform; 7] = [
+    BinaryPlatform::LinuxArm32,
+    BinaryPlatform::LinuxArm64,
+    BinaryPlatform::LinuxAthena,
+    BinaryPlatform::LinuxX86_64,
+    BinaryPlatform::OsxUniversal,
+    BinaryPlatform::WindowsArm64,
+    BinaryPlatform::WindowsX86_64,
+];
+
+impl VendorDep {
+    #[doc = "Summarize which platforms the JNI and C++ dependencies are published for, from [`crate::JniDependency::valid_platforms`] and [`crate::CppDependency::binary_platforms`]. "]
+    #[doc = "A dependency listing no platforms counts as published for every platform: the library platforms of [`BinaryPlatform`] and any other platform some dependency lists. "]
+    #[doc = "A vendordep without native dependencies is usable everywhere, so both [`PlatformCoverage::common`] and [`PlatformCoverage::any`] are every platform. "]
+    #[doc = "Platforms are in the order of [`BinaryPlatform`]'s variants, then in the order other platforms first appear."]
+    pub fn platform_coverage(&self) -> PlatformCoverage {
+        let jni = self.jni_dependencies.iter().map(|x| {
+            let artifact = Coordinate {
+                group_id: x.group_id.clone(),
+                artifact_id: x.artifact_id.clone(),
+                version: x.version.clone(),
+                kind: ArtifactKind::Jni,
+            };
+            (&x.valid_platforms, artifact)
+        });
+        let cpp = self.cpp_dependencies.iter().map(|x| {
+            let artifact = Coordinate {
+                group_id: x.group_id.clone(),
+                artifact_id: x.artifact_id.clone(),
+                version: x.version.clone(),
+                kind: ArtifactKind::Cpp,
+            };
+            (&x.binary_platforms, artifact)
+        });
+        let declared = jni.chain(cpp).collect::<Vec<_>>();
+
+        let mut all = KNOWN_PLATFORMS.to_vec();
+        for platform in declared.iter().flat_map(|(x, _)| x.iter()) {
+            let platform = match platform.parse::<BinaryPlatform>() {
+                Ok(x) => x,
+                Err(e) => match e {},
+            };
+            if platform != BinaryPlatform::Headers && !all.contains(&platform) {
+                all.push(platform);
+            }
+        }
+
+        let per_dep = declared
+            .into_iter()
+            .map(|(platforms, artifact)| DependencyCoverage {
+                artifact,
+                platforms: all
+                    .iter()
+                    .filter(|x| platforms.is_empty() || platforms.iter().any(|p| p == x.to_str()))
+                    .cloned()
+                    .collect(),
+                unrestricted: platforms.is_empty(),
+            })
+            .collect::<Vec<DependencyCoverage>>();
+
+        if per_dep.is_empty() {
+            return PlatformCoverage {
+                common: all.clone(),
+                any: all,
+                per_dep,
+            };
+        }
+        PlatformCoverage {
+            common: all
+                .iter()
+                .filter(|x| per_dep.iter().all(|dep| dep.platforms.contains(x)))
+                .cloned()
+                .collect(),
+            any: all
+                .iter()
+                .filter(|x| per_dep.iter().any(|dep| dep.platforms.contains(x)))
+                .cloned()
+                .collect(),
+            per_dep,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{BinaryPlatform, VendorDep};
+
+    #[test]
+    fn platform_coverage() {
+        let mut dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let coverage = dep.platform_coverage();
+        assert_eq!(
+            coverage.common,
+            vec![BinaryPlatform::LinuxX86_64, BinaryPlatform::WindowsX86_64]
+        );
+        assert_eq!(
+            coverage.any,
+            vec![
+                BinaryPlatform::LinuxAthena,
+                BinaryPlatform::LinuxX86_64,
+                BinaryPlatform::OsxUniversal,
+                BinaryPlatform::WindowsX86_64
+            ]
+        );
+        assert_eq!(coverage.per_dep.len(), 4);
+        assert_eq!(coverage.per_dep[2].artifact.artifact_id, "wpiapi-cpp");
+        assert!(!coverage.supports(&BinaryPlatform::WindowsArm64));
+
+        // A dependency without platforms is published for every platform.
+        dep.jni_dependencies.clear();
+        dep.cpp_dependencies.truncate(1);
+        dep.cpp_dependencies[0].binary_platforms.clear();
+        let coverage = dep.platform_coverage();
+        assert!(coverage.per_dep[0].unrestricted);
+        assert_eq!(coverage.common.len(), 7);
+        assert_eq!(coverage.common, coverage.any);
+
+        dep.cpp_dependencies[0].binary_platforms = vec!["osxarm64".to_string()];
+        let coverage = dep.platform_coverage();
+        assert_eq!(
+            coverage.common,
+            vec![BinaryPlatform::Other("osxarm64".to_string())]
+        );
+    }
+}
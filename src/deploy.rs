@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{classify_native, CppInfo, NativeKind, Result};
+
+#[doc = "Directory on a roboRIO that GradleRIO deploys vendor libraries to, and that robot programs load them from."]
+pub const ROBORIO_LIBRARY_DIR: &str = "/usr/local/frc/third-party/lib";
+
+#[doc = "One file to copy to the robot."]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployFile {
+    #[doc = "The downloaded file."]
+    pub source: PathBuf,
+    #[doc = "Where it goes on the robot. Always a `/`-separated absolute path, whatever the host platform."]
+    pub destination: String,
+}
+
+#[doc = "Which downloaded libraries go where on the robot, for a deploy tool (over SSH, SFTP or otherwise) to act on. Created with [`CppInfo::deploy_manifest`]."]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployManifest {
+    #[doc = "Files to copy, sorted by destination."]
+    pub files: Vec<DeployFile>,
+}
+
+impl CppInfo {
+    #[doc = "List the shared libraries in [`Self::runtime_search_paths`] with their destination in `robot_dir` on the robot, "]
+    #[doc = "usually [`ROBORIO_LIBRARY_DIR`] for the result of a `download_all_*` for [`BinaryPlatform::LinuxAthena`](crate::BinaryPlatform::LinuxAthena). "]
+    #[doc = "Static libraries and debug symbols are left out, since they aren't loaded at runtime. If two directories hold a library of the same name, the first one wins."]
+    pub fn deploy_manifest(&self, robot_dir: &str) -> Result<DeployManifest> {
+        let robot_dir = robot_dir.trim_end_matches('/');
+        let mut files: Vec<DeployFile> = Vec::new();
+        for dir in &self.runtime_search_paths {
+            let mut entries = std::fs::read_dir(dir)?
+                .map(|x| x.map(|x| x.path()))
+                .collect::<std::io::Result<Vec<_>>>()?;
+            entries.sort();
+            for source in entries {
+                if !matches!(classify_native(&source), Some((NativeKind::Shared, _))) {
+                    continue;
+                }
+                let Some(file_name) = source.file_name().and_then(|x| x.to_str()) else {
+                    continue;
+                };
+                let destination = format!("{}/{}", robot_dir, file_name);
+                if let Some(existing) = files.iter().find(|x| x.destination == destination) {
+                    log_warn!(
+                        "Not deploying {}, {} already goes to {}",
+                        source.display(),
+                        existing.source.display(),
+                        destination
+                    );
+                    continue;
+                }
+                files.push(DeployFile {
+                    source,
+                    destination,
+                });
+            }
+        }
+        files.sort_by(|a, b| a.destination.cmp(&b.destination));
+        Ok(DeployManifest { files })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::{DeployFile, DeployManifest, ROBORIO_LIBRARY_DIR};
+    use crate::CppInfo;
+
+    #[test]
+    fn deploy_manifest() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("first/linux/athena/shared");
+        let second = dir.path().join("second/linux/athena/shared");
+        for (dir, files) in [
+            (
+                &first,
+                &["libfirst.so", "libfirst.so.debug", "libfirst.a"][..],
+            ),
+            (&second, &["libsecond.so", "libfirst.so"][..]),
+        ] {
+            std::fs::create_dir_all(dir).unwrap();
+            for file in files {
+                std::fs::write(dir.join(file), b"").unwrap();
+            }
+        }
+        let info = CppInfo {
+            runtime_search_paths: vec![first.clone(), second.clone()],
+            ..CppInfo::new_empty()
+        };
+
+        let manifest = info.deploy_manifest(ROBORIO_LIBRARY_DIR).unwrap();
+        assert_eq!(
+            manifest,
+            DeployManifest {
+                files: vec![
+                    DeployFile {
+                        source: first.join("libfirst.so"),
+                        destination: "/usr/local/frc/third-party/lib/libfirst.so".to_string(),
+                    },
+                    DeployFile {
+                        source: second.join("libsecond.so"),
+                        destination: "/usr/local/frc/third-party/lib/libsecond.so".to_string(),
+                    },
+                ]
+            }
+        );
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DeployManifest>(&json).unwrap(),
+            manifest
+        );
+        assert_eq!(
+            info.deploy_manifest("/home/lvuser/").unwrap().files[0].destination,
+            "/home/lvuser/libfirst.so"
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{BinaryPlatform, Coordinate};
+
+#[doc = "Something unusual that a download got past without failing, collected in [`DownloadConfig::diagnostics`](crate::DownloadConfig::diagnostics) "]
+#[doc = "so it can be shown to users without parsing the log. Each is also logged as it happens."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    #[doc = "A dependency, or the headers of one, wasn't found in any repository and was skipped, as `skip_failed_packages` allows."]
+    Skipped {
+        #[doc = "The dependency."]
+        artifact: Coordinate,
+    },
+    #[doc = "A JNI dependency that [`skip_invalid_platforms`](crate::JniDependency::skip_invalid_platforms) isn't published for the platform, so it was left out."]
+    NotPublished {
+        #[doc = "The dependency."]
+        artifact: Coordinate,
+        #[doc = "The platform being downloaded for."]
+        platform: BinaryPlatform,
+    },
+    #[doc = "The libraries of a dependency were downloaded for one of the [`BinaryPlatform::fallbacks`] of the requested platform, such as `osxuniversal` for `osxarm64`."]
+    PlatformFallback {
+        #[doc = "The dependency."]
+        artifact: Coordinate,
+        #[doc = "The platform being downloaded for."]
+        requested: BinaryPlatform,
+        #[doc = "The platform whose libraries were downloaded."]
+        used: BinaryPlatform,
+    },
+    #[doc = "A C++ dependency only publishes the other linkage than the one requested, which was downloaded instead because of "]
+    #[doc = "[`DownloadConfig::linkage_fallback`](crate::DownloadConfig::linkage_fallback)."]
+    LinkageFallback {
+        #[doc = "The dependency."]
+        artifact: Coordinate,
+        #[doc = "The platform whose libraries were downloaded."]
+        platform: BinaryPlatform,
+        #[doc = "Whether the static build was downloaded, rather than the shared one."]
+        is_static: bool,
+    },
+    #[doc = "No repository has the header artifact a C++ dependency declares, so headers were only looked for among its libraries."]
+    HeadersMissing {
+        #[doc = "The dependency."]
+        artifact: Coordinate,
+    },
+    #[doc = "Downloading an artifact from one repository failed. The remaining repositories, if any, were tried next."]
+    RepositoryFailed {
+        #[doc = "The dependency."]
+        artifact: Coordinate,
+        #[doc = "The repository."]
+        maven_url: String,
+        #[doc = "Why it failed."]
+        error: String,
+    },
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skipped { artifact } => {
+                write!(
+                    f,
+                    "{} was not found in any repository and was skipped",
+                    artifact
+                )
+            }
+            Self::NotPublished { artifact, platform } => write!(
+                f,
+                "{} is not published for {} and was left out",
+                artifact,
+                platform.to_str()
+            ),
+            Self::PlatformFallback {
+                artifact,
+                requested,
+                used,
+            } => write!(
+                f,
+                "{} was downloaded for {} in place of {}",
+                artifact,
+                used.to_str(),
+                requested.to_str()
+            ),
+            Self::LinkageFallback {
+                artifact,
+                platform,
+                is_static,
+            } => write!(
+                f,
+                "{} has no {} build for {}, the {} one was downloaded instead",
+                artifact,
+                if *is_static { "shared" } else { "static" },
+                platform.to_str(),
+                if *is_static { "static" } else { "shared" }
+            ),
+            Self::HeadersMissing { artifact } => write!(
+                f,
+                "{} has no header artifact, headers were looked for among its libraries",
+                artifact
+            ),
+            Self::RepositoryFailed {
+                artifact,
+                maven_url,
+                error,
+            } => write!(
+                f,
+                "Failed to download {} from {}: {}",
+                artifact, maven_url, error
+            ),
+        }
+    }
+}
+
+#[doc = "Collects the [`Diagnostic`]s of the downloads using it, set as [`DownloadConfig::diagnostics`](crate::DownloadConfig::diagnostics). Clones share the same list, "]
+#[doc = "so keep a clone, or read it through [`Downloader::config`](crate::Downloader::config), once the download finishes."]
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics(Arc<Mutex<Vec<Diagnostic>>>);
+
+impl Diagnostics {
+    #[doc = "Create an empty list."]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[doc = "The diagnostics so far, in the order they happened. Dependencies download concurrently, so those of different dependencies interleave."]
+    pub fn list(&self) -> Vec<Diagnostic> {
+        self.0.lock().unwrap_or_else(|x| x.into_inner()).clone()
+    }
+
+    #[doc = "Like [`Self::list`], also clearing the list, such as between the downloads of a [`Downloader`](crate::Downloader)."]
+    pub fn take(&self) -> Vec<Diagnostic> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(|x| x.into_inner()))
+    }
+
+    pub(crate) fn push(&self, diagnostic: Diagnostic) {
+        self.0
+            .lock()
+            .unwrap_or_else(|x| x.into_inner())
+            .push(diagnostic);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{ArtifactKind, Coordinate, VendorDep};
+
+#[doc = "Differences between two versions of a vendordep, found by [`VendorDep::diff`]."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VendorDepDiff {
+    #[doc = "Changes to [`VendorDep::java_dependencies`]."]
+    pub java: DependencyDiff,
+    #[doc = "Changes to [`VendorDep::jni_dependencies`]."]
+    pub jni: DependencyDiff,
+    #[doc = "Changes to [`VendorDep::cpp_dependencies`]."]
+    pub cpp: DependencyDiff,
+}
+
+impl VendorDepDiff {
+    #[doc = "Whether no dependency was added, removed or changed."]
+    pub fn is_empty(&self) -> bool {
+        self.java.is_empty() && self.jni.is_empty() && self.cpp.is_empty()
+    }
+}
+
+#[doc = "Changes to one dependency list, matching dependencies by `group:artifact`."]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyDiff {
+    #[doc = "Dependencies only in the newer vendordep."]
+    pub added: Vec<Coordinate>,
+    #[doc = "Dependencies only in the older vendordep."]
+    pub removed: Vec<Coordinate>,
+    #[doc = "Dependencies in both whose version or platforms changed."]
+    pub changed: Vec<DependencyChange>,
+}
+
+impl DependencyDiff {
+    #[doc = "Whether no dependency was added, removed or changed."]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[doc = "A dependency present in both vendordeps with a different version or platform list."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyChange {
+    #[doc = "Maven group."]
+    pub group_id: String,
+    #[doc = "Maven artifact."]
+    pub artifact_id: String,
+    #[doc = "Version in the older vendordep."]
+    pub old_version: String,
+    #[doc = "Version in the newer vendordep."]
+    pub new_version: String,
+    #[doc = "Platforms only supported by the newer vendordep. Always empty for Java dependencies."]
+    pub added_platforms: Vec<String>,
+    #[doc = "Platforms only supported by the older vendordep. Always empty for Java dependencies."]
+    pub removed_platforms: Vec<String>,
+}
+
+struct Entry<'a> {
+    group_id: &'a str,
+    artifact_id: &'a str,
+    version: &'a str,
+    platforms: &'a [String],
+}
+
+impl Entry<'_> {
+    fn coordinate(&self, kind: ArtifactKind) -> Coordinate {
+        Coordinate {
+            group_id: self.group_id.to_string(),
+            artifact_id: self.artifact_id.to_string(),
+            version: self.version.to_string(),
+            kind,
+        }
+    }
+}
+
+fn diff_entries(kind: ArtifactKind, old: &[Entry], new: &[Entry]) -> DependencyDiff {
+    let find = |entries: &[Entry], x: &Entry| {
+        entries
+            .iter()
+            .position(|y| y.group_id == x.group_id && y.artifact_id == x.artifact_id)
+    };
+    let mut diff = DependencyDiff::default();
+    for x in old {
+        if find(new, x).is_none() {
+            diff.removed.push(x.coordinate(kind));
+        }
+    }
+    for x in new {
+        let Some(i) = find(old, x) else {
+            diff.added.push(x.coordinate(kind));
+            continue;
+        };
+        let y = &old[i];
+        let added_platforms = x
+            .platforms
+            .iter()
+            .filter(|p| !y.platforms.contains(p))
+            .cloned()
+            .collect::<Vec<_>>();
+        let removed_platforms = y
+            .platforms
+            .iter()
+            .filter(|p| !x.platforms.contains(p))
+            .cloned()
+            .collect::<Vec<_>>();
+        if x.version != y.version || !added_platforms.is_empty() || !removed_platforms.is_empty() {
+            diff.changed.push(DependencyChange {
+                group_id: x.group_id.to_string(),
+                artifact_id: x.artifact_id.to_string(),
+                old_version: y.version.to_string(),
+                new_version: x.version.to_string(),
+                added_platforms,
+                removed_platforms,
+            });
+        }
+    }
+    diff
+}
+
+impl VendorDep {
+    fn java_entries(&self) -> Vec<Entry> {
+        self.java_dependencies
+            .iter()
+            .map(|x| Entry {
+                group_id: &x.group_id,
+                artifact_id: &x.artifact_id,
+                version: &x.version,
+                platforms: &[],
+            })
+            .collect()
+    }
+
+    fn jni_entries(&self) -> Vec<Entry> {
+        self.jni_dependencies
+            .iter()
+            .map(|x| Entry {
+                group_id: &x.group_id,
+                artifact_id: &x.artifact_id,
+                version: &x.version,
+                platforms: &x.valid_platforms,
+            })
+            .collect()
+    }
+
+    fn cpp_entries(&self) -> Vec<Entry> {
+        self.cpp_dependencies
+            .iter()
+            .map(|x| Entry {
+                group_id: &x.group_id,
+                artifact_id: &x.artifact_id,
+                version: &x.version,
+                platforms: &x.binary_platforms,
+            })
+            .collect()
+    }
+
+    #[doc = "Compare this vendordep with a newer version of it, `other`. Dependencies are matched by `group:artifact`; "]
+    #[doc = "a matched dependency is changed if its version or supported platforms differ."]
+    pub fn diff(&self, other: &VendorDep) -> VendorDepDiff {
+        VendorDepDiff {
+            java: diff_entries(
+                ArtifactKind::Java,
+                &self.java_entries(),
+                &other.java_entries(),
+            ),
+            jni: diff_entries(ArtifactKind::Jni, &self.jni_entries(), &other.jni_entries()),
+            cpp: diff_entries(ArtifactKind::Cpp, &self.cpp_entries(), &other.cpp_entries()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DependencyChange;
+    use crate::{ArtifactKind, Coordinate, VendorDep};
+
+    #[test]
+    fn diff() {
+        let old: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        assert!(old.diff(&old).is_empty());
+        let mut new: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        new.java_dependencies[0].version = "25.0.0".to_string();
+        new.jni_dependencies.remove(1);
+        new.cpp_dependencies[0]
+            .binary_platforms
+            .retain(|x| x != "linuxathena");
+        new.cpp_dependencies[0]
+            .binary_platforms
+            .push("linuxarm64".to_string());
+        new.cpp_dependencies[1].artifact_id = "wpiapi-cpp-sim2".to_string();
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.java.changed,
+            vec![DependencyChange {
+                group_id: "com.ctre.phoenix6".to_string(),
+                artifact_id: "wpiapi-java".to_string(),
+                old_version: "24.3.0".to_string(),
+                new_version: "25.0.0".to_string(),
+                added_platforms: vec![],
+                removed_platforms: vec![],
+            }]
+        );
+        assert!(diff.jni.added.is_empty() && diff.jni.changed.is_empty());
+        assert_eq!(
+            diff.jni.removed,
+            vec![Coordinate {
+                group_id: "com.ctre.phoenix6.sim".to_string(),
+                artifact_id: "tools-sim".to_string(),
+                version: "24.3.0".to_string(),
+                kind: ArtifactKind::Jni,
+            }]
+        );
+        assert_eq!(diff.cpp.changed.len(), 1);
+        assert_eq!(diff.cpp.changed[0].added_platforms, vec!["linuxarm64"]);
+        assert_eq!(diff.cpp.changed[0].removed_platforms, vec!["linuxathena"]);
+        assert_eq!(diff.cpp.added[0].artifact_id, "wpiapi-cpp-sim2");
+        assert_eq!(diff.cpp.removed[0].artifact_id, "wpiapi-cpp-sim");
+    }
+}
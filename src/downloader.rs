@@ -0,0 +1,209 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    BinaryPlatform, Coordinate, CppInfo, DownloadConfig, DownloadReport, Result, VendorDep,
+};
+
+#[doc = "Downloads vendordeps with one [`DownloadConfig`], so options are set once and reused across many vendordeps. "]
+#[doc = "Clones share the throttle, cancellation token, tally and sink of the config, and a [`Downloader`] can be shared between tasks. "]
+#[doc = "The methods are the `*_with_config` methods of [`VendorDep`] with the config filled in; the ones without a config behave like `Downloader::default()`."]
+#[derive(Debug, Clone, Default)]
+pub struct Downloader {
+    config: DownloadConfig,
+}
+
+impl Downloader {
+    #[doc = "Download with `config`."]
+    pub fn new(config: DownloadConfig) -> Self {
+        Self { config }
+    }
+
+    #[doc = "The config downloads use."]
+    pub fn config(&self) -> &DownloadConfig {
+        &self.config
+    }
+
+    #[doc = "See [`VendorDep::download_all_to_folder_with_config`]."]
+    pub async fn download_all<P: AsRef<Path>>(
+        &self,
+        dep: &VendorDep,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+    ) -> Result<DownloadReport> {
+        dep.download_all_to_folder_with_config(
+            p,
+            binary_platform,
+            is_static,
+            is_debug,
+            skip_failed_packages,
+            &self.config,
+        )
+        .await
+    }
+
+    #[doc = "See [`VendorDep::download_all_cpp_deps_to_folder_with_config`]."]
+    pub async fn download_all_cpp<P: AsRef<Path>>(
+        &self,
+        dep: &VendorDep,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+    ) -> Result<CppInfo> {
+        dep.download_all_cpp_deps_to_folder_with_config(
+            p,
+            binary_platform,
+            is_static,
+            is_debug,
+            skip_failed_packages,
+            &self.config,
+        )
+        .await
+    }
+
+    #[doc = "See [`VendorDep::download_all_cpp_deps_by_dependency_with_config`]."]
+    pub async fn download_all_cpp_by_dependency<P: AsRef<Path>>(
+        &self,
+        dep: &VendorDep,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+    ) -> Result<Vec<(Coordinate, CppInfo)>> {
+        dep.download_all_cpp_deps_by_dependency_with_config(
+            p,
+            binary_platform,
+            is_static,
+            is_debug,
+            skip_failed_packages,
+            &self.config,
+        )
+        .await
+    }
+
+    #[doc = "See [`VendorDep::download_all_cpp_deps_for_set`]."]
+    pub async fn download_all_cpp_for_set<P: AsRef<Path>>(
+        &self,
+        deps: &[VendorDep],
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+    ) -> Result<CppInfo> {
+        VendorDep::download_all_cpp_deps_for_set(
+            deps,
+            p,
+            binary_platform,
+            is_static,
+            is_debug,
+            skip_failed_packages,
+            &self.config,
+        )
+        .await
+    }
+
+    #[doc = "See [`VendorDep::download_all_jni_deps_to_folder_with_config`]."]
+    pub async fn download_all_jni<P: AsRef<Path>>(
+        &self,
+        dep: &VendorDep,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_debug: bool,
+        skip_failed_packages: bool,
+    ) -> Result<CppInfo> {
+        dep.download_all_jni_deps_to_folder_with_config(
+            p,
+            binary_platform,
+            is_debug,
+            skip_failed_packages,
+            &self.config,
+        )
+        .await
+    }
+
+    #[doc = "See [`VendorDep::download_all_headers_to_folder_with_config`]."]
+    pub async fn download_all_headers<P: AsRef<Path>>(
+        &self,
+        dep: &VendorDep,
+        p: P,
+        skip_failed_packages: bool,
+    ) -> Result<Vec<PathBuf>> {
+        dep.download_all_headers_to_folder_with_config(p, skip_failed_packages, &self.config)
+            .await
+    }
+
+    #[doc = "See [`VendorDep::download_all_java_deps_to_folder_with_config`]."]
+    pub async fn download_all_java<P: AsRef<Path>>(
+        &self,
+        dep: &VendorDep,
+        p: P,
+        skip_failed_packages: bool,
+    ) -> Result<Vec<PathBuf>> {
+        dep.download_all_java_deps_to_folder_with_config(p, skip_failed_packages, &self.config)
+            .await
+    }
+}
+
+impl From<DownloadConfig> for Downloader {
+    fn from(config: DownloadConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::Downloader;
+    use crate::{
+        test_util::{java_dep, runtime, Mirror, UUID},
+        DownloadConfig, VendorDep,
+    };
+
+    fn assert_shareable<T: Clone + Send + Sync>() {}
+
+    #[test]
+    fn shareable() {
+        assert_shareable::<Downloader>();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reused_across_vendordeps() {
+        let mirror = Mirror::new();
+        let deps = ["first", "second"].map(|name| {
+            VendorDep::builder(name, UUID)
+                .add_maven_url(format!("file://{}", mirror.maven_url))
+                .add_java_dependency(java_dep(&format!("{}-java", name)))
+                .build()
+        });
+        for dep in &deps {
+            mirror.publish(
+                &dep.java_dependencies[0].get_url(&mirror.maven_url),
+                b"PK\x05\x06",
+            );
+        }
+
+        let config = DownloadConfig::default();
+        let downloader = Downloader::new(config.clone());
+        let out = tempdir().unwrap();
+        let runtime = runtime();
+        for dep in &deps {
+            let jars = runtime
+                .block_on(downloader.download_all_java(dep, out.path(), false))
+                .unwrap();
+            assert_eq!(
+                jars,
+                vec![out.path().join(dep.java_dependencies[0].file_name())]
+            );
+        }
+        // The tally is shared with the config the downloader was made from.
+        assert_eq!(config.tally.summary().artifacts_downloaded, 2);
+    }
+}
@@ -1,20 +1,81 @@
 use thiserror::Error;
 
-
 #[derive(Error, Debug)]
 pub enum Error {
+    #[cfg(feature = "download")]
     #[error("Error getting dependency from the internet.")]
     ReqwestError(#[from] reqwest::Error),
+    #[cfg(feature = "download")]
     #[error("Package was not a valid zip.")]
     ZipError(#[from] zip::result::ZipError),
-    #[error("Zipped file has an absolute file location. This is not allowed.")]
-    ZipSecurityError,
+    #[error("Zipped file {entry_name} would be extracted outside of the output directory. This is not allowed.")]
+    ZipSecurityError { entry_name: String },
     #[error("Error reading/writing files.")]
     IoError(#[from] std::io::Error),
-    #[error("Could not find Maven artifact {0}.")]
-    NotFoundError(String),
+    #[error("Could not extract {entry}, the archive is unusable.")]
+    ExtractionError {
+        entry: String,
+        source: std::io::Error,
+    },
+    #[error("Could not find Maven artifact {group_id}:{artifact_id}:{version}.")]
+    NotFoundError {
+        group_id: String,
+        artifact_id: String,
+        version: String,
+        tried_urls: Vec<String>,
+    },
+    #[cfg(feature = "download")]
     #[error("Could not search directory for C++ library objects.")]
     JwalkError(#[from] jwalk::Error),
+    #[error("File {0} already exists.")]
+    FileExistsError(std::path::PathBuf),
+    #[cfg(feature = "maven")]
+    #[error("Could not parse Maven XML.")]
+    XmlError(#[from] roxmltree::Error),
+    #[error("Server responded with HTTP {code} for {url}.")]
+    HttpStatus { code: u16, url: String },
+    #[error("{url} was served with unsupported Content-Encoding {encoding}.")]
+    UnsupportedEncoding { encoding: String, url: String },
+    #[error("{0} is not a Maven coordinate of the form group:artifact:version[:classifier].")]
+    InvalidCoordinate(String),
+    #[error("{0} was downloaded but provides no headers or libraries.")]
+    EmptyDependency(String),
+    #[error("{path} is provided by both {first} and {second}.")]
+    FileCollision {
+        path: std::path::PathBuf,
+        first: String,
+        second: String,
+    },
+    #[error("Downloaded {0} is not a valid archive.")]
+    InvalidArtifact(String),
+    #[error("{url} has SHA-256 {actual}, but the lockfile pins {expected}.")]
+    DigestMismatch {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Lockfile path {0} is not a relative path within the install directory.")]
+    UnsafeLockfilePath(String),
+    #[error("Download was cancelled.")]
+    Cancelled,
+    #[error("{0} is not a platform libraries are published for.")]
+    NotALibraryPlatform(String),
+    #[error("{vendordep} has no C++ dependency {artifact_id}.")]
+    NoSuchDependency {
+        vendordep: String,
+        artifact_id: String,
+    },
+    #[error("{artifact} has no {requested} build for {platform}, only a {available} one.")]
+    LinkageUnavailable {
+        artifact: String,
+        platform: String,
+        requested: String,
+        available: String,
+    },
+    #[error("{0} is not a valid HTTP header.")]
+    InvalidHeader(String),
+    #[error("Could not parse vendordep JSON.")]
+    JsonError(#[from] serde_json::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
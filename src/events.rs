@@ -0,0 +1,282 @@
+use std::path::Path;
+
+use futures::StreamExt;
+
+use crate::{BinaryPlatform, Coordinate, CppInfo, DownloadConfig, Result, VendorDep};
+
+#[doc = "Progress of [`VendorDep::download_all_cpp_deps_stream`]. Dependencies download concurrently, so events of different dependencies interleave; "]
+#[doc = "[`Self::Started`] and the `index` of [`Self::Progress`] are enough to drive one progress bar per dependency."]
+#[derive(Debug, Clone)]
+pub enum DownloadEvent {
+    #[doc = "The download started. Always the first event."]
+    Started {
+        #[doc = "Number of dependencies that will be downloaded, one per entry of [`VendorDep::cpp_dependencies`]."]
+        total_artifacts: usize,
+    },
+    #[doc = "Part of the library archive of a dependency arrived."]
+    Progress {
+        #[doc = "The dependency."]
+        artifact: Coordinate,
+        #[doc = "Position of the dependency in [`VendorDep::cpp_dependencies`]."]
+        index: usize,
+        #[doc = "Number of dependencies being downloaded, as in [`Self::Started`]."]
+        total_artifacts: usize,
+        #[doc = "Bytes of the archive downloaded so far."]
+        bytes_downloaded: u64,
+        #[doc = "Size of the archive, if the server reported it. Unknown for chunked responses, which call for an indeterminate progress bar."]
+        total_bytes: Option<u64>,
+    },
+    #[doc = "An entry of the library archive of a dependency is about to be extracted. Sent for every entry, including ones that end up skipped."]
+    Extracting {
+        #[doc = "The dependency."]
+        artifact: Coordinate,
+        #[doc = "Position of the dependency in [`VendorDep::cpp_dependencies`]."]
+        index: usize,
+        #[doc = "Number of dependencies being downloaded, as in [`Self::Started`]."]
+        total_artifacts: usize,
+        #[doc = "Name of the entry in the archive, such as `include/units/units.h`."]
+        entry: String,
+        #[doc = "Entries of the archive processed before this one."]
+        entries_extracted: usize,
+        #[doc = "Number of entries in the archive, including directories."]
+        total_entries: usize,
+    },
+    #[doc = "Started downloading the headers of a dependency."]
+    ResolvingHeaders {
+        #[doc = "The dependency."]
+        artifact: Coordinate,
+    },
+    #[doc = "Downloaded and extracted the libraries of a dependency."]
+    DownloadedLib {
+        #[doc = "The dependency."]
+        artifact: Coordinate,
+        #[doc = "Size of the downloaded archive."]
+        bytes: u64,
+    },
+    #[doc = "A dependency was not found in any repository and was skipped."]
+    Skipped {
+        #[doc = "The dependency."]
+        artifact: Coordinate,
+    },
+    #[doc = "Every dependency was downloaded. Always the last event of a successful download."]
+    Finished(CppInfo),
+}
+
+pub(crate) type EventSender = futures::channel::mpsc::UnboundedSender<Result<DownloadEvent>>;
+
+#[doc = "Where the events of one dependency go, and its place among the dependencies being downloaded."]
+#[derive(Clone, Copy)]
+pub(crate) struct Events<'a> {
+    pub(crate) sender: &'a EventSender,
+    pub(crate) index: usize,
+    pub(crate) total_artifacts: usize,
+}
+
+pub(crate) fn emit(events: Option<Events>, event: DownloadEvent) {
+    if let Some(events) = events {
+        // The receiver is only gone if the stream was dropped, in which case nobody is listening.
+        _ = events.sender.unbounded_send(Ok(event));
+    }
+}
+
+pub(crate) fn progress(
+    events: Option<Events>,
+    artifact: &Coordinate,
+    bytes_downloaded: u64,
+    total_bytes: Option<u64>,
+) {
+    if let Some(x) = events {
+        emit(
+            events,
+            DownloadEvent::Progress {
+                artifact: artifact.clone(),
+                index: x.index,
+                total_artifacts: x.total_artifacts,
+                bytes_downloaded,
+                total_bytes,
+            },
+        );
+    }
+}
+
+pub(crate) fn extracting(
+    events: Option<Events>,
+    artifact: &Coordinate,
+    entries_extracted: usize,
+    total_entries: usize,
+    entry: &str,
+) {
+    if let Some(x) = events {
+        emit(
+            events,
+            DownloadEvent::Extracting {
+                artifact: artifact.clone(),
+                index: x.index,
+                total_artifacts: x.total_artifacts,
+                entry: entry.to_string(),
+                entries_extracted,
+                total_entries,
+            },
+        );
+    }
+}
+
+impl VendorDep {
+    #[doc = "Like [`Self::download_all_cpp_deps_to_folder_with_config`], but reports progress as a stream of [`DownloadEvent`]s. "]
+    #[doc = "The resulting [`CppInfo`] arrives as [`DownloadEvent::Finished`]; if the download fails, the error is the last item instead. "]
+    #[doc = "Nothing is downloaded until the stream is polled, and dropping it stops the download."]
+    pub fn download_all_cpp_deps_stream<'a, P: AsRef<Path>>(
+        &'a self,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+        config: &'a DownloadConfig,
+    ) -> impl futures::Stream<Item = Result<DownloadEvent>> + 'a {
+        let path = p.as_ref().to_path_buf();
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let work = async move {
+            let claims = crate::sink::FileClaims::default();
+            let total_artifacts = self.cpp_dependencies.len();
+            emit(
+                Some(Events {
+                    sender: &tx,
+                    index: 0,
+                    total_artifacts,
+                }),
+                DownloadEvent::Started { total_artifacts },
+            );
+            let res = futures::future::try_join_all(self.cpp_dependencies.iter().enumerate().map(
+                |(index, dep)| {
+                    self.download_cpp_dep(
+                        dep,
+                        &path,
+                        binary_platform.clone(),
+                        is_static,
+                        is_debug,
+                        skip_failed_packages,
+                        config,
+                        &claims,
+                        Some(Events {
+                            sender: &tx,
+                            index,
+                            total_artifacts,
+                        }),
+                    )
+                },
+            ))
+            .await
+            .map(|parts| {
+                let mut info = CppInfo::new_empty();
+                for part in parts {
+                    info.extend(part);
+                }
+                info.dedup();
+                DownloadEvent::Finished(info)
+            });
+            // Sent through the channel rather than yielded directly so it can't overtake earlier events.
+            _ = tx.unbounded_send(res);
+        };
+        // `rx` ends once `work` has finished and dropped `tx`.
+        futures::stream::select(
+            rx,
+            futures::stream::once(work).filter_map(|()| async { None::<Result<DownloadEvent>> }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::StreamExt;
+    use tempfile::tempdir;
+
+    use super::DownloadEvent;
+    use crate::{
+        test_util::{cpp_dep, platforms, runtime, Mirror},
+        BinaryPlatform, CppDependency, DownloadConfig,
+    };
+
+    #[test]
+    fn cpp_stream() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(CppDependency {
+                binary_platforms: platforms(&["linuxx86-64"]),
+                ..cpp_dep("example-cpp")
+            })
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                ..cpp_dep("missing-cpp")
+            })
+            .build();
+        mirror.publish(
+            &dep.cpp_dependencies[0].get_url(&mirror.maven_url, "linuxx86-64", false, false),
+            include_bytes!("../tests/fixtures/executable.zip"),
+        );
+
+        let out = tempdir().unwrap();
+        let config = DownloadConfig::default();
+        let events = runtime()
+            .block_on(
+                dep.download_all_cpp_deps_stream(
+                    out.path(),
+                    BinaryPlatform::LinuxX86_64,
+                    false,
+                    false,
+                    true,
+                    &config,
+                )
+                .collect::<Vec<_>>(),
+            )
+            .into_iter()
+            .collect::<crate::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(events.len(), 8);
+        assert!(events.iter().any(|x| matches!(
+            x,
+            DownloadEvent::Extracting {
+                entry,
+                entries_extracted: 1,
+                total_entries: 2,
+                ..
+            } if entry == "lib/libexample.so"
+        )));
+        assert!(matches!(
+            events[0],
+            DownloadEvent::Started { total_artifacts: 2 }
+        ));
+        assert!(events.iter().any(|x| matches!(
+            x,
+            DownloadEvent::Progress {
+                artifact,
+                index: 0,
+                total_artifacts: 2,
+                bytes_downloaded,
+                total_bytes: Some(total_bytes),
+            } if artifact.artifact_id == "example-cpp" && bytes_downloaded == total_bytes
+        )));
+        assert!(events.iter().any(|x| matches!(
+            x,
+            DownloadEvent::DownloadedLib { artifact, bytes }
+                if artifact.artifact_id == "example-cpp"
+                    && *bytes == include_bytes!("../tests/fixtures/executable.zip").len() as u64
+        )));
+        assert!(events.iter().any(|x| matches!(
+            x,
+            DownloadEvent::ResolvingHeaders { artifact } if artifact.artifact_id == "missing-cpp"
+        )));
+        assert!(events.iter().any(|x| matches!(
+            x,
+            DownloadEvent::Skipped { artifact } if artifact.artifact_id == "missing-cpp"
+        )));
+        let Some(DownloadEvent::Finished(info)) = events.last() else {
+            panic!(
+                "expected the last event to be Finished, got {:?}",
+                events.last()
+            );
+        };
+        assert_eq!(info.libraries, vec!["example"]);
+    }
+}
@@ -0,0 +1,875 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Read, Seek},
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{error, ArtifactStore, CancellationToken, Result};
+
+#[doc = "What to do when a file being extracted already exists."]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExtractPolicy {
+    #[doc = "Replace the existing file."]
+    #[default]
+    Overwrite,
+    #[doc = "Keep the existing file and don't extract the entry."]
+    SkipExisting,
+    #[doc = "Fail with [`error::Error::FileExistsError`]."]
+    ErrorOnConflict,
+    #[doc = "Keep the existing file if its CRC-32 matches the one the archive records for the entry, and replace it otherwise. "]
+    #[doc = "Unchanged files keep their modification time, so re-extracting doesn't trigger rebuilds. Needs a sink that implements [`crate::ArtifactSink::crc32`]; other sinks always replace the file."]
+    SkipIdentical,
+    #[doc = "Like [`Self::Overwrite`], but replace read-only files too, such as those of a checked-out vendored tree, by removing the existing file first. "]
+    #[doc = "Needs a sink that implements [`crate::ArtifactSink::remove`]."]
+    OverwriteReadOnly,
+}
+
+#[doc = "Options controlling zip extraction."]
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    #[doc = "What to do when a file being extracted already exists."]
+    pub policy: ExtractPolicy,
+    #[doc = "Number of leading path components to remove from each entry, like `tar --strip-components`. Entries with no components left are skipped."]
+    pub strip_components: usize,
+    #[doc = "Glob patterns such as `**/include/**`; if any are given, only entries matching one of them are extracted. "]
+    #[doc = "Matched against the `/`-separated path the entry is extracted to, after [`Self::strip_components`]. "]
+    #[doc = "`*` and `?` match any characters and any one character within a path component, and `**` matches any number of whole components."]
+    pub include: Vec<String>,
+    #[doc = "Glob patterns, as in [`Self::include`], for entries not to extract even if they are included."]
+    pub exclude: Vec<String>,
+}
+
+impl ExtractOptions {
+    #[doc = "Whether an entry extracted to `relative` passes [`Self::include`] and [`Self::exclude`]."]
+    fn wants(&self, relative: &Path) -> bool {
+        if self.include.is_empty() && self.exclude.is_empty() {
+            return true;
+        }
+        let path = relative
+            .components()
+            .map(|x| x.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        (self.include.is_empty() || self.include.iter().any(|x| glob_matches(x, &path)))
+            && !self.exclude.iter().any(|x| glob_matches(x, &path))
+    }
+}
+
+#[doc = "Called before each entry is extracted with the number of entries already processed, the number of entries in the archive, and the entry's name."]
+pub(crate) type ExtractProgressFn<'a> = &'a (dyn Fn(usize, usize, &str) + Send + Sync);
+
+#[doc = "Extract every file in `archive` into `out_dir` through `sink`, returning the paths written. Entries that would land outside of `out_dir` fail with [`error::Error::ZipSecurityError`]. "]
+#[doc = "Permissions stored in the archive (such as the executable bit) are passed on to the sink. Symlink entries are recreated as symlinks after the regular files if their target stays within `out_dir`, "]
+#[doc = "and skipped with a warning otherwise; see [`put_symlinks`]. `cancel` is checked before each entry. Names that aren't valid UTF-8 despite the archive saying so are decoded lossily; "]
+#[doc = "if two differently named entries end up at the same path that way, extraction fails with [`error::Error::FileCollision`] rather than one silently replacing the other."]
+pub(crate) fn extract_zip<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    out_dir: &Path,
+    options: &ExtractOptions,
+    cancel: &CancellationToken,
+    sink: &ArtifactStore,
+) -> Result<Vec<PathBuf>> {
+    extract_zip_with_progress(archive, out_dir, options, cancel, sink, None)
+}
+
+#[doc = "Like [`extract_zip`], reporting each entry to `progress`."]
+pub(crate) fn extract_zip_with_progress<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    out_dir: &Path,
+    options: &ExtractOptions,
+    cancel: &CancellationToken,
+    sink: &ArtifactStore,
+    progress: Option<ExtractProgressFn<'_>>,
+) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    let mut links = Vec::new();
+    // Raw name of the entry extracted to each path, to tell repeated entries from distinct names decoded to the same one.
+    let mut raw_names: HashMap<PathBuf, Vec<u8>> = HashMap::new();
+    let total = archive.len();
+    for i in 0..total {
+        cancel.check()?;
+        let mut f = archive.by_index(i)?;
+        if let Some(progress) = progress {
+            progress(i, total, f.name());
+        }
+        if f.name().ends_with('/') {
+            continue;
+        }
+        if std::str::from_utf8(f.name_raw()).is_err() {
+            log_debug!(
+                "Entry {} doesn't have a UTF-8 name, extracting it as {}",
+                f.name_raw().escape_ascii(),
+                f.name()
+            );
+        }
+        // The zip crate refuses names that are absolute, escape the archive or hold a NUL byte.
+        let enclosed = f
+            .enclosed_name()
+            .ok_or_else(|| error::Error::ZipSecurityError {
+                entry_name: f.name().to_string(),
+            })?;
+        let Some(relative) = entry_path(f.name(), &enclosed, options)? else {
+            continue;
+        };
+        let outpath = out_dir.join(&relative);
+        match raw_names.get(&relative) {
+            Some(first) if first.as_slice() != f.name_raw() => {
+                return Err(error::Error::FileCollision {
+                    path: outpath,
+                    first: first.escape_ascii().to_string(),
+                    second: f.name_raw().escape_ascii().to_string(),
+                });
+            }
+            Some(_) => {}
+            None => {
+                raw_names.insert(relative.clone(), f.name_raw().to_vec());
+            }
+        }
+        let crc32 = f.crc32();
+        if keep_existing(&outpath, options, sink, || Ok(crc32))? {
+            continue;
+        }
+        let mode = f.unix_mode();
+        if mode.is_some_and(|x| x & S_IFMT == S_IFLNK) {
+            let mut target = String::new();
+            f.read_to_string(&mut target)?;
+            if !symlink_stays_within(&relative, Path::new(&target)) {
+                log_warn!(
+                    "Skipping symlink {} to {}, which points outside of {}",
+                    outpath.display(),
+                    target,
+                    out_dir.display()
+                );
+                continue;
+            }
+            links.push((relative, PathBuf::from(target)));
+        } else {
+            sink.put(&outpath, &mut f, mode)
+                .map_err(|e| incomplete(f.name(), e))?;
+            written.push(outpath);
+        }
+    }
+    put_symlinks(links, out_dir, sink, &mut written)?;
+    Ok(written)
+}
+
+#[doc = "Extract every file in the gzip-compressed tar archive `archive` into `out_dir` through `sink`, like [`extract_zip_with_progress`] does for zips: "]
+#[doc = "with the same [`ExtractOptions`], the same check against entries landing outside of `out_dir`, and the same handling of permissions and symlinks. "]
+#[doc = "Hard links and special files are skipped. The archive is read twice, first to count its entries for `progress`."]
+pub(crate) fn extract_tar_gz_with_progress<R: Read + Seek>(
+    archive: &mut R,
+    out_dir: &Path,
+    options: &ExtractOptions,
+    cancel: &CancellationToken,
+    sink: &ArtifactStore,
+    progress: Option<ExtractProgressFn<'_>>,
+) -> Result<Vec<PathBuf>> {
+    let start = archive.stream_position()?;
+    let total = match progress {
+        Some(_) => tar::Archive::new(flate2::read::GzDecoder::new(&mut *archive))
+            .entries()?
+            .count(),
+        None => 0,
+    };
+    archive.seek(std::io::SeekFrom::Start(start))?;
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    let mut written = Vec::new();
+    let mut links = Vec::new();
+    for (i, entry) in tar.entries()?.enumerate() {
+        cancel.check()?;
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let name = path.to_string_lossy().into_owned();
+        if let Some(progress) = progress {
+            progress(i, total, &name);
+        }
+        let kind = entry.header().entry_type();
+        if !kind.is_file() && !kind.is_symlink() {
+            if !kind.is_dir() {
+                log_warn!("Skipping {}, which is not a file or a symlink", name);
+            }
+            continue;
+        }
+        let Some(relative) = entry_path(&name, &path, options)? else {
+            continue;
+        };
+        let outpath = out_dir.join(&relative);
+        // Tar archives don't record checksums, so only read the entry for one if it's needed.
+        let mut contents = None;
+        if keep_existing(&outpath, options, sink, || {
+            let mut buf = Vec::new();
+            entry.read_to_end(&mut buf)?;
+            let crc32 = crc32fast::hash(&buf);
+            contents = Some(buf);
+            Ok(crc32)
+        })? {
+            continue;
+        }
+        if kind.is_symlink() {
+            let Some(target) = entry.link_name()? else {
+                continue;
+            };
+            if !symlink_stays_within(&relative, &target) {
+                log_warn!(
+                    "Skipping symlink {} to {}, which points outside of {}",
+                    outpath.display(),
+                    target.display(),
+                    out_dir.display()
+                );
+                continue;
+            }
+            links.push((relative, target.into_owned()));
+        } else {
+            let mode = entry.header().mode().ok();
+            match contents {
+                Some(buf) => sink.put(&outpath, &mut buf.as_slice(), mode),
+                None => sink.put(&outpath, &mut entry, mode),
+            }
+            .map_err(|e| incomplete(&name, e))?;
+            written.push(outpath);
+        }
+    }
+    put_symlinks(links, out_dir, sink, &mut written)?;
+    Ok(written)
+}
+
+#[doc = "Create the symlink entries `links`, each a path relative to `out_dir` and its target, once the regular files in `written` are extracted, adding them to `written`. "]
+#[doc = "[`symlink_stays_within`] only looks at one link, so a link is also skipped with a warning if it would be created inside another of `links` or in place of a directory of `written`, "]
+#[doc = "or if its target goes through another of `links`: otherwise chains such as `a -> .` and `e -> a/..` could lead outside of `out_dir`, and so could files written through them."]
+fn put_symlinks(
+    links: Vec<(PathBuf, PathBuf)>,
+    out_dir: &Path,
+    sink: &ArtifactStore,
+    written: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let paths = links
+        .iter()
+        .map(|(x, _)| x.as_path())
+        .collect::<HashSet<_>>();
+    let files = written.len();
+    for (relative, target) in &links {
+        let outpath = out_dir.join(relative);
+        if relative.ancestors().skip(1).any(|x| paths.contains(x))
+            || written[..files]
+                .iter()
+                .any(|x| x != &outpath && x.starts_with(&outpath))
+            || through_symlink(relative, target, &paths)
+        {
+            log_warn!(
+                "Skipping symlink {} to {}, which goes through another symlink",
+                outpath.display(),
+                target.display()
+            );
+            continue;
+        }
+        sink.put_symlink(&outpath, target)?;
+        written.push(outpath);
+    }
+    Ok(())
+}
+
+// Whether following `target` from the symlink at `link` passes through one of `links` before its last component, going by the paths alone like `symlink_stays_within`.
+fn through_symlink(link: &Path, target: &Path, links: &HashSet<&Path>) -> bool {
+    let mut path = link.parent().map(Path::to_path_buf).unwrap_or_default();
+    let mut components = target.components().peekable();
+    while let Some(component) = components.next() {
+        match component {
+            Component::Normal(x) => path.push(x),
+            Component::ParentDir => {
+                path.pop();
+            }
+            _ => {}
+        }
+        if components.peek().is_some() && links.contains(path.as_path()) {
+            return true;
+        }
+    }
+    false
+}
+
+// Report an I/O failure while writing the entry `name` as [`error::Error::ExtractionError`], since the sink didn't keep the partial file.
+fn incomplete(name: &str, e: error::Error) -> error::Error {
+    match e {
+        error::Error::IoError(source) => error::Error::ExtractionError {
+            entry: name.to_string(),
+            source,
+        },
+        e => e,
+    }
+}
+
+#[doc = "Where the entry `name`, stored at `path`, is extracted to relative to the output directory, after [`ExtractOptions::strip_components`]. "]
+#[doc = "`None` if it is left out, and [`error::Error::ZipSecurityError`] if it would land outside of the output directory."]
+fn entry_path(name: &str, path: &Path, options: &ExtractOptions) -> Result<Option<PathBuf>> {
+    let relative: PathBuf = path
+        .components()
+        .filter(|x| *x != Component::CurDir)
+        .skip(options.strip_components)
+        .collect();
+    if relative.as_os_str().is_empty() {
+        return Ok(None);
+    }
+    if relative
+        .components()
+        .any(|x| !matches!(x, Component::Normal(_)))
+    {
+        return Err(error::Error::ZipSecurityError {
+            entry_name: name.to_string(),
+        });
+    }
+    Ok(options.wants(&relative).then_some(relative))
+}
+
+#[doc = "Whether to keep the file already at `outpath` instead of extracting an entry over it, following [`ExtractOptions::policy`]. "]
+#[doc = "`crc32` gives the CRC-32 of the entry, and is only called for [`ExtractPolicy::SkipIdentical`]."]
+fn keep_existing(
+    outpath: &Path,
+    options: &ExtractOptions,
+    sink: &ArtifactStore,
+    crc32: impl FnOnce() -> Result<u32>,
+) -> Result<bool> {
+    if !sink.exists(outpath) {
+        return Ok(false);
+    }
+    match options.policy {
+        ExtractPolicy::Overwrite => Ok(false),
+        ExtractPolicy::SkipExisting => Ok(true),
+        ExtractPolicy::ErrorOnConflict => Err(error::Error::FileExistsError(outpath.to_path_buf())),
+        ExtractPolicy::SkipIdentical => {
+            // Don't read the entry if there's nothing to compare it to.
+            let Some(existing) = sink.crc32(outpath)? else {
+                return Ok(false);
+            };
+            Ok(existing == crc32()?)
+        }
+        ExtractPolicy::OverwriteReadOnly => {
+            sink.remove(outpath)?;
+            Ok(false)
+        }
+    }
+}
+
+#[doc = "Whether the `/`-separated `path` matches the glob `pattern`."]
+fn glob_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern
+        .split('/')
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<_>>();
+    let path = path.split('/').collect::<Vec<_>>();
+    components_match(&pattern, &path)
+}
+
+fn components_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| components_match(rest, &path[i..])),
+        Some((first, rest)) => path.split_first().is_some_and(|(name, path)| {
+            component_matches(first, name) && components_match(rest, path)
+        }),
+    }
+}
+
+fn component_matches(pattern: &str, name: &str) -> bool {
+    let mut chars = pattern.chars();
+    match chars.next() {
+        None => name.is_empty(),
+        Some('*') => name
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([name.len()])
+            .any(|i| component_matches(chars.as_str(), &name[i..])),
+        Some('?') => {
+            let mut rest = name.chars();
+            rest.next().is_some() && component_matches(chars.as_str(), rest.as_str())
+        }
+        Some(c) => name
+            .strip_prefix(c)
+            .is_some_and(|rest| component_matches(chars.as_str(), rest)),
+    }
+}
+
+// File type bits of a unix mode, and the type of a symlink.
+const S_IFMT: u32 = 0o170000;
+const S_IFLNK: u32 = 0o120000;
+
+#[doc = "Whether a symlink at `link` (relative to the extraction root) pointing to `target` resolves to somewhere inside the root. Absolute targets never do."]
+fn symlink_stays_within(link: &Path, target: &Path) -> bool {
+    let mut depth = link.components().count() - 1;
+    for component in target.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => depth -= 1,
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use std::{io::Cursor, path::Path};
+
+    use tempfile::tempdir;
+
+    use super::{
+        extract_tar_gz_with_progress, extract_zip, glob_matches, symlink_stays_within,
+        ExtractOptions, ExtractPolicy,
+    };
+    use crate::{error::Error, ArtifactStore, CancellationToken};
+
+    fn fixture(bytes: &'static [u8]) -> zip::ZipArchive<Cursor<&'static [u8]>> {
+        zip::ZipArchive::new(Cursor::new(bytes)).unwrap()
+    }
+
+    #[test]
+    fn extracts_files() {
+        let out = tempdir().unwrap();
+        let mut archive = fixture(include_bytes!("../tests/fixtures/headers.zip"));
+        let mut written = extract_zip(
+            &mut archive,
+            out.path(),
+            &ExtractOptions::default(),
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        )
+        .unwrap();
+        written.sort();
+        assert_eq!(
+            written,
+            vec![
+                out.path().join("frc/Example.h"),
+                out.path().join("frc/detail/Impl.h"),
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(out.path().join("frc/Example.h")).unwrap(),
+            "#pragma once\n"
+        );
+    }
+
+    #[test]
+    fn rejects_zip_slip() {
+        let root = tempdir().unwrap();
+        let out = root.path().join("out");
+        let mut archive = fixture(include_bytes!("../tests/fixtures/zip-slip.zip"));
+        let res = extract_zip(
+            &mut archive,
+            &out,
+            &ExtractOptions::default(),
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        );
+        assert!(matches!(
+            res,
+            Err(Error::ZipSecurityError { entry_name }) if entry_name == "../escape.txt"
+        ));
+        assert!(!root.path().join("escape.txt").exists());
+    }
+
+    #[test]
+    fn corrupt_entries() {
+        let out = tempdir().unwrap();
+        let mut archive = fixture(include_bytes!("../tests/fixtures/corrupt.zip"));
+        let res = extract_zip(
+            &mut archive,
+            out.path(),
+            &ExtractOptions::default(),
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        );
+        assert!(matches!(
+            res,
+            Err(Error::ExtractionError { entry, .. }) if entry == "lib/libexample.so"
+        ));
+        assert!(!out.path().join("lib/libexample.so").exists());
+    }
+
+    #[test]
+    fn rejects_lossy_name_collisions() {
+        // Two entries flagged as UTF-8 whose names aren't, and which both decode to `include/caf\u{FFFD}.h`.
+        let out = tempdir().unwrap();
+        let mut archive = fixture(include_bytes!("../tests/fixtures/lossy-names.zip"));
+        let res = extract_zip(
+            &mut archive,
+            out.path(),
+            &ExtractOptions::default(),
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        );
+        assert!(matches!(
+            res,
+            Err(Error::FileCollision { path, first, second })
+                if path == out.path().join("include/caf\u{FFFD}.h")
+                    && first == "include/caf\\xe9.h"
+                    && second == "include/caf\\xe8.h"
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn applies_unix_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let out = tempdir().unwrap();
+        let mut archive = fixture(include_bytes!("../tests/fixtures/executable.zip"));
+        extract_zip(
+            &mut archive,
+            out.path(),
+            &ExtractOptions::default(),
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        )
+        .unwrap();
+        let mode = |p: &str| {
+            std::fs::metadata(out.path().join(p))
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o777
+        };
+        assert_eq!(mode("bin/halsim-tool"), 0o755);
+        assert_eq!(mode("lib/libexample.so"), 0o644);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn extracts_tar_gz() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let out = tempdir().unwrap();
+        let entries = std::sync::Mutex::new(Vec::new());
+        let progress: super::ExtractProgressFn =
+            &|i, total, _| entries.lock().unwrap().push((i, total));
+        let mut written = extract_tar_gz_with_progress(
+            &mut Cursor::new(&include_bytes!("../tests/fixtures/bundled.tar.gz")[..]),
+            out.path(),
+            &ExtractOptions {
+                strip_components: 1,
+                ..Default::default()
+            },
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+            Some(progress),
+        )
+        .unwrap();
+        written.sort();
+        assert_eq!(
+            written,
+            vec![
+                out.path().join("example/Example.h"),
+                out.path().join("x86-64/shared/libexample.so"),
+                out.path().join("x86-64/shared/libexample.so.1"),
+            ]
+        );
+        assert_eq!(entries.into_inner().unwrap().last(), Some(&(3, 4)));
+        let lib = out.path().join("x86-64/shared/libexample.so");
+        assert_eq!(
+            std::fs::metadata(&lib).unwrap().permissions().mode() & 0o777,
+            0o755
+        );
+        assert_eq!(
+            std::fs::read_link(out.path().join("x86-64/shared/libexample.so.1")).unwrap(),
+            Path::new("libexample.so")
+        );
+    }
+
+    #[test]
+    fn rejects_tar_slip() {
+        let root = tempdir().unwrap();
+        let out = root.path().join("out");
+        let res = extract_tar_gz_with_progress(
+            &mut Cursor::new(&include_bytes!("../tests/fixtures/tar-slip.tar.gz")[..]),
+            &out,
+            &ExtractOptions::default(),
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+            None,
+        );
+        assert!(matches!(
+            res,
+            Err(Error::ZipSecurityError { entry_name }) if entry_name == "../escape.txt"
+        ));
+        assert!(!root.path().join("escape.txt").exists());
+    }
+
+    #[test]
+    fn symlink_targets() {
+        for (link, target, within) in [
+            ("lib/libexample.so", "libexample.so.1", true),
+            ("lib/libexample.so", "./libexample.so.1", true),
+            ("lib/linux/libexample.so", "../libexample.so.1", true),
+            ("lib/libexample.so", "../include/example.h", true),
+            ("lib/escape", "../../outside", false),
+            ("escape", "..", false),
+            ("lib/absolute", "/etc/passwd", false),
+        ] {
+            assert_eq!(
+                symlink_stays_within(Path::new(link), Path::new(target)),
+                within,
+                "{} -> {}",
+                link,
+                target
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn extracts_symlinks() {
+        let root = tempdir().unwrap();
+        let out = root.path().join("out");
+        let mut archive = fixture(include_bytes!("../tests/fixtures/symlinks.zip"));
+        let mut written = extract_zip(
+            &mut archive,
+            &out,
+            &ExtractOptions::default(),
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        )
+        .unwrap();
+        written.sort();
+        assert_eq!(
+            written,
+            vec![
+                out.join("lib/libexample.so"),
+                out.join("lib/libexample.so.1")
+            ]
+        );
+        assert_eq!(
+            std::fs::read_link(out.join("lib/libexample.so")).unwrap(),
+            Path::new("libexample.so.1")
+        );
+        assert_eq!(
+            std::fs::read(out.join("lib/libexample.so")).unwrap(),
+            b"\x7fELF"
+        );
+        assert!(std::fs::symlink_metadata(out.join("lib/escape")).is_err());
+        assert!(std::fs::symlink_metadata(out.join("lib/absolute")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlink_chains() {
+        // `a -> .` stays within, but `e -> a/..` would then lead out of it, and `e/pwned` with it.
+        for tar in [false, true] {
+            let root = tempdir().unwrap();
+            let out = root.path().join("out");
+            let mut written = if tar {
+                extract_tar_gz_with_progress(
+                    &mut Cursor::new(&include_bytes!("../tests/fixtures/symlink-chain.tar.gz")[..]),
+                    &out,
+                    &ExtractOptions::default(),
+                    &CancellationToken::new(),
+                    &ArtifactStore::default(),
+                    None,
+                )
+            } else {
+                extract_zip(
+                    &mut fixture(include_bytes!("../tests/fixtures/symlink-chain.zip")),
+                    &out,
+                    &ExtractOptions::default(),
+                    &CancellationToken::new(),
+                    &ArtifactStore::default(),
+                )
+            }
+            .unwrap();
+            written.sort();
+            assert_eq!(written, vec![out.join("a"), out.join("e/pwned")]);
+            assert_eq!(std::fs::read_link(out.join("a")).unwrap(), Path::new("."));
+            assert!(std::fs::symlink_metadata(out.join("e")).unwrap().is_dir());
+            assert_eq!(std::fs::read(out.join("e/pwned")).unwrap(), b"pwned");
+            assert!(!root.path().join("pwned").exists());
+        }
+    }
+
+    #[test]
+    fn extract_policies() {
+        let out = tempdir().unwrap();
+        let existing = out.path().join("frc/Example.h");
+        std::fs::create_dir_all(existing.parent().unwrap()).unwrap();
+        let options = |policy| ExtractOptions {
+            policy,
+            ..Default::default()
+        };
+
+        std::fs::write(&existing, "existing").unwrap();
+        let mut archive = fixture(include_bytes!("../tests/fixtures/headers.zip"));
+        let res = extract_zip(
+            &mut archive,
+            out.path(),
+            &options(ExtractPolicy::ErrorOnConflict),
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        );
+        assert!(matches!(res, Err(Error::FileExistsError(p)) if p == existing));
+
+        let written = extract_zip(
+            &mut archive,
+            out.path(),
+            &options(ExtractPolicy::SkipExisting),
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        )
+        .unwrap();
+        assert_eq!(written, vec![out.path().join("frc/detail/Impl.h")]);
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "existing");
+
+        extract_zip(
+            &mut archive,
+            out.path(),
+            &options(ExtractPolicy::Overwrite),
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&existing).unwrap(),
+            "#pragma once\n"
+        );
+
+        // Only the changed file is rewritten.
+        std::fs::write(out.path().join("frc/detail/Impl.h"), "changed").unwrap();
+        let written = extract_zip(
+            &mut archive,
+            out.path(),
+            &options(ExtractPolicy::SkipIdentical),
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        )
+        .unwrap();
+        assert_eq!(written, vec![out.path().join("frc/detail/Impl.h")]);
+        assert_ne!(
+            std::fs::read_to_string(out.path().join("frc/detail/Impl.h")).unwrap(),
+            "changed"
+        );
+
+        std::fs::write(&existing, "read-only").unwrap();
+        let mut permissions = std::fs::metadata(&existing).unwrap().permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&existing, permissions).unwrap();
+        extract_zip(
+            &mut archive,
+            out.path(),
+            &options(ExtractPolicy::OverwriteReadOnly),
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&existing).unwrap(),
+            "#pragma once\n"
+        );
+    }
+
+    #[test]
+    fn strip_components() {
+        let out = tempdir().unwrap();
+        let mut archive = fixture(include_bytes!("../tests/fixtures/wrapped.zip"));
+        let mut written = extract_zip(
+            &mut archive,
+            out.path(),
+            &ExtractOptions {
+                strip_components: 1,
+                ..Default::default()
+            },
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        )
+        .unwrap();
+        written.sort();
+        assert_eq!(
+            written,
+            vec![
+                out.path().join("README.txt"),
+                out.path().join("include/frc/Example.h"),
+            ]
+        );
+
+        let out = tempdir().unwrap();
+        let written = extract_zip(
+            &mut archive,
+            out.path(),
+            &ExtractOptions {
+                strip_components: 3,
+                ..Default::default()
+            },
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        )
+        .unwrap();
+        assert_eq!(written, vec![out.path().join("Example.h")]);
+    }
+
+    #[test]
+    fn globs() {
+        for (pattern, path, matches) in [
+            ("**/include/**", "include/frc/Example.h", true),
+            ("**/include/**", "pkg/include/frc/Example.h", true),
+            ("**/include/**", "pkg/docs/include.html", false),
+            ("*.txt", "README.txt", true),
+            ("*.txt", "docs/README.txt", false),
+            ("**/*.txt", "docs/README.txt", true),
+            ("lib?.so", "liba.so", true),
+            ("lib?.so", "lib.so", false),
+            ("frc/*", "frc/Example.h", true),
+            ("frc/*", "frc/detail/Impl.h", false),
+        ] {
+            assert_eq!(glob_matches(pattern, path), matches, "{} {}", pattern, path);
+        }
+    }
+
+    #[test]
+    fn filters_entries() {
+        let out = tempdir().unwrap();
+        let mut archive = fixture(include_bytes!("../tests/fixtures/wrapped.zip"));
+        let written = extract_zip(
+            &mut archive,
+            out.path(),
+            &ExtractOptions {
+                include: vec!["**/include/**".to_string()],
+                ..Default::default()
+            },
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            written,
+            vec![out.path().join("example-1.0/include/frc/Example.h")]
+        );
+        assert!(!out.path().join("example-1.0/README.txt").exists());
+
+        let out = tempdir().unwrap();
+        let written = extract_zip(
+            &mut archive,
+            out.path(),
+            &ExtractOptions {
+                strip_components: 1,
+                exclude: vec!["include/**".to_string()],
+                ..Default::default()
+            },
+            &CancellationToken::new(),
+            &ArtifactStore::default(),
+        )
+        .unwrap();
+        assert_eq!(written, vec![out.path().join("README.txt")]);
+    }
+
+    #[test]
+    fn cancelled() {
+        let out = tempdir().unwrap();
+        let mut archive = fixture(include_bytes!("../tests/fixtures/headers.zip"));
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let res = extract_zip(
+            &mut archive,
+            out.path(),
+            &ExtractOptions::default(),
+            &cancel,
+            &ArtifactStore::default(),
+        );
+        assert!(matches!(res, Err(Error::Cancelled)));
+        assert!(!out.path().join("frc").exists());
+    }
+}
@@ -0,0 +1,1035 @@
+use std::{
+    borrow::Cow,
+    io::{Seek, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{DownloadConfig, Result};
+
+#[doc = "Build the HTTP client used for downloads, sending the [`DownloadConfig::user_agent`] and [`DownloadConfig::headers`] of `config`. Uses rustls when the `rustls` feature is enabled."]
+// Not cached in a static: a client's pooled connections belong to the runtime that opened them.
+pub(crate) fn client(config: &DownloadConfig) -> Result<reqwest::Client> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (name, value) in &config.headers {
+        let invalid = || crate::error::Error::InvalidHeader(format!("{}: {}", name, value));
+        headers.append(
+            reqwest::header::HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?,
+            reqwest::header::HeaderValue::from_str(value).map_err(|_| invalid())?,
+        );
+    }
+    let builder = reqwest::Client::builder()
+        .user_agent(
+            config
+                .user_agent
+                .as_deref()
+                .unwrap_or(concat!("vendordeps/", env!("CARGO_PKG_VERSION"))),
+        )
+        .default_headers(headers)
+        .redirect(match config.max_redirects {
+            None => reqwest::redirect::Policy::default(),
+            Some(0) => reqwest::redirect::Policy::none(),
+            Some(max) => reqwest::redirect::Policy::limited(max),
+        });
+    #[cfg(feature = "rustls")]
+    let builder = builder.use_rustls_tls();
+    Ok(builder.build()?)
+}
+
+// Log where `url` ended up if the server redirected it, since a chain of redirects is otherwise invisible.
+fn log_redirect(url: &str, res: &reqwest::Response) {
+    if res.url().as_str() != url {
+        log_info!("{} was redirected to {}", url, res.url());
+    }
+}
+
+// The URL to request for `url`, after `config.url_rewriter`.
+fn rewrite<'a>(url: &'a str, config: &DownloadConfig) -> Cow<'a, str> {
+    match &config.url_rewriter {
+        Some(rewriter) => Cow::Owned(rewriter.rewrite(url)),
+        None => Cow::Borrowed(url),
+    }
+}
+
+// The copy of `url` in `config.cache`, unless `config.force` asks for a fresh one.
+fn cached_copy(url: &str, config: &DownloadConfig) -> Option<std::fs::File> {
+    match &config.cache {
+        Some(cache) if !config.force => cache.get(url),
+        _ => None,
+    }
+}
+
+// Like `cached_copy`, read into memory.
+fn cached_bytes(url: &str, config: &DownloadConfig) -> Result<Option<Vec<u8>>> {
+    let Some(mut file) = cached_copy(url, config) else {
+        return Ok(None);
+    };
+    let mut bytes = Vec::new();
+    std::io::Read::read_to_end(&mut file, &mut bytes)?;
+    Ok(Some(bytes))
+}
+
+// Store what was downloaded from `url` in `config.cache`. The cache only saves downloads, so failing to store it isn't worth failing the download over.
+fn store_in_cache(url: &str, contents: &mut dyn std::io::Read, config: &DownloadConfig) {
+    if let Some(cache) = &config.cache {
+        if let Err(e) = cache.put(url, contents) {
+            log_warn!("Failed to cache {}: {}", url, e);
+        }
+    }
+}
+
+#[doc = "Resolve a Maven URL to a path on the local filesystem if it refers to one. Accepts `file://` URLs and absolute paths."]
+pub(crate) fn local_path(url: &str) -> Option<PathBuf> {
+    if url.starts_with("file:") {
+        return reqwest::Url::parse(url).ok()?.to_file_path().ok();
+    }
+    let path = Path::new(url);
+    if path.is_absolute() {
+        Some(path.to_path_buf())
+    } else {
+        None
+    }
+}
+
+#[doc = "Turn a non-2xx response into [`crate::error::Error::HttpStatus`], since reqwest happily returns the body of an error page."]
+pub(crate) fn check_status(res: reqwest::Response) -> Result<reqwest::Response> {
+    let status = res.status();
+    if status.is_success() {
+        Ok(res)
+    } else {
+        Err(crate::error::Error::HttpStatus {
+            code: status.as_u16(),
+            url: res.url().to_string(),
+        })
+    }
+}
+
+#[doc = "Fail with [`crate::error::Error::UnsupportedEncoding`] if the body of `res` is still encoded. The client decodes gzip and deflate and drops their `Content-Encoding` header, "]
+#[doc = "so one that is left over means the body isn't the artifact itself and shouldn't be written out as such."]
+pub(crate) fn check_encoding(res: reqwest::Response) -> Result<reqwest::Response> {
+    match res.headers().get(reqwest::header::CONTENT_ENCODING) {
+        Some(encoding) if encoding != "identity" => Err(crate::error::Error::UnsupportedEncoding {
+            encoding: String::from_utf8_lossy(encoding.as_bytes()).into_owned(),
+            url: res.url().to_string(),
+        }),
+        _ => Ok(res),
+    }
+}
+
+#[doc = "Fetch the contents of `url`, reading from disk for local repositories, from [`DownloadConfig::cache`] if it has a copy, and over HTTP otherwise."]
+pub(crate) async fn fetch_bytes(url: &str, config: &DownloadConfig) -> Result<Vec<u8>> {
+    let url: &str = &rewrite(url, config);
+    if let Some(path) = local_path(url) {
+        return Ok(std::fs::read(path)?);
+    }
+    if let Some(bytes) = cached_bytes(url, config)? {
+        return Ok(bytes);
+    }
+    let _permit = config.throttle.acquire(url).await;
+    let res = client(config)?.get(url).send().await?;
+    log_redirect(url, &res);
+    let bytes = check_encoding(check_status(res)?)?.bytes().await?.to_vec();
+    store_in_cache(url, &mut bytes.as_slice(), config);
+    Ok(bytes)
+}
+
+#[doc = "Like [`fetch_bytes`], bypassing [`DownloadConfig::cache`], for files such as vendordep JSON that change under the same URL."]
+pub(crate) async fn fetch_bytes_uncached(url: &str, config: &DownloadConfig) -> Result<Vec<u8>> {
+    let config = DownloadConfig {
+        cache: None,
+        ..config.clone()
+    };
+    fetch_bytes(url, &config).await
+}
+
+#[doc = "Fetch an archive into a file positioned at its start, so it can be read without holding it in memory. "]
+#[doc = "Local repositories are opened in place; anything else is streamed into an anonymous temporary file that is deleted once closed."]
+pub(crate) async fn fetch_archive(url: &str, config: &DownloadConfig) -> Result<std::fs::File> {
+    match fetch_archive_if_modified(url, None, config, None).await? {
+        Some((file, _)) => Ok(file),
+        // Only a misbehaving server answers 304 without conditional headers.
+        None => Err(crate::error::Error::HttpStatus {
+            code: 304,
+            url: url.to_string(),
+        }),
+    }
+}
+
+#[doc = "`ETag` and `Last-Modified` headers of a downloaded artifact, stored next to it in a `.meta` file when [`DownloadConfig::conditional`] is set."]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Validators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_response(res: &reqwest::Response) -> Self {
+        let header = |name| {
+            res.headers()
+                .get(name)
+                .and_then(|x| x.to_str().ok())
+                .map(|x| x.to_string())
+        };
+        Self {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        }
+    }
+
+    #[doc = "Path of the `.meta` file stored next to `path`."]
+    pub(crate) fn path(path: &Path) -> PathBuf {
+        let mut name = path.as_os_str().to_os_string();
+        name.push(".meta");
+        PathBuf::from(name)
+    }
+
+    #[doc = "Read the validators stored for `path`, if the download should be conditional at all. A missing or unreadable `.meta` file means the artifact is downloaded in full."]
+    pub(crate) fn cached(path: &Path, config: &DownloadConfig) -> Option<Self> {
+        if !config.conditional || config.force {
+            return None;
+        }
+        serde_json::from_slice(&std::fs::read(Self::path(path)).ok()?).ok()
+    }
+
+    #[doc = "Store the validators for `path` if conditional downloads are enabled."]
+    pub(crate) fn store(&self, path: &Path, config: &DownloadConfig) -> Result<()> {
+        if config.conditional && *self != Self::default() {
+            config.sink.put(
+                &Self::path(path),
+                &mut serde_json::to_vec(self)?.as_slice(),
+                None,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[doc = "Send a `GET` request, with `If-None-Match` and `If-Modified-Since` headers from `cached`. Returns `None` if the server answered `304 Not Modified`."]
+async fn get_if_modified(
+    url: &str,
+    cached: Option<&Validators>,
+    config: &DownloadConfig,
+) -> Result<Option<reqwest::Response>> {
+    let mut req = client(config)?.get(url);
+    if let Some(cached) = cached {
+        if let Some(etag) = &cached.etag {
+            req = req.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            req = req.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+    let res = req.send().await?;
+    log_redirect(url, &res);
+    if res.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    Ok(Some(check_encoding(check_status(res)?)?))
+}
+
+#[doc = "Like [`fetch_bytes`], but returns `None` if the server reports the artifact hasn't changed since `cached` was recorded."]
+pub(crate) async fn fetch_bytes_if_modified(
+    url: &str,
+    cached: Option<&Validators>,
+    config: &DownloadConfig,
+) -> Result<Option<(Vec<u8>, Validators)>> {
+    let url: &str = &rewrite(url, config);
+    if let Some(path) = local_path(url) {
+        return Ok(Some((std::fs::read(path)?, Validators::default())));
+    }
+    if let Some(bytes) = cached_bytes(url, config)? {
+        return Ok(Some((bytes, Validators::default())));
+    }
+    let _permit = config.throttle.acquire(url).await;
+    let Some(res) = get_if_modified(url, cached, config).await? else {
+        return Ok(None);
+    };
+    let validators = Validators::from_response(&res);
+    let bytes = res.bytes().await?.to_vec();
+    store_in_cache(url, &mut bytes.as_slice(), config);
+    Ok(Some((bytes, validators)))
+}
+
+#[doc = "Called with the bytes downloaded so far and the size of the artifact, if the server reported it."]
+pub(crate) type ProgressFn<'a> = &'a (dyn Fn(u64, Option<u64>) + Send + Sync);
+
+#[doc = "Like [`fetch_archive`], but returns `None` if the server reports the artifact hasn't changed since `cached` was recorded. "]
+#[doc = "`progress` is called as each chunk arrives, and once for local repositories."]
+pub(crate) async fn fetch_archive_if_modified(
+    url: &str,
+    cached: Option<&Validators>,
+    config: &DownloadConfig,
+    progress: Option<ProgressFn<'_>>,
+) -> Result<Option<(std::fs::File, Validators)>> {
+    let url: &str = &rewrite(url, config);
+    if let Some(file) = local_path(url)
+        .map(std::fs::File::open)
+        .transpose()?
+        .or_else(|| cached_copy(url, config))
+    {
+        if let Some(progress) = progress {
+            let len = file.metadata()?.len();
+            progress(len, Some(len));
+        }
+        return Ok(Some((file, Validators::default())));
+    }
+    let _permit = config.throttle.acquire(url).await;
+    let Some(mut res) = get_if_modified(url, cached, config).await? else {
+        return Ok(None);
+    };
+    let validators = Validators::from_response(&res);
+    let total = res.content_length();
+    let mut downloaded = 0;
+    let mut file = tempfile::tempfile()?;
+    while let Some(chunk) = res.chunk().await? {
+        file.write_all(&chunk)?;
+        downloaded += chunk.len() as u64;
+        if let Some(progress) = progress {
+            progress(downloaded, total);
+        }
+    }
+    file.rewind()?;
+    if config.cache.is_some() {
+        store_in_cache(url, &mut file, config);
+        file.rewind()?;
+    }
+    Ok(Some((file, validators)))
+}
+
+#[doc = "Fetch a zip or `.tar.gz` archive and extract it into `out_folder`. Returns the size of the archive in bytes, "]
+#[doc = "or 0 if it was left alone because the server reported it unchanged since the last conditional download into `out_folder`."]
+pub(crate) async fn fetch_and_extract(
+    url: &str,
+    out_folder: &Path,
+    config: &DownloadConfig,
+) -> Result<u64> {
+    fetch_and_extract_with_progress(url, out_folder, config, None, None).await
+}
+
+#[doc = "Like [`fetch_and_extract`], reporting download progress to `progress` and extraction progress to `extract_progress`."]
+pub(crate) async fn fetch_and_extract_with_progress(
+    url: &str,
+    out_folder: &Path,
+    config: &DownloadConfig,
+    progress: Option<ProgressFn<'_>>,
+    extract_progress: Option<crate::extract::ExtractProgressFn<'_>>,
+) -> Result<u64> {
+    log_debug!("Downloading {}", url);
+    // Validators are stored in the folder the archive was extracted to, next to the archive itself if it is kept.
+    let meta_name = url.rsplit('/').next().unwrap_or(url);
+    let cached = Validators::cached(&out_folder.join(meta_name), config);
+    let Some((file, validators)) =
+        fetch_archive_if_modified(url, cached.as_ref(), config, progress).await?
+    else {
+        log_debug!("{} is not modified", url);
+        return Ok(0);
+    };
+    let bytes = file.metadata()?.len();
+    let files_extracted = extract_file(
+        file,
+        meta_name,
+        &validators,
+        out_folder,
+        config,
+        config.keep_archives,
+        extract_progress,
+    )?;
+    config.tally.downloaded(bytes, files_extracted);
+    Ok(bytes)
+}
+
+#[doc = "Extract the zip or `.tar.gz` archive at `archive` into `out_folder` the way [`fetch_and_extract`] does once it has downloaded one, without any network access. "]
+#[doc = "Returns the number of files extracted. [`DownloadConfig::keep_archives`] doesn't apply, since the archive is on disk already."]
+pub(crate) fn extract_local(
+    archive: &Path,
+    out_folder: &Path,
+    config: &DownloadConfig,
+) -> Result<usize> {
+    log_debug!("Extracting {}", archive.display());
+    let meta_name = archive
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    extract_file(
+        std::fs::File::open(archive)?,
+        &meta_name,
+        &Validators::default(),
+        out_folder,
+        config,
+        false,
+        None,
+    )
+}
+
+#[doc = "Extract the archive `file` into `out_folder`, atomically if `config` asks for it, storing `validators` and, with `keep_archive`, the archive itself under `meta_name`. "]
+#[doc = "Returns the number of files extracted."]
+fn extract_file(
+    file: std::fs::File,
+    meta_name: &str,
+    validators: &Validators,
+    out_folder: &Path,
+    config: &DownloadConfig,
+    keep_archive: bool,
+    extract_progress: Option<crate::extract::ExtractProgressFn<'_>>,
+) -> Result<usize> {
+    let mut kept = if keep_archive {
+        Some(file.try_clone()?)
+    } else {
+        None
+    };
+    let mut keep = |out_folder: &Path| -> Result<()> {
+        if let Some(file) = &mut kept {
+            file.rewind()?;
+            config.sink.put(&out_folder.join(meta_name), file, None)?;
+        }
+        Ok(())
+    };
+    let mut archive = Archive::open(file)?;
+    let mut extract = |out_folder: &Path| match &mut archive {
+        Archive::Zip(zip) => crate::extract::extract_zip_with_progress(
+            zip,
+            out_folder,
+            &config.extract,
+            &config.cancel,
+            &config.sink,
+            extract_progress,
+        ),
+        Archive::TarGz(file) => crate::extract::extract_tar_gz_with_progress(
+            file,
+            out_folder,
+            &config.extract,
+            &config.cancel,
+            &config.sink,
+            extract_progress,
+        ),
+    };
+    // A directory other archives extract into too can't be replaced with this one alone.
+    if !(config.atomic && config.sink.can_replace_dirs() && !config.layout.shares_directories()) {
+        let written = extract(out_folder)?;
+        keep(out_folder)?;
+        validators.store(&out_folder.join(meta_name), config)?;
+        return Ok(written.len());
+    }
+    let staging = staging_dir(out_folder);
+    let res = (|| -> Result<usize> {
+        std::fs::create_dir_all(&staging)?;
+        let written = extract(&staging)?;
+        keep(&staging)?;
+        validators.store(&staging.join(meta_name), config)?;
+        config.sink.replace_dir(&staging, out_folder)?;
+        Ok(written.len())
+    })();
+    if res.is_err() {
+        _ = std::fs::remove_dir_all(&staging);
+    }
+    res
+}
+
+#[doc = "A downloaded archive, in one of the formats [`fetch_and_extract`] can extract."]
+enum Archive {
+    Zip(zip::ZipArchive<std::io::BufReader<std::fs::File>>),
+    TarGz(std::io::BufReader<std::fs::File>),
+}
+
+impl Archive {
+    #[doc = "Open `file`, telling the formats apart by their first bytes: gzip-compressed tar archives start with the gzip magic, anything else is read as a zip."]
+    fn open(mut file: std::fs::File) -> Result<Self> {
+        let mut magic = [0; 2];
+        let is_gzip =
+            std::io::Read::read_exact(&mut file, &mut magic).is_ok() && magic == GZIP_MAGIC;
+        file.rewind()?;
+        let reader = std::io::BufReader::new(file);
+        if is_gzip {
+            Ok(Self::TarGz(reader))
+        } else {
+            Ok(Self::Zip(zip::ZipArchive::new(reader)?))
+        }
+    }
+}
+
+pub(crate) const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[doc = "A fresh hidden directory next to `out_folder` to extract into before replacing `out_folder` with it."]
+fn staging_dir(out_folder: &Path) -> PathBuf {
+    static NEXT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let name = out_folder.file_name().unwrap_or_default().to_string_lossy();
+    out_folder.with_file_name(format!(
+        ".{}.partial-{}-{}",
+        name,
+        std::process::id(),
+        NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    ))
+}
+
+#[doc = "Like [`fetch_bytes`], but returns `None` if the artifact does not exist instead of an error."]
+pub(crate) async fn fetch_bytes_if_exists(
+    url: &str,
+    config: &DownloadConfig,
+) -> Result<Option<Vec<u8>>> {
+    Ok(fetch_resolved_if_exists(url, config)
+        .await?
+        .map(|(bytes, _)| bytes))
+}
+
+#[doc = "Like [`fetch_bytes_if_exists`], also returning the URL the artifact was served from after following redirects. "]
+#[doc = "Copies from [`DownloadConfig::cache`] are returned as served from `url` itself."]
+pub(crate) async fn fetch_resolved_if_exists(
+    url: &str,
+    config: &DownloadConfig,
+) -> Result<Option<(Vec<u8>, String)>> {
+    let url: &str = &rewrite(url, config);
+    if let Some(path) = local_path(url) {
+        return match std::fs::read(path) {
+            Ok(bytes) => Ok(Some((bytes, url.to_string()))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        };
+    }
+    if let Some(bytes) = cached_bytes(url, config)? {
+        return Ok(Some((bytes, url.to_string())));
+    }
+    let _permit = config.throttle.acquire(url).await;
+    let res = client(config)?.get(url).send().await?;
+    log_redirect(url, &res);
+    if res.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let resolved = res.url().to_string();
+    let bytes = check_encoding(check_status(res)?)?.bytes().await?.to_vec();
+    store_in_cache(url, &mut bytes.as_slice(), config);
+    Ok(Some((bytes, resolved)))
+}
+
+#[doc = "Fail with [`crate::error::Error::InvalidArtifact`] unless `bytes` start like a zip archive (which includes jars) or a gzip-compressed tar archive. "]
+#[doc = "Catches mirrors and proxies that answer with an HTML error page instead of the artifact."]
+pub(crate) fn ensure_archive(url: &str, bytes: &[u8]) -> Result<()> {
+    // Local file header, or the end of central directory record of an empty archive.
+    if bytes.starts_with(b"PK\x03\x04")
+        || bytes.starts_with(b"PK\x05\x06")
+        || bytes.starts_with(&GZIP_MAGIC)
+    {
+        Ok(())
+    } else {
+        Err(crate::error::Error::InvalidArtifact(url.to_string()))
+    }
+}
+
+#[doc = "Result of a `HEAD` request for an artifact."]
+pub(crate) enum Head {
+    #[doc = "The artifact does not exist."]
+    Missing,
+    #[doc = "The artifact exists, with a size if the server reported one."]
+    Found(Option<u64>),
+}
+
+#[doc = "Check whether an artifact exists and how large it is without downloading it."]
+pub(crate) async fn head(url: &str, config: &DownloadConfig) -> Result<Head> {
+    let url: &str = &rewrite(url, config);
+    match local_path(url) {
+        Some(path) => match std::fs::metadata(path) {
+            Ok(metadata) => Ok(Head::Found(Some(metadata.len()))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Head::Missing),
+            Err(e) => Err(e.into()),
+        },
+        None => {
+            let _permit = config.throttle.acquire(url).await;
+            let res = client(config)?.head(url).send().await?;
+            log_redirect(url, &res);
+            if res.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok(Head::Missing);
+            }
+            // `Response::content_length` reflects the (empty) body of a HEAD response, so read the header instead.
+            let len = check_status(res)?
+                .headers()
+                .get(reqwest::header::CONTENT_LENGTH)
+                .and_then(|x| x.to_str().ok())
+                .and_then(|x| x.parse().ok());
+            Ok(Head::Found(len))
+        }
+    }
+}
+
+#[doc = "The repositories of `maven_urls` in the order to try them for the artifact at `artifact_url(maven_url)`: the order they are listed in, unless "]
+#[doc = "[`DownloadConfig::race_mirrors`] is set. Then each is sent a `HEAD` request at once, and the first to report the artifact moves to the front, dropping the requests still in flight."]
+pub(crate) async fn mirror_order<'a>(
+    maven_urls: &'a [String],
+    config: &DownloadConfig,
+    artifact_url: impl Fn(&str) -> String,
+) -> Vec<&'a String> {
+    let mut order = maven_urls.iter().collect::<Vec<_>>();
+    if !config.race_mirrors || order.len() < 2 {
+        return order;
+    }
+    let probes = maven_urls.iter().enumerate().map(|(i, maven_url)| {
+        let url = artifact_url(maven_url);
+        Box::pin(async move {
+            match head(&url, config).await {
+                Ok(Head::Found(_)) => Ok(i),
+                _ => Err(()),
+            }
+        })
+    });
+    // If no repository has it, trying them in order reports why.
+    if let Ok((i, _)) = futures::future::select_ok(probes).await {
+        log_debug!("{} answered first", order[i]);
+        let first = order.remove(i);
+        order.insert(0, first);
+    }
+    order
+}
+
+#[doc = "Like [`head`], but only reports whether the artifact exists."]
+pub(crate) async fn exists(url: &str, config: &DownloadConfig) -> Result<bool> {
+    Ok(matches!(head(url, config).await?, Head::Found(_)))
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Read, Write};
+
+    use super::{
+        ensure_archive, fetch_and_extract, fetch_bytes, fetch_bytes_if_modified,
+        fetch_bytes_uncached, fetch_resolved_if_exists, head, local_path, mirror_order, Head,
+        Validators,
+    };
+    use crate::{error::Error, test_util::runtime};
+
+    #[cfg(unix)]
+    #[test]
+    fn local_paths() {
+        assert_eq!(
+            local_path("file:///srv/maven/edu/wpi/first/a.jar").unwrap(),
+            std::path::Path::new("/srv/maven/edu/wpi/first/a.jar")
+        );
+        assert_eq!(
+            local_path("/srv/maven/a.jar").unwrap(),
+            std::path::Path::new("/srv/maven/a.jar")
+        );
+        assert!(local_path("https://frcmaven.wpi.edu/artifactory/release/a.jar").is_none());
+        assert!(local_path("relative/a.jar").is_none());
+    }
+
+    #[test]
+    fn archive_magic() {
+        assert!(ensure_archive("a.jar", b"PK\x03\x04rest").is_ok());
+        assert!(ensure_archive("empty.jar", b"PK\x05\x06").is_ok());
+        assert!(ensure_archive("a.tar.gz", b"\x1f\x8b\x08rest").is_ok());
+        assert!(matches!(
+            ensure_archive("a.jar", b"<!DOCTYPE html><html>"),
+            Err(Error::InvalidArtifact(url)) if url == "a.jar"
+        ));
+    }
+
+    // Serve a single HTTP response on localhost, returning the URL to request.
+    fn serve_once(response: impl AsRef<[u8]> + Send + 'static) -> String {
+        serve_once_with_request(response).0
+    }
+
+    // Like `serve_once`, also returning the request the server received.
+    fn serve_once_with_request(
+        response: impl AsRef<[u8]> + Send + 'static,
+    ) -> (String, std::thread::JoinHandle<String>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let request = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0; 1024];
+            let len = stream.read(&mut buf).unwrap_or(0);
+            stream.write_all(response.as_ref()).unwrap();
+            String::from_utf8_lossy(&buf[..len]).to_lowercase()
+        });
+        (format!("http://{}/a.jar", addr), request)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_extract() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("a.zip");
+        std::fs::write(&archive, include_bytes!("../tests/fixtures/executable.zip")).unwrap();
+        let archive = archive.to_str().unwrap();
+        let out = dir.path().join("out");
+        let config = crate::DownloadConfig {
+            atomic: true,
+            ..Default::default()
+        };
+        let runtime = runtime();
+
+        runtime
+            .block_on(fetch_and_extract(archive, &out, &config))
+            .unwrap();
+        assert!(out.join("bin/halsim-tool").exists());
+        // The whole directory is replaced.
+        std::fs::write(out.join("stale.txt"), b"").unwrap();
+        runtime
+            .block_on(fetch_and_extract(archive, &out, &config))
+            .unwrap();
+        assert!(!out.join("stale.txt").exists());
+        assert!(out.join("lib/libexample.so").exists());
+
+        let cancelled = crate::DownloadConfig {
+            cancel: crate::CancellationToken::new(),
+            ..config
+        };
+        cancelled.cancel.cancel();
+        assert!(matches!(
+            runtime.block_on(fetch_and_extract(archive, &out, &cancelled)),
+            Err(Error::Cancelled)
+        ));
+        assert!(out.join("bin/halsim-tool").exists());
+        let mut entries = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|x| x.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        entries.sort();
+        assert_eq!(entries, ["a.zip", "out"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keep_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("a.zip");
+        std::fs::write(&archive, include_bytes!("../tests/fixtures/executable.zip")).unwrap();
+        let archive = archive.to_str().unwrap();
+        let runtime = runtime();
+
+        let out = dir.path().join("out");
+        runtime
+            .block_on(fetch_and_extract(
+                archive,
+                &out,
+                &crate::DownloadConfig::default(),
+            ))
+            .unwrap();
+        assert!(!out.join("a.zip").exists());
+
+        for atomic in [false, true] {
+            let out = dir.path().join(format!("kept-{}", atomic));
+            let config = crate::DownloadConfig {
+                keep_archives: true,
+                atomic,
+                ..Default::default()
+            };
+            runtime
+                .block_on(fetch_and_extract(archive, &out, &config))
+                .unwrap();
+            assert!(out.join("lib/libexample.so").exists());
+            assert_eq!(
+                std::fs::read(out.join("a.zip")).unwrap(),
+                include_bytes!("../tests/fixtures/executable.zip")
+            );
+
+            // The kept archive can be extracted again without the repository.
+            let again = dir.path().join(format!("again-{}", atomic));
+            runtime
+                .block_on(fetch_and_extract(
+                    out.join("a.zip").to_str().unwrap(),
+                    &again,
+                    &crate::DownloadConfig::default(),
+                ))
+                .unwrap();
+            assert!(again.join("lib/libexample.so").exists());
+        }
+    }
+
+    #[test]
+    fn head_without_content_length() {
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n",
+        );
+        let res = runtime().block_on(head(&url, &crate::DownloadConfig::default()));
+        assert!(matches!(res, Ok(Head::Found(None))));
+    }
+
+    #[test]
+    fn conditional_requests() {
+        let runtime = runtime();
+        let config = crate::DownloadConfig::default();
+        let (url, _) = serve_once_with_request(
+            "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nETag: \"abc\"\r\nLast-Modified: Wed, 21 Oct 2024 07:28:00 GMT\r\nConnection: close\r\n\r\nPK\x05\x06",
+        );
+        let (bytes, validators) = runtime
+            .block_on(fetch_bytes_if_modified(&url, None, &config))
+            .unwrap()
+            .unwrap();
+        assert_eq!(bytes, b"PK\x05\x06");
+        assert_eq!(
+            validators,
+            Validators {
+                etag: Some("\"abc\"".to_string()),
+                last_modified: Some("Wed, 21 Oct 2024 07:28:00 GMT".to_string()),
+            }
+        );
+
+        let (url, request) =
+            serve_once_with_request("HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n");
+        let res = runtime
+            .block_on(fetch_bytes_if_modified(&url, Some(&validators), &config))
+            .unwrap();
+        assert!(res.is_none());
+        let request = request.join().unwrap();
+        assert!(request.contains("if-none-match: \"abc\""));
+        assert!(request.contains("if-modified-since: wed, 21 oct 2024 07:28:00 gmt"));
+    }
+
+    #[test]
+    fn stored_validators() {
+        let dir = tempfile::tempdir().unwrap();
+        let jar = dir.path().join("a.jar");
+        let validators = Validators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+        };
+        let mut config = crate::DownloadConfig::default();
+        validators.store(&jar, &config).unwrap();
+        assert!(!dir.path().join("a.jar.meta").exists());
+        config.conditional = true;
+        validators.store(&jar, &config).unwrap();
+        assert!(dir.path().join("a.jar.meta").exists());
+        assert_eq!(Validators::cached(&jar, &config), Some(validators));
+        config.force = true;
+        assert_eq!(Validators::cached(&jar, &config), None);
+    }
+
+    #[test]
+    fn http_error_status() {
+        let url = serve_once(
+            "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 5\r\nConnection: close\r\n\r\nerror",
+        );
+        let res = runtime().block_on(fetch_bytes(&url, &crate::DownloadConfig::default()));
+        assert!(matches!(res, Err(Error::HttpStatus { code: 500, url: x }) if x == url));
+    }
+
+    #[test]
+    fn content_encoding() {
+        let runtime = runtime();
+        let config = crate::DownloadConfig::default();
+        // An empty zip archive, gzipped on top.
+        let url = serve_once(
+            &b"HTTP/1.1 200 OK\r\nContent-Length: 26\r\nContent-Encoding: gzip\r\nConnection: close\r\n\r\n\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\x03\x0b\xf0\x66\x65\x63\xc0\x00\x00\x0e\xc5\xcb\xd7\x16\x00\x00\x00"[..],
+        );
+        let bytes = runtime.block_on(fetch_bytes(&url, &config)).unwrap();
+        assert_eq!(bytes, [&b"PK\x05\x06"[..], &[0u8; 18][..]].concat());
+
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nContent-Encoding: br\r\nConnection: close\r\n\r\nPK\x05\x06",
+        );
+        let res = runtime.block_on(fetch_bytes(&url, &config));
+        assert!(
+            matches!(res, Err(Error::UnsupportedEncoding { encoding, url: x }) if encoding == "br" && x == url)
+        );
+    }
+
+    #[test]
+    fn request_headers() {
+        let runtime = runtime();
+        let (url, request) = serve_once_with_request(
+            "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\nPK\x05\x06",
+        );
+        runtime
+            .block_on(fetch_bytes(&url, &crate::DownloadConfig::default()))
+            .unwrap();
+        assert!(request.join().unwrap().contains(&format!(
+            "user-agent: vendordeps/{}",
+            env!("CARGO_PKG_VERSION")
+        )));
+
+        let config = crate::DownloadConfig {
+            user_agent: Some("team-tool/1.0".to_string()),
+            headers: vec![("X-Team-Number".to_string(), "1234".to_string())],
+            ..Default::default()
+        };
+        let (url, request) = serve_once_with_request(
+            "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\nPK\x05\x06",
+        );
+        runtime.block_on(fetch_bytes(&url, &config)).unwrap();
+        let request = request.join().unwrap();
+        assert!(request.contains("user-agent: team-tool/1.0"));
+        assert!(request.contains("x-team-number: 1234"));
+
+        let config = crate::DownloadConfig {
+            headers: vec![("X-Team Number".to_string(), "1234".to_string())],
+            ..Default::default()
+        };
+        assert!(matches!(
+            runtime.block_on(fetch_bytes("http://127.0.0.1:1/a.jar", &config)),
+            Err(Error::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn redirects() {
+        let runtime = runtime();
+        let redirect = |to: &str| {
+            serve_once(format!(
+                "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                to
+            ))
+        };
+        let target = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\nPK\x05\x06",
+        );
+        let url = redirect(&target);
+        let (bytes, resolved) = runtime
+            .block_on(fetch_resolved_if_exists(
+                &url,
+                &crate::DownloadConfig::default(),
+            ))
+            .unwrap()
+            .unwrap();
+        assert_eq!(bytes, b"PK\x05\x06");
+        assert_eq!(resolved, target);
+
+        let config = crate::DownloadConfig {
+            max_redirects: Some(0),
+            ..Default::default()
+        };
+        let url = redirect("http://127.0.0.1:1/a.jar");
+        assert!(matches!(
+            runtime.block_on(fetch_bytes(&url, &config)),
+            Err(Error::HttpStatus { code: 302, url: x }) if x == url
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn mirror_order_races() {
+        let runtime = runtime();
+        let empty = tempfile::tempdir().unwrap();
+        let full = tempfile::tempdir().unwrap();
+        std::fs::write(full.path().join("a.jar"), b"PK\x05\x06").unwrap();
+        let maven_urls = vec![
+            format!("file://{}/", empty.path().display()),
+            format!("file://{}/", full.path().display()),
+        ];
+        let artifact_url = |x: &str| format!("{}a.jar", x);
+
+        let listed = runtime.block_on(mirror_order(
+            &maven_urls,
+            &crate::DownloadConfig::default(),
+            artifact_url,
+        ));
+        assert_eq!(listed, vec![&maven_urls[0], &maven_urls[1]]);
+
+        let config = crate::DownloadConfig {
+            race_mirrors: true,
+            ..Default::default()
+        };
+        let raced = runtime.block_on(mirror_order(&maven_urls, &config, artifact_url));
+        assert_eq!(raced, vec![&maven_urls[1], &maven_urls[0]]);
+
+        // Nobody has it, so the listed order stands.
+        let missing = runtime.block_on(mirror_order(&maven_urls, &config, |x| {
+            format!("{}b.jar", x)
+        }));
+        assert_eq!(missing, vec![&maven_urls[0], &maven_urls[1]]);
+    }
+
+    #[test]
+    fn url_rewriter() {
+        let runtime = runtime();
+        let (url, request) = serve_once_with_request(
+            "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\nPK\x05\x06",
+        );
+        let base = url.trim_end_matches("/a.jar").to_string();
+        let config = crate::DownloadConfig {
+            url_rewriter: Some(crate::UrlRewriter::new(move |url| {
+                format!("{}/cache?url={}", base, url)
+            })),
+            ..Default::default()
+        };
+        let bytes = runtime
+            .block_on(fetch_bytes("https://frcmaven.wpi.edu/a.jar", &config))
+            .unwrap();
+        assert_eq!(bytes, b"PK\x05\x06");
+        assert!(request
+            .join()
+            .unwrap()
+            .starts_with("get /cache?url=https://frcmaven.wpi.edu/a.jar "));
+    }
+
+    #[test]
+    fn download_cache() {
+        let runtime = runtime();
+        let dir = tempfile::tempdir().unwrap();
+        let config = crate::DownloadConfig {
+            cache: Some(crate::DownloadCache::new(dir.path())),
+            ..Default::default()
+        };
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\nPK\x05\x06",
+        );
+        let (bytes, _) = runtime
+            .block_on(fetch_bytes_if_modified(&url, None, &config))
+            .unwrap()
+            .unwrap();
+        assert_eq!(bytes, b"PK\x05\x06");
+        // The server is gone, so this can only come from the cache.
+        let (bytes, _) = runtime
+            .block_on(fetch_bytes_if_modified(&url, None, &config))
+            .unwrap()
+            .unwrap();
+        assert_eq!(bytes, b"PK\x05\x06");
+        let force = crate::DownloadConfig {
+            force: true,
+            ..config
+        };
+        assert!(runtime
+            .block_on(fetch_bytes_if_modified(&url, None, &force))
+            .is_err());
+    }
+
+    #[test]
+    fn download_cache_fetch_bytes() {
+        let runtime = runtime();
+        let dir = tempfile::tempdir().unwrap();
+        let config = crate::DownloadConfig {
+            cache: Some(crate::DownloadCache::new(dir.path())),
+            ..Default::default()
+        };
+        let response =
+            "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\nPK\x05\x06";
+
+        let url = serve_once(response);
+        assert_eq!(
+            runtime.block_on(fetch_bytes(&url, &config)).unwrap(),
+            b"PK\x05\x06"
+        );
+        // The server is gone, so these can only come from the cache.
+        assert_eq!(
+            runtime.block_on(fetch_bytes(&url, &config)).unwrap(),
+            b"PK\x05\x06"
+        );
+        assert_eq!(
+            runtime
+                .block_on(fetch_resolved_if_exists(&url, &config))
+                .unwrap(),
+            Some((b"PK\x05\x06".to_vec(), url.clone()))
+        );
+        assert!(runtime
+            .block_on(fetch_bytes_uncached(&url, &config))
+            .is_err());
+
+        let url = serve_once(response);
+        assert!(runtime
+            .block_on(fetch_resolved_if_exists(&url, &config))
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            runtime.block_on(fetch_bytes(&url, &config)).unwrap(),
+            b"PK\x05\x06"
+        );
+    }
+
+    #[cfg(feature = "rustls")]
+    #[test]
+    fn rustls_client() {
+        super::client(&crate::DownloadConfig::default()).unwrap();
+    }
+}
@@ -0,0 +1,160 @@
+use std::cmp::Ordering;
+
+use crate::{fetch, maven, DownloadConfig, Result, VendorDep};
+
+#[doc = "What fetching [`VendorDep::json_url`] turned up, as reported by [`VendorDep::verify_json_url`]."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonUrlStatus {
+    #[doc = "`json_url` is empty."]
+    NoUrl,
+    #[doc = "`json_url` couldn't be fetched, with the reason."]
+    Unreachable(String),
+    #[doc = "`json_url` was fetched but isn't vendordep JSON, with the reason."]
+    Invalid(String),
+    #[doc = "`json_url` holds a vendordep."]
+    Reachable {
+        #[doc = "Whether its `uuid` is the same as this vendordep's."]
+        uuid_matches: bool,
+        #[doc = "Whether its `name` is the same as this vendordep's."]
+        name_matches: bool,
+        #[doc = "Its `version`."]
+        version: String,
+        #[doc = "How its `version` compares to this vendordep's by [`crate::maven::compare_versions`]: [`Ordering::Greater`] if `json_url` has a newer version."]
+        version_ordering: Ordering,
+    },
+}
+
+impl JsonUrlStatus {
+    #[doc = "Whether `json_url` reproduces the vendordep: it is reachable and has the same `uuid`, `name` and version."]
+    pub fn is_consistent(&self) -> bool {
+        matches!(
+            self,
+            Self::Reachable {
+                uuid_matches: true,
+                name_matches: true,
+                version_ordering: Ordering::Equal,
+                ..
+            }
+        )
+    }
+}
+
+impl VendorDep {
+    #[doc = "Fetch [`Self::json_url`] and check that it holds this vendordep, for auditing vendordeps before publishing them. "]
+    #[doc = "Problems with the URL are reported in the returned [`JsonUrlStatus`] rather than as errors."]
+    pub async fn verify_json_url(&self) -> Result<JsonUrlStatus> {
+        self.verify_json_url_with_config(&DownloadConfig::default())
+            .await
+    }
+
+    #[doc = "Like [`Self::verify_json_url`], with additional options. Fails with [`crate::error::Error::Cancelled`] if `config` is cancelled."]
+    pub async fn verify_json_url_with_config(
+        &self,
+        config: &DownloadConfig,
+    ) -> Result<JsonUrlStatus> {
+        if self.json_url.is_empty() {
+            return Ok(JsonUrlStatus::NoUrl);
+        }
+        config.cancel.check()?;
+        let bytes = match config
+            .retry
+            .run(&config.cancel, || {
+                fetch::fetch_bytes_uncached(&self.json_url, config)
+            })
+            .await
+        {
+            Ok(bytes) => bytes,
+            Err(crate::error::Error::Cancelled) => return Err(crate::error::Error::Cancelled),
+            Err(e) => return Ok(JsonUrlStatus::Unreachable(e.to_string())),
+        };
+        let remote = match VendorDep::from_slice(&bytes) {
+            Ok(remote) => remote,
+            Err(e) => return Ok(JsonUrlStatus::Invalid(e.to_string())),
+        };
+        Ok(JsonUrlStatus::Reachable {
+            uuid_matches: remote.uuid == self.uuid,
+            name_matches: remote.name == self.name,
+            version_ordering: maven::compare_versions(&remote.version, &self.version),
+            version: remote.version,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+
+    use tempfile::tempdir;
+
+    use super::JsonUrlStatus;
+    use crate::{test_util::runtime, VendorDep};
+
+    #[cfg(unix)]
+    #[test]
+    fn verify_json_url() {
+        let dir = tempdir().unwrap();
+        let mut dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let runtime = runtime();
+
+        let json_path = dir.path().join("Phoenix6.json");
+        dep.json_url = format!("file://{}", json_path.display());
+        assert!(matches!(
+            runtime.block_on(dep.verify_json_url()).unwrap(),
+            JsonUrlStatus::Unreachable(_)
+        ));
+
+        std::fs::write(&json_path, "<html></html>").unwrap();
+        assert!(matches!(
+            runtime.block_on(dep.verify_json_url()).unwrap(),
+            JsonUrlStatus::Invalid(_)
+        ));
+
+        std::fs::write(&json_path, dep.to_json(true).unwrap()).unwrap();
+        let status = runtime.block_on(dep.verify_json_url()).unwrap();
+        assert!(status.is_consistent());
+
+        let mut newer = serde_json::from_str::<VendorDep>(&dep.to_json(false).unwrap()).unwrap();
+        newer.version = "24.10.0".to_string();
+        newer.name = "Phoenix6".to_string();
+        std::fs::write(&json_path, newer.to_json(true).unwrap()).unwrap();
+        let status = runtime.block_on(dep.verify_json_url()).unwrap();
+        assert_eq!(
+            status,
+            JsonUrlStatus::Reachable {
+                uuid_matches: true,
+                name_matches: false,
+                version: "24.10.0".to_string(),
+                version_ordering: Ordering::Greater,
+            }
+        );
+        assert!(!status.is_consistent());
+
+        // The URL goes through the config's rewriter, as downloads do.
+        dep.json_url = "https://example.com/Phoenix6.json".to_string();
+        let config = crate::DownloadConfig {
+            url_rewriter: Some(crate::UrlRewriter::new(move |_| {
+                format!("file://{}", json_path.display())
+            })),
+            ..Default::default()
+        };
+        assert!(matches!(
+            runtime
+                .block_on(dep.verify_json_url_with_config(&config))
+                .unwrap(),
+            JsonUrlStatus::Reachable { .. }
+        ));
+        config.cancel.cancel();
+        assert!(matches!(
+            runtime.block_on(dep.verify_json_url_with_config(&config)),
+            Err(crate::error::Error::Cancelled)
+        ));
+
+        dep.json_url.clear();
+        assert_eq!(
+            runtime.block_on(dep.verify_json_url()).unwrap(),
+            JsonUrlStatus::NoUrl
+        );
+    }
+}
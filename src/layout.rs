@@ -0,0 +1,298 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{BinaryPlatform, CppDependency, CppInfo, Result, VendorDep};
+
+type DirFn = dyn Fn(&Path, &CppDependency) -> PathBuf + Send + Sync;
+
+#[doc = "Where the C++ download methods put each dependency's headers and libraries, given the output folder. "]
+#[doc = "The default is `<folder>/<artifact_id>/include` and `<folder>/<artifact_id>/libs`, which is also what [`CppInfo::from_existing`], "]
+#[doc = "[`VendorDep::verify_downloaded`] and [`VendorDep::prune_unreferenced`] expect. Read a custom layout back with [`CppInfo::from_existing_with_layout`]."]
+#[derive(Clone)]
+pub struct LayoutStrategy {
+    include_dir: Arc<DirFn>,
+    libs_dir: Arc<DirFn>,
+    shared: bool,
+}
+
+impl LayoutStrategy {
+    #[doc = "Build a layout from functions mapping the output folder and a dependency to its header and library directories. "]
+    #[doc = "Each dependency should get directories of its own, since a cancelled download removes them."]
+    pub fn new(
+        include_dir: impl Fn(&Path, &CppDependency) -> PathBuf + Send + Sync + 'static,
+        libs_dir: impl Fn(&Path, &CppDependency) -> PathBuf + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            include_dir: Arc::new(include_dir),
+            libs_dir: Arc::new(libs_dir),
+            shared: false,
+        }
+    }
+
+    #[doc = "A sysroot for `platform` that a cross-compiler can be pointed at: every dependency's headers go into one `<folder>/include` tree "]
+    #[doc = "and its libraries into `<folder>/lib/<platform>`. Files that two dependencies both provide fail with [`crate::error::Error::FileCollision`]. "]
+    #[doc = "Since the directories are shared, [`crate::DownloadConfig::atomic`] doesn't apply, and a cancelled download leaves partially extracted files behind."]
+    pub fn sysroot(platform: &BinaryPlatform) -> Self {
+        let platform = platform.to_str().to_string();
+        Self {
+            shared: true,
+            ..Self::new(
+                |folder, _| folder.join("include"),
+                move |folder, _| folder.join("lib").join(&platform),
+            )
+        }
+    }
+
+    #[doc = "Whether dependencies share directories, as with [`Self::sysroot`], so that one dependency's directories can't be removed or replaced as a whole."]
+    pub fn shares_directories(&self) -> bool {
+        self.shared
+    }
+
+    #[doc = "Directory the headers of `dep` are extracted to."]
+    pub fn include_dir(&self, folder: &Path, dep: &CppDependency) -> PathBuf {
+        (self.include_dir)(folder, dep)
+    }
+
+    #[doc = "Directory the libraries of `dep` are extracted to."]
+    pub fn libs_dir(&self, folder: &Path, dep: &CppDependency) -> PathBuf {
+        (self.libs_dir)(folder, dep)
+    }
+}
+
+impl Default for LayoutStrategy {
+    fn default() -> Self {
+        Self::new(
+            |folder, dep| folder.join(&dep.artifact_id).join("include"),
+            |folder, dep| folder.join(&dep.artifact_id).join("libs"),
+        )
+    }
+}
+
+impl std::fmt::Debug for LayoutStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LayoutStrategy").finish_non_exhaustive()
+    }
+}
+
+impl CppInfo {
+    #[doc = "Create new [`CppInfo`] for the cpp dependencies of `vendordep`, downloaded to `p` with `layout`. "]
+    #[doc = "Unlike [`CppInfo::from_existing`], only the directories `layout` assigns to those dependencies are read. Header directories that don't exist are left out."]
+    pub fn from_existing_with_layout<P: AsRef<Path>>(
+        p: P,
+        vendordep: &VendorDep,
+        layout: &LayoutStrategy,
+    ) -> Result<Self> {
+        let p = p.as_ref();
+        let mut info = Self::new_empty();
+        for dep in &vendordep.cpp_dependencies {
+            let include_dir = layout.include_dir(p, dep);
+            if include_dir.is_dir() {
+                info.include_dirs.push(include_dir);
+            }
+            info.add_libraries_in(&layout.libs_dir(p, dep))?;
+            info.add_include_dirs_in(&layout.libs_dir(p, dep))?;
+        }
+        info.dedup();
+        Ok(info)
+    }
+}
+
+impl CppDependency {
+    #[doc = "Directories the headers and the libraries for `platform` are extracted to when downloaded into `root` with `layout`. "]
+    #[doc = "What ends up inside depends on the archives, so only the directories are returned. The header directory is left out if the dependency has no headers, "]
+    #[doc = "and the library directory if it isn't published for `platform`. Nothing is read or fetched."]
+    pub fn expected_paths<P: AsRef<Path>>(
+        &self,
+        root: P,
+        platform: &BinaryPlatform,
+        layout: &LayoutStrategy,
+    ) -> Vec<PathBuf> {
+        let root = root.as_ref();
+        let mut paths = Vec::new();
+        if self.has_headers() {
+            paths.push(layout.include_dir(root, self));
+        }
+        if self.supports_platform(platform) {
+            paths.push(layout.libs_dir(root, self));
+        }
+        paths
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::LayoutStrategy;
+    use crate::{
+        test_util::{cpp_dep, platforms, runtime, Mirror},
+        BinaryPlatform, CppDependency, CppInfo, DownloadConfig, VendorDep,
+    };
+
+    #[test]
+    fn expected_paths() {
+        let dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let root = Path::new("/vendor");
+        let layout = LayoutStrategy::default();
+        assert_eq!(
+            dep.cpp_dependencies[0].expected_paths(root, &BinaryPlatform::LinuxX86_64, &layout),
+            vec![
+                root.join(&dep.cpp_dependencies[0].artifact_id)
+                    .join("include"),
+                root.join(&dep.cpp_dependencies[0].artifact_id).join("libs"),
+            ]
+        );
+        assert_eq!(
+            dep.cpp_dependencies[0].expected_paths(root, &BinaryPlatform::OsxUniversal, &layout),
+            vec![root
+                .join(&dep.cpp_dependencies[0].artifact_id)
+                .join("include")]
+        );
+        assert_eq!(
+            dep.java_dependencies[0].expected_paths(root),
+            vec![root.join(dep.java_dependencies[0].file_name())]
+        );
+        assert_eq!(
+            dep.jni_dependencies[0].expected_paths(root, &BinaryPlatform::LinuxAthena),
+            vec![root.join(&dep.jni_dependencies[0].artifact_id)]
+        );
+        assert!(dep.jni_dependencies[0]
+            .expected_paths(root, &BinaryPlatform::Other("none".to_string()))
+            .is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn custom_layout() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                ..cpp_dep("example-cpp")
+            })
+            .build();
+        for (classifier, fixture) in [
+            (
+                "headers",
+                &include_bytes!("../tests/fixtures/headers.zip")[..],
+            ),
+            (
+                "linuxx86-64",
+                include_bytes!("../tests/fixtures/executable.zip"),
+            ),
+        ] {
+            mirror.publish(
+                &dep.cpp_dependencies[0].get_url(&mirror.maven_url, classifier, false, false),
+                fixture,
+            );
+        }
+
+        let layout = LayoutStrategy::new(
+            |folder, dep| folder.join("include").join(&dep.artifact_id),
+            |folder, dep| folder.join("lib").join(&dep.artifact_id),
+        );
+        let config = DownloadConfig {
+            layout: layout.clone(),
+            ..Default::default()
+        };
+        let out = tempfile::tempdir().unwrap();
+        let info = runtime()
+            .block_on(dep.download_all_cpp_deps_to_folder_with_config(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                false,
+                &config,
+            ))
+            .unwrap();
+        assert_eq!(
+            info.include_dirs,
+            vec![out.path().join("include/example-cpp")]
+        );
+        assert_eq!(info.libraries, vec!["example"]);
+        assert!(!out.path().join("example-cpp").exists());
+
+        let existing = CppInfo::from_existing_with_layout(out.path(), &dep, &layout).unwrap();
+        assert_eq!(existing.include_dirs, info.include_dirs);
+        assert_eq!(existing.library_search_paths, info.library_search_paths);
+        assert_eq!(existing.libraries, info.libraries);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sysroot_layout() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                ..cpp_dep("example-cpp")
+            })
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                binary_platforms: platforms(&["linuxathena"]),
+                ..cpp_dep("wrapped-cpp")
+            })
+            .build();
+        for (dep, classifier, fixture) in [
+            (
+                &dep.cpp_dependencies[0],
+                "headers",
+                &include_bytes!("../tests/fixtures/headers.zip")[..],
+            ),
+            (
+                &dep.cpp_dependencies[0],
+                "linuxx86-64",
+                include_bytes!("../tests/fixtures/executable.zip"),
+            ),
+            (
+                &dep.cpp_dependencies[1],
+                "headers",
+                include_bytes!("../tests/fixtures/wrapped.zip"),
+            ),
+        ] {
+            mirror.publish(
+                &dep.get_url(&mirror.maven_url, classifier, false, false),
+                fixture,
+            );
+        }
+
+        let layout = LayoutStrategy::sysroot(&BinaryPlatform::LinuxX86_64);
+        assert!(layout.shares_directories());
+        assert!(!LayoutStrategy::default().shares_directories());
+        let config = DownloadConfig {
+            layout,
+            atomic: true,
+            ..Default::default()
+        };
+        let out = tempfile::tempdir().unwrap();
+        let info = runtime()
+            .block_on(dep.download_all_cpp_deps_to_folder_with_config(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                true,
+                &config,
+            ))
+            .unwrap();
+        assert_eq!(info.include_dirs, vec![out.path().join("include")]);
+        // Both dependencies' headers end up in the one tree, even with `atomic` set.
+        assert!(out.path().join("include/frc/Example.h").is_file());
+        assert!(out
+            .path()
+            .join("include/example-1.0/include/frc/Example.h")
+            .is_file());
+        assert_eq!(info.libraries, vec!["example"]);
+        assert_eq!(
+            info.library_search_paths,
+            vec![out.path().join("lib/linuxx86-64/lib")]
+        );
+    }
+}
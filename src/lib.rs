@@ -3,27 +3,163 @@
 
 #[allow(unused)]
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
 use serde::{Deserialize, Serialize};
 
-#[doc = "Latest release version of WPILib."]
+#[cfg(feature = "log")]
+macro_rules! log_warn {
+    ($($arg:tt)*) => { log::warn!($($arg)*) };
+}
+#[cfg(feature = "log")]
+macro_rules! log_info {
+    ($($arg:tt)*) => { log::info!($($arg)*) };
+}
+#[cfg(feature = "log")]
+macro_rules! log_debug {
+    ($($arg:tt)*) => { log::debug!($($arg)*) };
+}
+// Without the `log` feature these still type-check their arguments so variables used only for logging aren't reported as unused.
+#[cfg(not(feature = "log"))]
+macro_rules! log_warn {
+    ($($arg:tt)*) => { { let _ = format_args!($($arg)*); } };
+}
+#[cfg(not(feature = "log"))]
+macro_rules! log_info {
+    ($($arg:tt)*) => { { let _ = format_args!($($arg)*); } };
+}
+#[cfg(not(feature = "log"))]
+macro_rules! log_debug {
+    ($($arg:tt)*) => { { let _ = format_args!($($arg)*); } };
+}
+
+#[doc = "Latest release version of WPILib when this crate was last updated. A snapshot that goes stale; [`latest_wpilib_version`] asks the WPILib Maven repository instead."]
 pub const WPILIB_LATEST_VERSION: &'static str = "2024.3.2";
+#[doc = "FRC year of [`WPILIB_LATEST_VERSION`]."]
+pub const WPILIB_LATEST_YEAR: u32 = 2024;
 #[doc = "Maven repository for WPILib releases."]
 pub const WPILIB_RELEASE_MAVEN_REPO: &'static str = "https://frcmaven.wpi.edu/artifactory/release/";
 
-#[cfg(feature = "download")]
 pub mod error;
-#[cfg(feature = "download")]
 pub use error::Result;
+#[doc = "The `serde_json` version used for [`VendorDep::from_slice`], [`VendorDep::to_json`] and [`error::Error::JsonError`], so callers don't have to match it themselves."]
+pub use serde_json;
+#[cfg(feature = "download")]
+mod build_script;
+#[cfg(feature = "download")]
+pub use build_script::install_vendordep_blocking;
+#[cfg(feature = "download")]
+mod cache;
+#[cfg(feature = "download")]
+pub use cache::DownloadCache;
+#[cfg(feature = "download")]
+mod cancel;
+#[cfg(feature = "download")]
+pub use cancel::CancellationToken;
+#[cfg(feature = "download")]
+mod extract;
+#[cfg(feature = "download")]
+mod throttle;
+#[cfg(feature = "download")]
+pub use extract::{ExtractOptions, ExtractPolicy};
+#[cfg(feature = "download")]
+pub use throttle::Throttle;
+mod builder;
+pub use builder::VendorDepBuilder;
+mod native;
+pub use native::{classify_native, NativeKind};
+mod bundle;
+mod deploy;
+mod diff;
+mod machine;
+mod symbols;
+pub use bundle::{BundleBuild, BundleLibraries, BundleManifest};
+pub use deploy::{DeployFile, DeployManifest, ROBORIO_LIBRARY_DIR};
+pub use diff::{DependencyChange, DependencyDiff, VendorDepDiff};
+#[cfg(feature = "download")]
+mod diagnostics;
+#[cfg(feature = "download")]
+mod downloader;
+#[cfg(feature = "download")]
+mod events;
+#[cfg(feature = "download")]
+mod json_url;
+#[cfg(feature = "download")]
+mod layout;
+#[cfg(feature = "download")]
+mod lockfile;
+#[cfg(feature = "download")]
+mod preflight;
+#[cfg(feature = "download")]
+mod retry;
+#[cfg(feature = "download")]
+mod rewrite;
+#[cfg(feature = "download")]
+mod sim;
+#[cfg(feature = "download")]
+pub use sim::{DiscoveredLibrary, SimJniInfo, SimJniLibrary};
+#[cfg(feature = "download")]
+mod sink;
+#[cfg(feature = "download")]
+mod summary;
+#[cfg(feature = "download")]
+pub use diagnostics::{Diagnostic, Diagnostics};
+#[cfg(feature = "download")]
+pub use downloader::Downloader;
+#[cfg(feature = "download")]
+pub use events::DownloadEvent;
+#[cfg(feature = "download")]
+pub use json_url::JsonUrlStatus;
+#[cfg(feature = "download")]
+pub use layout::LayoutStrategy;
+#[cfg(feature = "download")]
+pub use lockfile::{
+    install_from_lockfile, install_from_lockfile_with_config, LockedArtifact, Lockfile,
+};
+#[cfg(feature = "download")]
+pub use preflight::{ArtifactCheck, ArtifactStatus, ValidationReport};
+#[cfg(feature = "download")]
+pub use retry::RetryPolicy;
+#[cfg(feature = "download")]
+pub use rewrite::UrlRewriter;
+#[cfg(feature = "download")]
+pub use sink::{ArtifactSink, ArtifactStore, FileSystemSink};
+#[cfg(feature = "download")]
+pub use summary::{DownloadSummary, DownloadTally, SizeEstimate};
+#[cfg(feature = "download")]
+mod fetch;
+#[cfg(feature = "maven")]
+#[doc = "Maven metadata and POM handling. Parsing only needs the `maven` feature; fetching needs `download`."]
+pub mod maven;
+#[cfg(all(test, feature = "download"))]
+mod test_util;
+mod validate;
+pub use validate::{
+    Compatibility, Incompatibility, JavaVersionConflict, MissingRequirement, UnknownPlatform,
+    ValidationError,
+};
+mod year;
+pub use year::FrcYear;
+mod coverage;
+pub use coverage::{DependencyCoverage, PlatformCoverage};
+mod observe;
+pub use observe::{observe_cpp_downloads, ObservedArtifact};
+mod verify;
+pub use verify::{MissingArtifact, MissingKind, VersionMismatch};
+#[doc = "Maven coordinates and naming conventions of WPILib's own libraries, shared with the `wpilib-index` tool."]
+pub mod wpilib;
+#[cfg(feature = "download")]
+pub use wpilib::latest_wpilib_version;
+pub use wpilib::WPILIB_CORE_UUID;
 
 #[doc = "A reference to another vendordep."]
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PackageSpec {
     #[doc = "The `uuid` field of the other vendordep."]
+    #[serde(deserialize_with = "__private::deserialize_uuid")]
     pub uuid: String,
     #[doc = "The message printed if this package is also included."]
     pub error_message: String,
@@ -31,8 +167,17 @@ pub struct PackageSpec {
     pub offline_file_name: String,
 }
 
+#[doc = "A [`PackageSpec`] whose `offline_file_name` does not match the `file_name` of any [`VendorDep`] being written alongside it. See [`VendorDep::write_vendordeps_dir`]."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DanglingConflict {
+    #[doc = "`file_name` of the vendordep declaring the conflict."]
+    pub file_name: String,
+    #[doc = "The `offline_file_name` that matched nothing."]
+    pub offline_file_name: String,
+}
+
 #[doc = "A dependency for Java Compilation."]
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JavaDependency {
     #[doc = "Maven group."]
@@ -41,9 +186,44 @@ pub struct JavaDependency {
     pub artifact_id: String,
     #[doc = "Maven version."]
     pub version: String,
+    #[doc = "Maven classifier, such as `linuxathena` or `javadoc`."]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classifier: Option<String>,
+    #[doc = "Maven packaging, used as the file extension. Usually `jar`, but may be `aar` or `pom`."]
+    #[serde(
+        default = "__private::default_packaging",
+        skip_serializing_if = "__private::is_default_packaging"
+    )]
+    pub packaging: String,
+    #[doc = "Repositories to fetch this artifact from instead of [`VendorDep::maven_urls`], for vendordeps that publish one artifact elsewhere. "]
+    #[doc = "Not part of the format GradleRIO reads; absent to use the vendordep's repositories."]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maven_urls: Option<Vec<String>>,
 }
 
 impl JavaDependency {
+    #[doc = "[`Self::maven_urls`] if set, and otherwise `vendordep_urls` (usually [`VendorDep::maven_urls`])."]
+    pub fn effective_maven_urls<'a>(&'a self, vendordep_urls: &'a [String]) -> &'a [String] {
+        self.maven_urls.as_deref().unwrap_or(vendordep_urls)
+    }
+
+    #[doc = "The platform this jar is built for, if its [`Self::classifier`] names one, such as `linuxathena`. Other classifiers, and no classifier, mean the jar is platform-agnostic."]
+    pub fn platform(&self) -> Option<BinaryPlatform> {
+        match self.classifier.as_deref()?.parse() {
+            Ok(BinaryPlatform::Other(_) | BinaryPlatform::Headers) => None,
+            Ok(platform) => Some(platform),
+            Err(e) => match e {},
+        }
+    }
+
+    #[doc = "Whether this jar belongs on `platform`: it is platform-agnostic, or built for `platform` or one of its [`BinaryPlatform::fallbacks`]."]
+    pub fn supports_platform(&self, platform: &BinaryPlatform) -> bool {
+        match self.platform() {
+            Some(x) => platform.fallbacks().contains(&x),
+            None => true,
+        }
+    }
+
     #[doc = "Resolve Maven URL."]
     pub fn get_url(&self, maven_url: &str) -> String {
         format!(
@@ -56,22 +236,118 @@ impl JavaDependency {
         )
     }
 
-    #[doc = "Get name of jar file."]
+    #[doc = "Path of the artifact once downloaded into `root`, as by [`VendorDep::download_all_java_deps_to_folder`]. Nothing is read or fetched."]
+    pub fn expected_paths<P: AsRef<Path>>(&self, root: P) -> Vec<PathBuf> {
+        vec![root.as_ref().join(self.file_name())]
+    }
+
+    #[doc = "Get name of artifact file. This is a `.jar` unless [`Self::packaging`] says otherwise."]
     pub fn file_name(&self) -> String {
-        format!("{}-{}.jar", self.artifact_id, self.version)
+        match &self.classifier {
+            Some(classifier) => format!(
+                "{}-{}-{}.{}",
+                self.artifact_id, self.version, classifier, self.packaging
+            ),
+            None => format!("{}-{}.{}", self.artifact_id, self.version, self.packaging),
+        }
+    }
+
+    #[doc = "Resolve Maven URL of the POM."]
+    pub fn pom_url(&self, maven_url: &str) -> String {
+        format!(
+            "{0}{1}/{2}/{3}/{4}",
+            maven_url,
+            self.group_id.replace('.', "/"),
+            self.artifact_id,
+            self.version,
+            self.pom_file_name()
+        )
+    }
+
+    #[doc = "Get name of POM file."]
+    pub fn pom_file_name(&self) -> String {
+        format!("{}-{}.pom", self.artifact_id, self.version)
+    }
+
+    #[doc = "Resolve Maven URL of the sources jar."]
+    pub fn sources_url(&self, maven_url: &str) -> String {
+        format!(
+            "{0}{1}/{2}/{3}/{4}",
+            maven_url,
+            self.group_id.replace('.', "/"),
+            self.artifact_id,
+            self.version,
+            self.sources_file_name()
+        )
+    }
+
+    #[doc = "Get name of sources jar file."]
+    pub fn sources_file_name(&self) -> String {
+        format!("{}-{}-sources.jar", self.artifact_id, self.version)
     }
 
     #[cfg(feature = "download")]
-    #[doc = "Download Maven artifact and save it in a directory."]
+    #[doc = "Download the sources jar and save it in a directory. Returns `None` if the artifact does not publish sources."]
+    pub async fn download_sources_to_folder<P: AsRef<Path>>(
+        &self,
+        out_folder: P,
+        maven_url: &str,
+    ) -> Result<Option<PathBuf>> {
+        let url = self.sources_url(maven_url);
+        log_debug!("Downloading {}", url);
+        let config = DownloadConfig::default();
+        let Some(res) = fetch::fetch_bytes_if_exists(&url, &config).await? else {
+            return Ok(None);
+        };
+        fetch::ensure_archive(&url, &res)?;
+        let out_path = out_folder.as_ref().join(self.sources_file_name());
+        config.sink.put(&out_path, &mut res.as_slice(), None)?;
+        Ok(Some(out_path))
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Download Maven artifact and save it in a directory. Archive packagings such as `jar` are checked to actually be zip archives."]
     pub async fn download_library_to_folder<P: AsRef<Path>>(
         &self,
         out_folder: P,
         maven_url: &str,
+    ) -> Result<()> {
+        self.download_library_to_folder_with_config(
+            out_folder,
+            maven_url,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_library_to_folder`], with additional options."]
+    pub async fn download_library_to_folder_with_config<P: AsRef<Path>>(
+        &self,
+        out_folder: P,
+        maven_url: &str,
+        config: &DownloadConfig,
     ) -> Result<()> {
         let url = self.get_url(maven_url);
-        let res = reqwest::get(url).await?.bytes().await?.to_vec();
-        _ = std::fs::create_dir_all(out_folder.as_ref());
-        std::fs::write(out_folder.as_ref().join(self.file_name()), res)?;
+        log_debug!("Downloading {}", url);
+        let out_path = out_folder.as_ref().join(self.file_name());
+        let cached = if config.sink.exists(&out_path) {
+            fetch::Validators::cached(&out_path, config)
+        } else {
+            None
+        };
+        let Some((res, validators)) =
+            fetch::fetch_bytes_if_modified(&url, cached.as_ref(), config).await?
+        else {
+            log_debug!("{} is not modified", url);
+            return Ok(());
+        };
+        if matches!(self.packaging.as_str(), "jar" | "aar" | "war" | "zip") {
+            fetch::ensure_archive(&url, &res)?;
+        }
+        config.tally.downloaded(res.len() as u64, 0);
+        config.sink.put(&out_path, &mut res.as_slice(), None)?;
+        validators.store(&out_path, config)?;
         Ok(())
     }
 }
@@ -94,23 +370,155 @@ pub struct JniDependency {
     // Idk what this does
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sim_mode: Option<String>,
+    #[doc = "Classifier of the artifacts, for artifacts that don't follow `{platform}{debug}`. See [`Self::classifier`]. "]
+    #[doc = "Not part of the format GradleRIO reads; absent for WPILib's own convention."]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classifier_template: Option<String>,
+    #[doc = "Repositories to fetch this artifact from instead of [`VendorDep::maven_urls`], for vendordeps that publish one artifact elsewhere. "]
+    #[doc = "Not part of the format GradleRIO reads; absent to use the vendordep's repositories."]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maven_urls: Option<Vec<String>>,
 }
 
 impl JniDependency {
+    #[doc = "[`Self::maven_urls`] if set, and otherwise `vendordep_urls` (usually [`VendorDep::maven_urls`])."]
+    pub fn effective_maven_urls<'a>(&'a self, vendordep_urls: &'a [String]) -> &'a [String] {
+        self.maven_urls.as_deref().unwrap_or(vendordep_urls)
+    }
+
+    #[doc = "Whether this dependency is published for `platform`, or for a platform that [`BinaryPlatform::covers`] it, such as `osxuniversal` for `osxarm64`. "]
+    #[doc = "An empty [`Self::valid_platforms`] means every platform."]
+    pub fn supports_platform(&self, platform: &BinaryPlatform) -> bool {
+        self.published_platform(platform).is_some()
+    }
+
+    #[doc = "The platform whose artifact to download for `platform`: `platform` itself if it is listed in [`Self::valid_platforms`] or nothing is listed, "]
+    #[doc = "and otherwise the most specific listed platform that [`BinaryPlatform::covers`] it. `None` if the dependency isn't published for `platform`."]
+    pub fn published_platform(&self, platform: &BinaryPlatform) -> Option<BinaryPlatform> {
+        published_platform(&self.valid_platforms, platform)
+    }
+
     #[doc = "Resolve Maven URL."]
     pub fn get_url(&self, maven_url: &str, platform: &str, is_debug: bool) -> String {
+        self.packaged_url(maven_url, platform, is_debug, self.is_jar)
+    }
+
+    // `get_url` as if `is_jar` were `is_jar`.
+    fn packaged_url(
+        &self,
+        maven_url: &str,
+        platform: &str,
+        is_debug: bool,
+        is_jar: bool,
+    ) -> String {
         format!(
-            "{0}{1}/{2}/{3}/{2}-{3}-{4}{5}.{6}",
+            "{0}{1}/{2}/{3}/{4}",
             maven_url,
             self.group_id.replace('.', "/"),
             self.artifact_id,
             self.version,
+            self.packaged_file_name(platform, is_debug, is_jar)
+        )
+    }
+
+    #[doc = "Get name of artifact file for `platform`, such as `tools-24.3.0-linuxx86-64debug.zip`."]
+    pub fn file_name(&self, platform: &str, is_debug: bool) -> String {
+        self.packaged_file_name(platform, is_debug, self.is_jar)
+    }
+
+    fn packaged_file_name(&self, platform: &str, is_debug: bool, is_jar: bool) -> String {
+        format!(
+            "{}-{}-{}.{}",
+            self.artifact_id,
+            self.version,
+            self.classifier(platform, is_debug),
+            if is_jar { "jar" } else { "zip" },
+        )
+    }
+
+    #[doc = "Classifier of the artifact for `platform`, such as `linuxx86-64debug`. Follows [`Self::classifier_template`] if set, "]
+    #[doc = "replacing `{platform}` with the platform name and `{debug}` with `debug` for debug builds (and nothing otherwise). `{static}` is always replaced with nothing."]
+    pub fn classifier(&self, platform: &str, is_debug: bool) -> String {
+        apply_classifier_template(
+            self.classifier_template.as_deref(),
             platform,
-            if is_debug { "debug" } else { "" },
-            if self.is_jar { "jar" } else { "zip" },
+            false,
+            is_debug,
+        )
+    }
+
+    #[doc = "Directory the artifact is extracted to for `platform` when downloaded into `root`, as by [`VendorDep::download_all_jni_deps_to_folder`]. "]
+    #[doc = "What ends up inside depends on the archive, so only the directory is returned, and nothing if the dependency isn't published for `platform`. Nothing is read or fetched."]
+    pub fn expected_paths<P: AsRef<Path>>(
+        &self,
+        root: P,
+        platform: &BinaryPlatform,
+    ) -> Vec<PathBuf> {
+        if self.supports_platform(platform) {
+            vec![root.as_ref().join(&self.artifact_id)]
+        } else {
+            vec![]
+        }
+    }
+
+    #[doc = "Resolve Maven URL of the C headers some JNI artifacts publish with a `headers` classifier, for writing native code against the library."]
+    pub fn headers_url(&self, maven_url: &str) -> String {
+        format!(
+            "{0}{1}/{2}/{3}/{2}-{3}-headers.zip",
+            maven_url,
+            self.group_id.replace('.', "/"),
+            self.artifact_id,
+            self.version,
         )
     }
 
+    #[cfg(feature = "download")]
+    #[doc = "Check which of the release and debug builds for `platform` exist in the repository at `maven_url`, without downloading them. "]
+    #[doc = "JNI dependencies aren't published statically, so the static variants are always reported missing."]
+    pub async fn available_variants(
+        &self,
+        maven_url: &str,
+        platform: &BinaryPlatform,
+    ) -> Result<VariantAvailability> {
+        let config = DownloadConfig::default();
+        let (release, debug) = futures::try_join!(
+            fetch::exists(&self.get_url(maven_url, platform.to_str(), false), &config),
+            fetch::exists(&self.get_url(maven_url, platform.to_str(), true), &config)
+        )?;
+        Ok(VariantAvailability {
+            release,
+            debug,
+            ..Default::default()
+        })
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Find out whether the release build for `platform` is published in the repository at `maven_url` as a `.jar` (`true`) or a `.zip` (`false`), "]
+    #[doc = "whatever [`Self::is_jar`] says, trying `.jar` first. Fails with [`error::Error::NotFoundError`] if it is published as neither. "]
+    #[doc = "Assign the result to [`Self::is_jar`] to fix up a vendordep that guessed wrong, or see [`DownloadConfig::detect_jni_packaging`]."]
+    pub async fn detect_packaging(
+        &self,
+        maven_url: &str,
+        platform: &BinaryPlatform,
+    ) -> Result<bool> {
+        platform.ensure_library_platform()?;
+        let config = DownloadConfig::default();
+        let mut tried_urls = Vec::new();
+        for is_jar in [true, false] {
+            let url = self.packaged_url(maven_url, platform.to_str(), false, is_jar);
+            if fetch::exists(&url, &config).await? {
+                return Ok(is_jar);
+            }
+            tried_urls.push(url);
+        }
+        Err(crate::error::Error::NotFoundError {
+            group_id: self.group_id.clone(),
+            artifact_id: self.artifact_id.clone(),
+            version: self.version.clone(),
+            tried_urls,
+        })
+    }
+
     #[cfg(feature = "download")]
     #[doc = "Download Maven artifact and unzip it to a directory."]
     pub async fn download_library_to_folder<P: AsRef<Path>>(
@@ -120,49 +528,162 @@ impl JniDependency {
         platform: BinaryPlatform,
         is_debug: bool,
     ) -> Result<()> {
-        let url = self.get_url(maven_url, platform.to_str(), is_debug);
-        let res = std::io::Cursor::new(reqwest::get(url).await?.bytes().await?.to_vec());
-        let mut zip = zip::ZipArchive::new(res)?;
-        for i in 0..zip.len() {
-            let mut f = zip.by_index(i)?;
-            if f.name().ends_with("/") {
-                continue;
-            }
-            let outpath = out_folder.as_ref().join(
-                f.enclosed_name()
-                    .ok_or_else(|| error::Error::ZipSecurityError)?,
-            );
-            _ = std::fs::create_dir_all(outpath.parent().unwrap());
-            let mut outf = std::fs::File::create(outpath)?;
-            std::io::copy(&mut f, &mut outf)?;
-        }
+        self.download_library_to_folder_with_config(
+            out_folder,
+            maven_url,
+            platform,
+            is_debug,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_library_to_folder`], with additional options."]
+    pub async fn download_library_to_folder_with_config<P: AsRef<Path>>(
+        &self,
+        out_folder: P,
+        maven_url: &str,
+        platform: BinaryPlatform,
+        is_debug: bool,
+        config: &DownloadConfig,
+    ) -> Result<()> {
+        platform.ensure_library_platform()?;
+        fetch::fetch_and_extract(
+            &self.get_url(maven_url, platform.to_str(), is_debug),
+            out_folder.as_ref(),
+            config,
+        )
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Extract a `.jar` or `.zip` archive already on disk, such as one kept with [`DownloadConfig::keep_archives`] or copied from another machine, "]
+    #[doc = "into a directory the way [`Self::download_library_to_folder`] does after downloading it, without any network access."]
+    pub fn extract_archive_to_folder<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        archive: P,
+        out_folder: Q,
+    ) -> Result<()> {
+        self.extract_archive_to_folder_with_config(archive, out_folder, &DownloadConfig::default())
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::extract_archive_to_folder`], with additional options."]
+    pub fn extract_archive_to_folder_with_config<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        archive: P,
+        out_folder: Q,
+        config: &DownloadConfig,
+    ) -> Result<()> {
+        fetch::extract_local(archive.as_ref(), out_folder.as_ref(), config)?;
         Ok(())
     }
 }
 
+// The classifier of a native artifact for `platform`, following `template` or else WPILib's `{platform}{static}{debug}`.
+fn apply_classifier_template(
+    template: Option<&str>,
+    platform: &str,
+    is_static: bool,
+    is_debug: bool,
+) -> String {
+    template
+        .unwrap_or("{platform}{static}{debug}")
+        .replace("{platform}", platform)
+        .replace("{static}", if is_static { "static" } else { "" })
+        .replace("{debug}", if is_debug { "debug" } else { "" })
+}
+
 macro_rules! binary_platform {
     ($name:ident {$($variant:ident = $val:literal),* $(,)?}) => {
         #[doc = "Valid platforms for WPILib execution."]
-        #[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
         pub enum $name {
             $(
-                #[serde(rename = $val)]
                 $variant,
             )*
+            #[doc = "A platform without a variant of its own, such as a newly introduced coprocessor target."]
+            Other(String),
         }
 
         impl $name {
-            pub fn to_str(&self) -> &'static str {
+            pub fn to_str(&self) -> &str {
                 match self {
                     $(
                         Self::$variant => $val,
                     )*
+                    Self::Other(x) => x,
+                }
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = std::convert::Infallible;
+
+            #[doc = "Parse a platform name. Unrecognized names become [`Self::Other`]."]
+            fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+                Ok(match s {
+                    $(
+                        $val => Self::$variant,
+                    )*
+                    _ => Self::Other(s.to_string()),
+                })
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                match String::deserialize(deserializer)?.parse() {
+                    Ok(x) => Ok(x),
+                    Err(e) => match e {},
                 }
             }
         }
     };
 }
 
+// The first of the `fallbacks` of `platform` that `listed` names, or `platform` if nothing is listed.
+fn published_platform(listed: &[String], platform: &BinaryPlatform) -> Option<BinaryPlatform> {
+    if listed.is_empty() {
+        return Some(platform.clone());
+    }
+    platform
+        .fallbacks()
+        .into_iter()
+        .find(|x| listed.iter().any(|y| y == x.to_str()))
+}
+
+// How a C++ build is linked, for messages.
+#[cfg(feature = "download")]
+fn linkage_name(is_static: bool) -> &'static str {
+    if is_static {
+        "static"
+    } else {
+        "shared"
+    }
+}
+
+// The platforms to try the artifacts of for `platforms`, in order and without repeats, as decided by `published`.
+#[cfg(feature = "download")]
+fn published_platforms(
+    platforms: &[BinaryPlatform],
+    published: impl Fn(&BinaryPlatform) -> Option<BinaryPlatform>,
+) -> Vec<BinaryPlatform> {
+    let mut result = Vec::new();
+    for platform in platforms {
+        let platform = published(platform).unwrap_or_else(|| platform.clone());
+        if !result.contains(&platform) {
+            result.push(platform);
+        }
+    }
+    result
+}
+
 binary_platform!(BinaryPlatform {
     LinuxArm32 = "linuxarm32",
     LinuxArm64 = "linuxarm64",
@@ -174,6 +695,52 @@ binary_platform!(BinaryPlatform {
     Headers = "headers",
 });
 
+impl BinaryPlatform {
+    #[doc = "Platforms whose artifacts can stand in for this one, most specific first, starting with this platform itself. "]
+    #[doc = "Architecture-specific macOS platforms fall back to `osxuniversal`, which covers both Mac architectures."]
+    pub fn fallbacks(&self) -> Vec<BinaryPlatform> {
+        match self {
+            Self::Other(x) if x == "osxarm64" || x == "osxx86-64" => {
+                vec![self.clone(), Self::OsxUniversal]
+            }
+            _ => vec![self.clone()],
+        }
+    }
+
+    #[doc = "Whether artifacts built for this platform run on `requested`: it is `requested` itself or one of its [`Self::fallbacks`], "]
+    #[doc = "so `osxuniversal` covers both `osxarm64` and `osxx86-64`."]
+    pub fn covers(&self, requested: &BinaryPlatform) -> bool {
+        requested.fallbacks().contains(self)
+    }
+
+    #[doc = "Whether this is the roboRIO, the only platform robot code is deployed to."]
+    pub fn is_roborio(&self) -> bool {
+        matches!(self, Self::LinuxAthena)
+    }
+
+    #[doc = "Whether this is a desktop platform that WPILib tools and simulation target."]
+    pub fn is_desktop(&self) -> bool {
+        matches!(
+            self,
+            Self::LinuxX86_64 | Self::OsxUniversal | Self::WindowsArm64 | Self::WindowsX86_64
+        )
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Fail with [`error::Error::NotALibraryPlatform`] for [`Self::Headers`], which only names the header artifacts of C++ dependencies."]
+    pub(crate) fn ensure_library_platform(&self) -> Result<()> {
+        match self {
+            Self::Headers => Err(error::Error::NotALibraryPlatform(self.to_str().to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    #[doc = "Whether robot code can be simulated on this platform: any known platform with binaries other than the roboRIO, including coprocessors."]
+    pub fn is_simulation_capable(&self) -> bool {
+        !matches!(self, Self::LinuxAthena | Self::Headers | Self::Other(_))
+    }
+}
+
 #[doc = "A dependency for C++ compilation."]
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -184,15 +751,47 @@ pub struct CppDependency {
     pub artifact_id: String,
     #[doc = "Maven version."]
     pub version: String,
-    #[doc = "Instead of shipping headers with individual platform artifacts, headers are stored in a separate artifact. This value is used in place of the 'platform' to get the url."]
+    #[doc = "Instead of shipping headers with individual platform artifacts, headers are stored in a separate artifact. This value is used in place of the 'platform' to get the url. "]
+    #[doc = "Empty or absent if the dependency ships no headers."]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub header_classifier: String,
     #[doc = "Platforms this library supports. May be empty."]
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub binary_platforms: Vec<String>,
+    #[doc = "Classifier of the library artifacts, for artifacts that don't follow `{platform}{static}{debug}`. See [`Self::library_classifier`]. "]
+    #[doc = "Not part of the format GradleRIO reads; absent for WPILib's own convention."]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classifier_template: Option<String>,
+    #[doc = "Repositories to fetch this artifact from instead of [`VendorDep::maven_urls`], for vendordeps that publish one artifact elsewhere. "]
+    #[doc = "Not part of the format GradleRIO reads; absent to use the vendordep's repositories."]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maven_urls: Option<Vec<String>>,
+    #[doc = "File extension of the artifacts, such as `tar.gz` for toolchains, if they aren't `.zip` archives. "]
+    #[doc = "Not part of the format GradleRIO reads; absent for `.zip`."]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub packaging: Option<String>,
 }
 
 impl CppDependency {
-    #[doc = "Resolve Maven URL."]
+    #[doc = "[`Self::maven_urls`] if set, and otherwise `vendordep_urls` (usually [`VendorDep::maven_urls`])."]
+    pub fn effective_maven_urls<'a>(&'a self, vendordep_urls: &'a [String]) -> &'a [String] {
+        self.maven_urls.as_deref().unwrap_or(vendordep_urls)
+    }
+
+    #[doc = "Whether this dependency is published for `platform`, or for a platform that [`BinaryPlatform::covers`] it, such as `osxuniversal` for `osxarm64`. "]
+    #[doc = "An empty [`Self::binary_platforms`] means every platform."]
+    pub fn supports_platform(&self, platform: &BinaryPlatform) -> bool {
+        self.published_platform(platform).is_some()
+    }
+
+    #[doc = "The platform whose artifact to download for `platform`: `platform` itself if it is listed in [`Self::binary_platforms`] or nothing is listed, "]
+    #[doc = "and otherwise the most specific listed platform that [`BinaryPlatform::covers`] it. `None` if the dependency isn't published for `platform`."]
+    pub fn published_platform(&self, platform: &BinaryPlatform) -> Option<BinaryPlatform> {
+        published_platform(&self.binary_platforms, platform)
+    }
+
+    #[doc = "Resolve Maven URL of the library artifact for `platform`, with the classifier from [`Self::library_classifier`]. "]
+    #[doc = "For the headers, use [`Self::classifier_url`] with [`Self::header_classifier`]; passing it as `platform` only works without a [`Self::classifier_template`]."]
     pub fn get_url(
         &self,
         maven_url: &str,
@@ -200,45 +799,184 @@ impl CppDependency {
         is_static: bool,
         is_debug: bool,
     ) -> String {
+        self.classifier_url(
+            maven_url,
+            &self.library_classifier(platform, is_static, is_debug),
+        )
+    }
+
+    #[doc = "Resolve the Maven URL of the artifact with an arbitrary classifier, such as a vendor-specific one."]
+    pub fn classifier_url(&self, maven_url: &str, classifier: &str) -> String {
         format!(
-            "{0}{1}/{2}/{3}/{2}-{3}-{4}{5}{6}.zip",
+            "{0}{1}/{2}/{3}/{4}",
             maven_url,
             self.group_id.replace('.', "/"),
             self.artifact_id,
             self.version,
+            self.classifier_file_name(classifier)
+        )
+    }
+
+    #[doc = "Compose the classifier of a library artifact following WPILib's convention, such as `linuxathenastaticdebug`."]
+    pub fn classifier(platform: &str, is_static: bool, is_debug: bool) -> String {
+        apply_classifier_template(None, platform, is_static, is_debug)
+    }
+
+    #[doc = "Classifier of this dependency's library artifact for `platform`. Follows [`Self::classifier_template`] if set, replacing `{platform}` with the platform name, "]
+    #[doc = "`{static}` with `static` for static builds and `{debug}` with `debug` for debug builds (and with nothing otherwise); [`Self::classifier`] if not."]
+    pub fn library_classifier(&self, platform: &str, is_static: bool, is_debug: bool) -> String {
+        apply_classifier_template(
+            self.classifier_template.as_deref(),
             platform,
-            if is_static { "static" } else { "" },
-            if is_debug { "debug" } else { "" }
+            is_static,
+            is_debug,
         )
     }
 
-    #[cfg(feature = "download")]
-    #[doc = "Download Maven artifact and unzip it to a directory."]
-    pub async fn download_library_to_folder<P: AsRef<Path>>(
-        &self,
+    #[doc = "Get name of the library artifact file for `platform`, such as `wpiapi-cpp-24.3.0-linuxathenastaticdebug.zip`. "]
+    #[doc = "For the headers, use [`Self::classifier_file_name`] with [`Self::header_classifier`]."]
+    pub fn file_name(&self, platform: &str, is_static: bool, is_debug: bool) -> String {
+        self.classifier_file_name(&self.library_classifier(platform, is_static, is_debug))
+    }
+
+    #[doc = "Get name of the artifact file with an arbitrary classifier, `<artifact_id>-<version>-<classifier>.zip`, or with the extension from [`Self::packaging`]."]
+    pub fn classifier_file_name(&self, classifier: &str) -> String {
+        format!(
+            "{}-{}-{}.{}",
+            self.artifact_id,
+            self.version,
+            classifier,
+            self.packaging.as_deref().unwrap_or("zip")
+        )
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Check which of the release, debug, static and static debug builds for `platform` exist in the repository at `maven_url`, without downloading them, "]
+    #[doc = "so a downloader can pick a variant that is actually published."]
+    pub async fn available_variants(
+        &self,
+        maven_url: &str,
+        platform: &BinaryPlatform,
+    ) -> Result<VariantAvailability> {
+        let config = DownloadConfig::default();
+        let exists = |is_static, is_debug| {
+            let url = self.get_url(maven_url, platform.to_str(), is_static, is_debug);
+            let config = &config;
+            async move { fetch::exists(&url, config).await }
+        };
+        let (release, debug, static_release, static_debug) = futures::try_join!(
+            exists(false, false),
+            exists(false, true),
+            exists(true, false),
+            exists(true, true)
+        )?;
+        Ok(VariantAvailability {
+            release,
+            debug,
+            static_release,
+            static_debug,
+        })
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Download the artifact with an arbitrary classifier and unzip it to a directory. "]
+    #[doc = "[`Self::download_library_to_folder`] and [`Self::download_headers_to_folder`] are shorthands for the classifiers WPILib publishes."]
+    pub async fn download_classifier_to_folder<P: AsRef<Path>>(
+        &self,
+        out_folder: P,
+        maven_url: &str,
+        classifier: &str,
+    ) -> Result<()> {
+        self.download_classifier_to_folder_with_config(
+            out_folder,
+            maven_url,
+            classifier,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_classifier_to_folder`], with additional options."]
+    pub async fn download_classifier_to_folder_with_config<P: AsRef<Path>>(
+        &self,
+        out_folder: P,
+        maven_url: &str,
+        classifier: &str,
+        config: &DownloadConfig,
+    ) -> Result<()> {
+        fetch::fetch_and_extract(
+            &self.classifier_url(maven_url, classifier),
+            out_folder.as_ref(),
+            config,
+        )
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Extract a library or headers archive already on disk, such as one kept with [`DownloadConfig::keep_archives`] or copied from another machine, "]
+    #[doc = "into a directory the way [`Self::download_classifier_to_folder`] does after downloading it, without any network access."]
+    pub fn extract_archive_to_folder<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        archive: P,
+        out_folder: Q,
+    ) -> Result<()> {
+        self.extract_archive_to_folder_with_config(archive, out_folder, &DownloadConfig::default())
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::extract_archive_to_folder`], with additional options."]
+    pub fn extract_archive_to_folder_with_config<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        archive: P,
+        out_folder: Q,
+        config: &DownloadConfig,
+    ) -> Result<()> {
+        fetch::extract_local(archive.as_ref(), out_folder.as_ref(), config)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Download Maven artifact and unzip it to a directory."]
+    pub async fn download_library_to_folder<P: AsRef<Path>>(
+        &self,
         out_folder: P,
         maven_url: &str,
         platform: BinaryPlatform,
         is_static: bool,
         is_debug: bool,
     ) -> Result<()> {
-        let url = self.get_url(maven_url, platform.to_str(), is_static, is_debug);
-        let res = std::io::Cursor::new(reqwest::get(url).await?.bytes().await?.to_vec());
-        let mut zip = zip::ZipArchive::new(res)?;
-        for i in 0..zip.len() {
-            let mut f = zip.by_index(i)?;
-            if f.name().ends_with("/") {
-                continue;
-            }
-            let outpath = out_folder.as_ref().join(
-                f.enclosed_name()
-                    .ok_or_else(|| error::Error::ZipSecurityError)?,
-            );
-            _ = std::fs::create_dir_all(outpath.parent().unwrap());
-            let mut outf = std::fs::File::create(outpath)?;
-            std::io::copy(&mut f, &mut outf)?;
-        }
-        Ok(())
+        self.download_library_to_folder_with_config(
+            out_folder,
+            maven_url,
+            platform,
+            is_static,
+            is_debug,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_library_to_folder`], with additional options."]
+    pub async fn download_library_to_folder_with_config<P: AsRef<Path>>(
+        &self,
+        out_folder: P,
+        maven_url: &str,
+        platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        config: &DownloadConfig,
+    ) -> Result<()> {
+        platform.ensure_library_platform()?;
+        self.download_classifier_to_folder_with_config(
+            out_folder,
+            maven_url,
+            &self.library_classifier(platform.to_str(), is_static, is_debug),
+            config,
+        )
+        .await
     }
 
     #[cfg(feature = "download")]
@@ -248,26 +986,100 @@ impl CppDependency {
         out_folder: P,
         maven_url: &str,
     ) -> Result<()> {
-        self.download_library_to_folder(
+        self.download_headers_to_folder_with_config(
             out_folder,
             maven_url,
-            BinaryPlatform::Headers,
-            false,
-            false,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_headers_to_folder`], with additional options."]
+    pub async fn download_headers_to_folder_with_config<P: AsRef<Path>>(
+        &self,
+        out_folder: P,
+        maven_url: &str,
+        config: &DownloadConfig,
+    ) -> Result<()> {
+        self.download_classifier_to_folder_with_config(
+            out_folder,
+            maven_url,
+            &self.header_classifier,
+            config,
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_classifier_to_folder`], but keeps the extracted files in memory instead of touching the filesystem. "]
+    #[doc = "Returns the contents of each file keyed by its path within the archive. Symlinks in the archive are left out."]
+    pub async fn download_classifier_to_memory(
+        &self,
+        maven_url: &str,
+        classifier: &str,
+    ) -> Result<HashMap<PathBuf, Vec<u8>>> {
+        let sink = sink::MemorySink::default();
+        let config = DownloadConfig {
+            sink: ArtifactStore::new(sink.clone()),
+            ..Default::default()
+        };
+        self.download_classifier_to_folder_with_config("", maven_url, classifier, &config)
+            .await?;
+        Ok(sink.take())
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_library_to_folder`], but keeps the extracted files in memory. See [`Self::download_classifier_to_memory`]."]
+    pub async fn download_library_to_memory(
+        &self,
+        maven_url: &str,
+        platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+    ) -> Result<HashMap<PathBuf, Vec<u8>>> {
+        platform.ensure_library_platform()?;
+        self.download_classifier_to_memory(
+            maven_url,
+            &self.library_classifier(platform.to_str(), is_static, is_debug),
         )
         .await
     }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_headers_to_folder`], but keeps the extracted files in memory. See [`Self::download_classifier_to_memory`]."]
+    pub async fn download_headers_to_memory(
+        &self,
+        maven_url: &str,
+    ) -> Result<HashMap<PathBuf, Vec<u8>>> {
+        self.download_classifier_to_memory(maven_url, &self.header_classifier)
+            .await
+    }
+
+    #[doc = "Whether this dependency publishes a header artifact, i.e. [`Self::header_classifier`] is not empty."]
+    pub fn has_headers(&self) -> bool {
+        !self.header_classifier.is_empty()
+    }
 }
 
 #[doc = "Info needed for C++ compilation. Retrieved as a result of [`VendorDep::download_all_cpp_deps_to_folder`]."]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CppInfo {
     #[doc = "Root directories containing headers."]
     pub include_dirs: Vec<PathBuf>,
-    #[doc = "Directories containing library objects."]
+    #[doc = "Directories containing libraries to link against: shared objects, and on Windows `.lib` import libraries."]
     pub library_search_paths: Vec<PathBuf>,
+    #[doc = "Directories containing libraries loaded at runtime: shared objects, and on Windows `.dll`s. "]
+    #[doc = "These belong on `LD_LIBRARY_PATH` or `PATH`; see [`Self::runtime_path`]."]
+    #[serde(default)]
+    pub runtime_search_paths: Vec<PathBuf>,
     #[doc = "Library names."]
     pub libraries: Vec<String>,
+    #[doc = "The names in [`Self::libraries`] that are static archives: `.a` files, and Windows `.lib`s without a `.dll`, as static builds ship them. "]
+    #[doc = "[`Self::cargo_directives`] links these with `static=` rather than `dylib=`."]
+    #[serde(default)]
+    pub static_libraries: Vec<String>,
 }
 
 impl CppInfo {
@@ -276,63 +1088,232 @@ impl CppInfo {
         Self {
             include_dirs: vec![],
             library_search_paths: vec![],
+            runtime_search_paths: vec![],
             libraries: vec![],
+            static_libraries: vec![],
+        }
+    }
+
+    #[doc = "Create new [`CppInfo`] for libraries that are already on disk, such as ones linked alongside vendordeps. "]
+    #[doc = "The libraries are loaded at runtime from where they are linked, so [`Self::runtime_search_paths`] is `library_search_paths`."]
+    pub fn new(
+        include_dirs: Vec<PathBuf>,
+        library_search_paths: Vec<PathBuf>,
+        libraries: Vec<String>,
+    ) -> Self {
+        Self {
+            include_dirs,
+            runtime_search_paths: library_search_paths.clone(),
+            library_search_paths,
+            libraries,
+            static_libraries: vec![],
         }
     }
 
+    #[doc = "Add an include directory."]
+    pub fn with_include_dir(mut self, include_dir: impl Into<PathBuf>) -> Self {
+        self.include_dirs.push(include_dir.into());
+        self
+    }
+
+    #[doc = "Add a directory holding libraries, to both [`Self::library_search_paths`] and [`Self::runtime_search_paths`]."]
+    pub fn with_library_search_path(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        self.runtime_search_paths.push(path.clone());
+        self.library_search_paths.push(path);
+        self
+    }
+
+    #[doc = "Add a library to link against, by name as passed to `-l`."]
+    pub fn with_library(mut self, library: impl Into<String>) -> Self {
+        self.libraries.push(library.into());
+        self
+    }
+
     #[cfg(feature = "download")]
     #[doc = "Create new [`CppInfo`] from existing directory structure generated by [`VendorDep::download_all_cpp_deps_to_folder`]."]
     pub fn from_existing<P: AsRef<Path>>(p: P) -> Result<Self> {
-        let p = p.as_ref();
-        let mut include_dirs = Vec::new();
-        let mut library_search_paths = Vec::new();
-        let mut libraries = Vec::new();
-        for item in std::fs::read_dir(p)? {
+        let mut info = Self::new_empty();
+        for item in std::fs::read_dir(p.as_ref())? {
             let item = item?;
-            include_dirs.push(item.path().join("include"));
-            let mut temp_search_paths = HashSet::new();
-            for item in jwalk::WalkDir::new(item.path().join("libs")) {
-                let item = item?;
-                if let Some(stem) = item.path().file_stem() {
-                    let stem = stem.to_string_lossy();
-                    match item.path().extension().and_then(|x| x.to_str()) {
-                        Some("so") => {
-                            temp_search_paths.insert(item.parent_path().to_path_buf());
-                            libraries.push(stem[3..].to_string());
-                        }
-                        Some("dll") => {
-                            temp_search_paths.insert(item.parent_path().to_path_buf());
-                            libraries.push(stem.to_string());
-                        }
-                        _ => {}
-                    }
+            info.include_dirs.push(item.path().join("include"));
+            info.add_libraries_in(&item.path().join("libs"))?;
+            info.add_include_dirs_in(&item.path().join("libs"))?;
+        }
+        Ok(info)
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Record every directory named `include` below `dir`, for library artifacts that bundle their headers. "]
+    #[doc = "`include` directories within one that was already found are left out."]
+    fn add_include_dirs_in(&mut self, dir: &Path) -> Result<()> {
+        if !dir.exists() {
+            return Ok(());
+        }
+        let mut found = Vec::new();
+        for item in jwalk::WalkDir::new(dir) {
+            let item = item?;
+            if item.file_type().is_dir() && item.file_name() == "include" {
+                found.push(item.path());
+            }
+        }
+        found.sort();
+        for path in found {
+            if !self.include_dirs.iter().any(|x| path.starts_with(x)) {
+                self.include_dirs.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Record every library below `dir` at any depth, as classified by [`classify_native`], so natives that jars nest under package directories are found too. Shared objects are both linked against and loaded at runtime, and static archives are only linked. "]
+    #[doc = "Windows artifacts ship a runtime `.dll` next to its `.lib` import library, so those are sorted into [`Self::runtime_search_paths`] and [`Self::library_search_paths`] respectively."]
+    fn add_libraries_in(&mut self, dir: &Path) -> Result<()> {
+        // Nothing to find if an `ArtifactSink` put the libraries somewhere else.
+        if !dir.exists() {
+            return Ok(());
+        }
+        let mut link_paths = HashSet::new();
+        let mut runtime_paths = HashSet::new();
+        let mut archives = Vec::new();
+        let mut dlls = HashSet::new();
+        for item in jwalk::WalkDir::new(dir) {
+            let item = item?;
+            let path = item.path();
+            let Some((kind, name)) = classify_native(&path) else {
+                continue;
+            };
+            let parent = item.parent_path().to_path_buf();
+            match kind {
+                // A `.dll` is linked through its `.lib` import library instead.
+                NativeKind::Shared if path.extension().is_some_and(|x| x == "dll") => {
+                    runtime_paths.insert(parent);
+                    dlls.insert(name.clone());
+                }
+                NativeKind::Shared => {
+                    link_paths.insert(parent.clone());
+                    runtime_paths.insert(parent);
+                }
+                NativeKind::Static | NativeKind::Import => {
+                    link_paths.insert(parent);
+                    archives.push((kind, name.clone()));
                 }
             }
-            library_search_paths.extend(temp_search_paths);
+            if !self.libraries.contains(&name) {
+                self.libraries.push(name);
+            }
         }
-        Ok(Self {
-            include_dirs,
-            library_search_paths,
-            libraries,
-        })
+        // An import library comes with its `.dll`, so a `.lib` without one is a static archive.
+        for (kind, name) in archives {
+            if (kind == NativeKind::Static || !dlls.contains(&name))
+                && !self.static_libraries.contains(&name)
+            {
+                self.static_libraries.push(name);
+            }
+        }
+        self.library_search_paths.extend(link_paths);
+        self.runtime_search_paths.extend(runtime_paths);
+        Ok(())
+    }
+
+    #[doc = "Remove repeated include directories, library search paths, libraries and static libraries, keeping the first occurrence of each."]
+    pub fn dedup(&mut self) {
+        let mut seen = HashSet::new();
+        self.include_dirs.retain(|x| seen.insert(x.clone()));
+        let mut seen = HashSet::new();
+        self.library_search_paths.retain(|x| seen.insert(x.clone()));
+        let mut seen = HashSet::new();
+        self.runtime_search_paths.retain(|x| seen.insert(x.clone()));
+        let mut seen = HashSet::new();
+        self.libraries.retain(|x| seen.insert(x.clone()));
+        let mut seen = HashSet::new();
+        self.static_libraries.retain(|x| seen.insert(x.clone()));
+    }
+
+    #[doc = "Resolve every include directory, library search path and runtime search path to an absolute path with symlinks resolved, "]
+    #[doc = "so that compiler and linker flags work from any working directory. Paths that don't exist are removed; see [`Self::canonicalize_strict`] to fail instead. "]
+    #[doc = "Paths that were spelled differently may become equal, which [`Self::dedup`] cleans up."]
+    pub fn canonicalize(&mut self) -> Result<()> {
+        self.canonicalize_paths(false)
+    }
+
+    #[doc = "Like [`Self::canonicalize`], but fail with [`error::Error::IoError`] on the first path that doesn't exist, leaving `self` unchanged."]
+    pub fn canonicalize_strict(&mut self) -> Result<()> {
+        self.canonicalize_paths(true)
+    }
+
+    fn canonicalize_paths(&mut self, strict: bool) -> Result<()> {
+        let canonicalize = |paths: &[PathBuf]| -> Result<Vec<PathBuf>> {
+            let mut canonical = Vec::with_capacity(paths.len());
+            for path in paths {
+                match std::fs::canonicalize(path) {
+                    Ok(x) => canonical.push(x),
+                    Err(e) if !strict && e.kind() == std::io::ErrorKind::NotFound => {
+                        log_debug!("Dropping {}, which does not exist", path.display());
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            Ok(canonical)
+        };
+        let include_dirs = canonicalize(&self.include_dirs)?;
+        let library_search_paths = canonicalize(&self.library_search_paths)?;
+        let runtime_search_paths = canonicalize(&self.runtime_search_paths)?;
+        self.include_dirs = include_dirs;
+        self.library_search_paths = library_search_paths;
+        self.runtime_search_paths = runtime_search_paths;
+        Ok(())
     }
 
     #[doc = "Combine another [`CppInfo`] value into this one."]
     pub fn extend(&mut self, other: Self) {
         self.include_dirs.extend(other.include_dirs);
         self.library_search_paths.extend(other.library_search_paths);
+        self.runtime_search_paths.extend(other.runtime_search_paths);
         self.libraries.extend(other.libraries);
+        self.static_libraries.extend(other.static_libraries);
     }
 
     #[doc = "Get `LD_LIBRARY_PATH` environment variable for runtime linking."]
     pub fn ld_library_path(&self) -> String {
-        self.library_search_paths
+        self.runtime_search_paths
             .iter()
             .map(|x| format!("{}", x.display()))
             .collect::<Vec<_>>()
             .join(":")
     }
 
+    #[doc = "Get the directories to add to `PATH` on Windows, or `LD_LIBRARY_PATH` elsewhere, so the libraries can be loaded at runtime."]
+    pub fn runtime_path(&self) -> String {
+        join_search_path(&self.runtime_search_paths)
+    }
+
+    #[doc = "Environment variables for a child process running on `platform` that loads the libraries at runtime, such as a simulation or test harness: "]
+    #[doc = "`PATH` on Windows, `DYLD_LIBRARY_PATH` on macOS and `LD_LIBRARY_PATH` elsewhere, holding this process's value of the variable followed by [`Self::runtime_search_paths`], "]
+    #[doc = "joined with the separator of `platform`. Empty if there are no runtime search paths. A JVM also needs [`JniInfo::jvm_arg`] on its command line."]
+    pub fn runtime_env(&self, platform: BinaryPlatform) -> HashMap<String, String> {
+        let mut env = HashMap::new();
+        if self.runtime_search_paths.is_empty() {
+            return env;
+        }
+        let (var, separator) = match platform.to_str() {
+            x if x.starts_with("windows") => ("PATH", ";"),
+            x if x.starts_with("osx") => ("DYLD_LIBRARY_PATH", ":"),
+            _ => ("LD_LIBRARY_PATH", ":"),
+        };
+        let inherited = std::env::var_os(var)
+            .map(|x| x.to_string_lossy().into_owned())
+            .filter(|x| !x.is_empty());
+        let paths = inherited.into_iter().chain(
+            self.runtime_search_paths
+                .iter()
+                .map(|x| format!("{}", x.display())),
+        );
+        env.insert(var.to_string(), paths.collect::<Vec<_>>().join(separator));
+        env
+    }
+
     #[doc = "Get command line arguments passed to either `gcc` or `clang` for include directories."]
     pub fn gcc_clang_include_dir_args<'a>(&'a self) -> impl Iterator<Item = String> + 'a {
         self.include_dirs
@@ -359,280 +1340,4646 @@ impl CppInfo {
             .chain(self.gcc_clang_library_search_path_args())
             .chain(self.gcc_clang_library_args())
     }
-}
 
-#[doc = "Vendor Dependency Format."]
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct VendorDep {
-    #[doc = "File name that GradleRIO will write to `vendordeps/` directory."]
-    pub file_name: String,
-    #[doc = "Name of vendor library."]
-    pub name: String,
-    #[doc = "Vendor library version. Usually is the same as each artifact's Maven version."]
-    pub version: String,
-    #[doc = "Supported year."]
-    #[serde(deserialize_with = "__private::deserialize_string_or_u32_for_u32")]
-    pub frc_year: u32,
-    #[doc = "UUID used for checking compatibility."]
-    pub uuid: String,
-    #[doc = "List of Maven repositories to search for Maven artifacts."]
-    pub maven_urls: Vec<String>,
-    #[doc = "URL for this. If up to date, the contents of the url should reproduce this [`VendorDep`] value."]
-    pub json_url: String,
-    #[doc = "A list of other [`VendorDep`]s this is explicitly incompatible with. Generally this includes older versions which would introduce name collisions."]
-    #[serde(default)]
-    pub conflicts_with: Vec<PackageSpec>,
-    #[doc = "A list of Java source dependencies."]
-    pub java_dependencies: Vec<JavaDependency>,
-    #[doc = "A list of Java native dependencies."]
-    pub jni_dependencies: Vec<JniDependency>,
-    #[doc = "A list of C++ dependencies."]
-    pub cpp_dependencies: Vec<CppDependency>,
-}
+    #[doc = "Get command line arguments passed to MSVC (`cl.exe`) for include directories."]
+    pub fn msvc_include_args<'a>(&'a self) -> impl Iterator<Item = String> + 'a {
+        self.include_dirs
+            .iter()
+            .map(|x| format!("/I{}", x.display()))
+    }
 
-impl VendorDep {
-    #[cfg(feature = "download")]
-    #[doc = "Download JSON from url and parse it."]
-    pub async fn from_url(url: &str) -> Result<Self> {
-        Ok(reqwest::get(url).await?.json::<Self>().await?)
+    #[doc = "Get command line arguments passed to the MSVC linker (`link.exe`) for library search paths."]
+    pub fn msvc_library_path_args<'a>(&'a self) -> impl Iterator<Item = String> + 'a {
+        self.library_search_paths
+            .iter()
+            .map(|x| format!("/LIBPATH:{}", x.display()))
     }
 
-    #[cfg(feature = "download")]
-    #[doc = "Download all cpp dependencies. Directory structure follows `<output_folder>/<cpp_dependency_name>/(lib|include)`."]
-    pub async fn download_all_cpp_deps_to_folder<P: AsRef<Path>>(
-        &self,
-        p: P,
-        binary_platform: BinaryPlatform,
-        is_static: bool,
-        is_debug: bool,
-        skip_failed_packages: bool,
-    ) -> Result<CppInfo> {
-        let path = p.as_ref();
-        let mut include_dirs = Vec::new();
-        let mut library_search_paths = Vec::new();
-        let mut libraries = Vec::new();
-        for dep in &self.cpp_dependencies {
-            let dep_path = path.join(&dep.artifact_id);
-            let header_path = dep_path.join("include");
-            'outer: loop {
-                for maven_url in &self.maven_urls {
-                    match dep
-                        .download_headers_to_folder(&header_path, maven_url.as_str())
-                        .await
-                    {
-                        Ok(_) => break 'outer,
-                        _ => {}
-                    }
-                }
-                if !skip_failed_packages {
-                    return Err(crate::error::Error::NotFoundError(format!(
-                        "{}:{}:{}",
-                        dep.group_id, dep.artifact_id, dep.version
-                    )));
-                }
-            }
-            include_dirs.push(header_path);
-            let libs_path = dep_path.join("libs");
-            'outer: loop {
-                for maven_url in &self.maven_urls {
-                    match dep
-                        .download_library_to_folder(
-                            &libs_path,
-                            maven_url.as_str(),
-                            binary_platform,
-                            is_static,
-                            is_debug,
-                        )
-                        .await
-                    {
-                        Ok(_) => break 'outer,
-                        _ => {}
-                    }
-                }
-                if !skip_failed_packages {
-                    return Err(crate::error::Error::NotFoundError(format!(
-                        "{}:{}:{}",
-                        dep.group_id, dep.artifact_id, dep.version
-                    )));
+    #[doc = "Get command line arguments passed to the MSVC linker (`link.exe`) for libraries."]
+    pub fn msvc_library_args<'a>(&'a self) -> impl Iterator<Item = String> + 'a {
+        self.libraries.iter().map(|x| format!("{}.lib", x))
+    }
+
+    #[doc = "Get the `cargo:` directives a build script prints to link against these libraries, "]
+    #[doc = "linking [`Self::static_libraries`] with `static=` and the rest with `dylib=`. See [`Self::emit_cargo_directives`]."]
+    pub fn cargo_directives(&self) -> Vec<String> {
+        self.library_search_paths
+            .iter()
+            .map(|x| format!("cargo:rustc-link-search=native={}", x.display()))
+            .chain(self.libraries.iter().map(|x| {
+                let kind = if self.static_libraries.contains(x) {
+                    "static"
+                } else {
+                    "dylib"
+                };
+                format!("cargo:rustc-link-lib={}={}", kind, x)
+            }))
+            .chain(
+                self.include_dirs
+                    .iter()
+                    .chain(self.library_search_paths.iter())
+                    .map(|x| format!("cargo:rerun-if-changed={}", x.display())),
+            )
+            .collect()
+    }
+
+    #[doc = "Print `cargo:rustc-link-search`, `cargo:rustc-link-lib`, and `cargo:rerun-if-changed` directives for use from a `build.rs`."]
+    pub fn emit_cargo_directives(&self) {
+        for directive in self.cargo_directives() {
+            println!("{}", directive);
+        }
+    }
+
+    #[cfg(feature = "cc")]
+    #[doc = "Add the include directories to a `cc` build, for compiling C++ shims against these dependencies from a `build.rs`. "]
+    #[doc = "`cc` only compiles, so link against the libraries with [`Self::emit_cargo_directives`]."]
+    pub fn configure_cc(&self, build: &mut cc::Build) {
+        for dir in &self.include_dirs {
+            build.include(dir);
+        }
+    }
+
+    #[doc = "Generate a fragment of VS Code's `.vscode/c_cpp_properties.json` containing the `includePath` for these include directories. "]
+    #[doc = "Merge the result into an entry of `configurations`. If `recursive` is true, each path is suffixed with `/**` so subdirectories are searched as well."]
+    pub fn to_vscode_cpp_properties(&self, recursive: bool) -> serde_json::Value {
+        let include_path = self
+            .include_dirs
+            .iter()
+            .map(|x| {
+                let path = x.display().to_string();
+                if recursive {
+                    serde_json::Value::String(format!("{}/**", path))
+                } else {
+                    serde_json::Value::String(path)
                 }
+            })
+            .collect::<Vec<_>>();
+        serde_json::json!({ "includePath": include_path })
+    }
+
+    #[doc = "Generate a CMake script defining an `INTERFACE IMPORTED` target named `target_name` with these include directories, library search paths, and libraries. "]
+    #[doc = "The result is meant to be written to a file and pulled in with `include()`."]
+    pub fn to_cmake(&self, target_name: &str) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("if(NOT TARGET {0})\n", target_name));
+        out.push_str(&format!(
+            "  add_library({0} INTERFACE IMPORTED)\n",
+            target_name
+        ));
+        let sections: [(&str, Vec<String>); 3] = [
+            (
+                "target_include_directories",
+                self.include_dirs
+                    .iter()
+                    .map(|x| cmake_quote_path(x))
+                    .collect(),
+            ),
+            (
+                "target_link_directories",
+                self.library_search_paths
+                    .iter()
+                    .map(|x| cmake_quote_path(x))
+                    .collect(),
+            ),
+            (
+                "target_link_libraries",
+                self.libraries.iter().map(|x| cmake_quote(x)).collect(),
+            ),
+        ];
+        for (command, items) in sections {
+            if items.is_empty() {
+                continue;
             }
-            let mut temp_search_paths = HashSet::new();
-            for item in jwalk::WalkDir::new(libs_path) {
-                let item = item?;
-                if let Some(stem) = item.path().file_stem() {
-                    let stem = stem.to_string_lossy();
-                    match item.path().extension().and_then(|x| x.to_str()) {
-                        Some("so") => {
-                            temp_search_paths.insert(item.parent_path().to_path_buf());
-                            libraries.push(stem[3..].to_string());
-                        }
-                        Some("dll") => {
-                            temp_search_paths.insert(item.parent_path().to_path_buf());
-                            libraries.push(stem.to_string());
-                        }
-                        _ => {}
-                    }
-                }
+            out.push_str(&format!("  {0}({1} INTERFACE\n", command, target_name));
+            for item in items {
+                out.push_str(&format!("    {0}\n", item));
             }
-            library_search_paths.extend(temp_search_paths);
+            out.push_str("  )\n");
         }
-        Ok(CppInfo {
-            include_dirs,
-            library_search_paths,
-            libraries,
-        })
+        out.push_str("endif()\n");
+        out
+    }
+
+    #[doc = "Generate the contents of a pkg-config `.pc` file named `name` at `version`. "]
+    #[doc = "`Cflags` is built from [`Self::include_dirs`] and `Libs` from [`Self::library_search_paths`] and [`Self::libraries`]."]
+    pub fn to_pkg_config(&self, name: &str, version: &str) -> String {
+        let cflags = self
+            .include_dirs
+            .iter()
+            .map(|x| format!("-I{}", pkg_config_quote(&x.display().to_string())))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let libs = self
+            .library_search_paths
+            .iter()
+            .map(|x| format!("-L{}", pkg_config_quote(&x.display().to_string())))
+            .chain(
+                self.libraries
+                    .iter()
+                    .map(|x| format!("-l{}", pkg_config_quote(x))),
+            )
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!(
+            "Name: {0}\nDescription: {0} libraries downloaded by vendordeps\nVersion: {1}\nCflags: {2}\nLibs: {3}\n",
+            name, version, cflags, libs
+        )
+    }
+}
+
+// Escape a value for a `.pc` file.
+fn pkg_config_quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            // `$` starts a variable reference in `.pc` files.
+            '$' => out.push_str("$$"),
+            c if c.is_whitespace() || matches!(c, '\\' | '"' | '\'' | '#') => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// Quote a value as a CMake string argument.
+fn cmake_quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        if matches!(c, '\\' | '"' | '$' | ';') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    out
+}
+
+fn cmake_quote_path(p: &Path) -> String {
+    // CMake accepts forward slashes on every platform, and backslashes would otherwise need escaping.
+    cmake_quote(&p.display().to_string().replace('\\', "/"))
+}
+
+// Join paths with the separator of `PATH`-like variables on this platform.
+fn join_search_path(paths: &[PathBuf]) -> String {
+    let separator = if cfg!(windows) { ";" } else { ":" };
+    paths
+        .iter()
+        .map(|x| format!("{}", x.display()))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+#[doc = "Join jars, such as those returned by [`VendorDep::download_all_java_deps_to_folder`], into a classpath for `java -cp`, using `;` on Windows and `:` elsewhere. "]
+#[doc = "The native side goes alongside it as [`JniInfo::jvm_arg`]."]
+pub fn java_classpath(jars: &[PathBuf]) -> String {
+    join_search_path(jars)
+}
+
+#[doc = "Native libraries for the JVM, as needed to launch a Java simulation. Converted from the [`CppInfo`] returned by [`VendorDep::download_all_jni_deps_to_folder`]."]
+#[derive(Debug, Clone)]
+pub struct JniInfo {
+    #[doc = "Directories containing native libraries."]
+    pub library_dirs: Vec<PathBuf>,
+    #[doc = "Base names of the native libraries, as passed to `System.loadLibrary`."]
+    pub library_names: Vec<String>,
+}
+
+impl JniInfo {
+    #[doc = "Get the value of the `java.library.path` system property, joined with the platform's path separator."]
+    pub fn java_library_path(&self) -> String {
+        join_search_path(&self.library_dirs)
+    }
+
+    #[doc = "Get the `-Djava.library.path=...` argument to pass to `java`."]
+    pub fn jvm_arg(&self) -> String {
+        format!("-Djava.library.path={}", self.java_library_path())
+    }
+}
+
+impl From<CppInfo> for JniInfo {
+    fn from(value: CppInfo) -> Self {
+        let mut library_names = Vec::new();
+        for name in value.libraries {
+            if !library_names.contains(&name) {
+                library_names.push(name);
+            }
+        }
+        Self {
+            library_dirs: value.runtime_search_paths,
+            library_names,
+        }
+    }
+}
+
+#[cfg(feature = "download")]
+#[doc = "Options for the `*_with_config` download methods. The methods without a config use [`DownloadConfig::default`]. See [`Downloader`] to set them once for many vendordeps."]
+#[derive(Debug, Clone, Default)]
+pub struct DownloadConfig {
+    #[doc = "How downloaded archives are extracted."]
+    pub extract: ExtractOptions,
+    #[doc = "Limits on concurrent requests and request rate, shared by the dependencies the `download_all_*` methods download concurrently. Unlimited by default."]
+    pub throttle: Throttle,
+    #[doc = "Try the [`BinaryPlatform::fallbacks`] of the requested platform when a JNI or C++ dependency isn't published for it."]
+    pub platform_fallbacks: bool,
+    #[doc = "Checked between dependencies and between extracted files. When cancelled, the directory of the dependency being extracted is removed and the download fails with [`error::Error::Cancelled`]."]
+    pub cancel: CancellationToken,
+    #[doc = "Record the `ETag` and `Last-Modified` headers of each artifact in a `.meta` file next to it (next to the extracted files for archives), "]
+    #[doc = "and send them back as `If-None-Match` and `If-Modified-Since` on the next download, keeping the existing copy if the server answers `304 Not Modified`. "]
+    #[doc = "Meant for mirrors that re-sync periodically. Off by default."]
+    pub conditional: bool,
+    #[doc = "With [`Self::conditional`], download every artifact in full regardless of the recorded headers, recording fresh ones."]
+    pub force: bool,
+    #[doc = "Where C++ headers and libraries are extracted to within the output folder. If dependencies share a directory and two of them provide the same file, "]
+    #[doc = "the download fails with [`error::Error::FileCollision`] instead of one overwriting the other."]
+    pub layout: LayoutStrategy,
+    #[doc = "Also download the headers of JNI dependencies where a `headers` artifact is published (see [`JniDependency::headers_url`]), "]
+    #[doc = "adding them to the `include_dirs` of the returned [`CppInfo`]. Off by default, since it costs a request per dependency and repository."]
+    pub jni_headers: bool,
+    #[doc = "Only download the Java dependencies that [`JavaDependency::supports_platform`] this platform, leaving out jars classified for other platforms. "]
+    #[doc = "Platform-agnostic jars are always downloaded. Unset by default, downloading every Java dependency."]
+    pub java_platform: Option<BinaryPlatform>,
+    #[doc = "Counts artifacts downloaded and skipped, files extracted and bytes downloaded. Keep a clone to read the [`DownloadSummary`] afterwards."]
+    pub tally: DownloadTally,
+    #[doc = "Where downloaded files are written. The local filesystem by default; see [`ArtifactSink`]."]
+    pub sink: ArtifactStore,
+    #[doc = "Fail with [`error::Error::EmptyDependency`] when a C++ dependency downloads fine but contributes no include directories and no libraries to the [`CppInfo`], "]
+    #[doc = "which usually means its archives don't hold what they should. Skipped dependencies don't count. Off by default."]
+    pub strict: bool,
+    #[doc = "How transient failures are retried against the same repository before trying the next one. Retries twice by default."]
+    pub retry: RetryPolicy,
+    #[doc = "Extract each archive into a hidden sibling of its directory and move it into place only once extraction succeeds, so that other processes, "]
+    #[doc = "such as an IDE indexer, never see a partially extracted dependency; a failed or cancelled download leaves the previous copy untouched. "]
+    #[doc = "The directory is replaced as a whole, so each archive needs a directory of its own, as with the default [`Self::layout`], and [`ExtractOptions::policy`] "]
+    #[doc = "has nothing to conflict with. Ignored for layouts that [`LayoutStrategy::shares_directories`]. Only applies to sinks that [`ArtifactSink::can_replace_dirs`], such as the local filesystem. Off by default."]
+    pub atomic: bool,
+    #[doc = "Don't trust [`JniDependency::is_jar`], which vendordeps sometimes get wrong: try each repository for a `.jar`, then for a `.zip`, "]
+    #[doc = "and use whichever exists. Costs an extra request for every artifact published as a `.zip`. Off by default."]
+    pub detect_jni_packaging: bool,
+    #[doc = "`User-Agent` sent with every HTTP request, to identify the tool downloading to mirrors that block or rate-limit anonymous clients. "]
+    #[doc = "`vendordeps/<version>` if unset."]
+    pub user_agent: Option<String>,
+    #[doc = "Additional headers sent with every HTTP request, as `(name, value)` pairs, such as one a proxy expects. "]
+    #[doc = "Requests fail with [`error::Error::InvalidHeader`] if a name or value isn't valid in a header."]
+    pub headers: Vec<(String, String)>,
+    #[doc = "When a dependency lists several Maven repositories, send each a `HEAD` request for the artifact at once and download from the first to report it, "]
+    #[doc = "instead of trying them one after another in the order listed, so a slow repository doesn't hold up downloads another can serve. The other requests are dropped, "]
+    #[doc = "and the remaining repositories are still tried in order if that download fails. Costs a request per repository and artifact. Off by default."]
+    pub race_mirrors: bool,
+    #[doc = "How many redirects an HTTP request follows before failing. `Some(0)` follows none, so a redirect fails with [`error::Error::HttpStatus`] carrying its `3xx` status. "]
+    #[doc = "Redirects that are followed are logged along with the URL they lead to, and [`LockedArtifact::resolved_url`] records it. Unset by default, following up to 10."]
+    pub max_redirects: Option<usize>,
+    #[doc = "Applied to every URL right before it is requested, including `file://` ones. More general than [`VendorDep::with_maven_override`], "]
+    #[doc = "which only swaps a repository prefix. Unset by default."]
+    pub url_rewriter: Option<UrlRewriter>,
+    #[doc = "Also store each downloaded C++ and JNI archive in the folder it is extracted to, under its file name in the repository, "]
+    #[doc = "so it can be mirrored or extracted again later without downloading it. With [`Self::conditional`], the `.meta` file sits next to it. Off by default."]
+    pub keep_archives: bool,
+    #[doc = "Look C++, JNI and Java artifacts up in this cache before downloading them, and store them there afterwards, "]
+    #[doc = "so projects on the same machine download each only once. [`Self::force`] downloads them anyway. "]
+    #[doc = "Unset by default; [`DownloadCache::in_default_dir`] gives the usual location."]
+    pub cache: Option<DownloadCache>,
+    #[doc = "When a C++ dependency doesn't publish the static or shared build that was asked for, download the other one with a warning instead. "]
+    #[doc = "Off by default, failing with [`error::Error::LinkageUnavailable`] if only the other one is published."]
+    pub linkage_fallback: bool,
+    #[doc = "Collects what downloads got past without failing, such as skipped dependencies and platform fallbacks. Keep a clone to read the [`Diagnostic`]s afterwards."]
+    pub diagnostics: Diagnostics,
+}
+
+#[doc = "Result of [`VendorDep::download_all_to_folder`]."]
+#[derive(Debug, Clone)]
+pub struct DownloadReport {
+    #[doc = "Paths of downloaded Java jars."]
+    pub java_jars: Vec<PathBuf>,
+    #[doc = "Info needed for C++ compilation."]
+    pub cpp: CppInfo,
+    #[doc = "Native libraries needed by the Java dependencies."]
+    pub jni: CppInfo,
+}
+
+#[doc = "Which build variants of a native artifact a Maven repository publishes for a platform, as reported by [`CppDependency::available_variants`] and [`JniDependency::available_variants`]."]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VariantAvailability {
+    #[doc = "The shared release build."]
+    pub release: bool,
+    #[doc = "The shared debug build."]
+    pub debug: bool,
+    #[doc = "The static release build. Always false for JNI dependencies, which aren't published statically."]
+    pub static_release: bool,
+    #[doc = "The static debug build. Always false for JNI dependencies."]
+    pub static_debug: bool,
+}
+
+impl VariantAvailability {
+    #[doc = "Whether the variant downloaded with `is_static` and `is_debug` is published."]
+    pub fn has(&self, is_static: bool, is_debug: bool) -> bool {
+        match (is_static, is_debug) {
+            (false, false) => self.release,
+            (false, true) => self.debug,
+            (true, false) => self.static_release,
+            (true, true) => self.static_debug,
+        }
+    }
+}
+
+#[doc = "Which dependency list of a [`VendorDep`] a [`Coordinate`] came from."]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArtifactKind {
+    #[doc = "A [`JavaDependency`]."]
+    Java,
+    #[doc = "A [`JniDependency`]."]
+    Jni,
+    #[doc = "A [`CppDependency`]."]
+    Cpp,
+}
+
+#[doc = "Maven coordinates of an artifact referenced by a [`VendorDep`]."]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Coordinate {
+    #[doc = "Maven group."]
+    pub group_id: String,
+    #[doc = "Maven artifact."]
+    pub artifact_id: String,
+    #[doc = "Maven version."]
+    pub version: String,
+    #[doc = "Kind of dependency."]
+    pub kind: ArtifactKind,
+}
+
+impl std::fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.group_id, self.artifact_id, self.version)
+    }
+}
+
+impl Coordinate {
+    #[doc = "Parse a `group:artifact:version` or `group:artifact:version:classifier` string, returning the classifier separately. "]
+    #[doc = "Fails with [`error::Error::InvalidCoordinate`] if there are too few or too many parts, or any of them is empty."]
+    pub fn parse(coordinate: &str, kind: ArtifactKind) -> Result<(Self, Option<String>)> {
+        let invalid = || error::Error::InvalidCoordinate(coordinate.to_string());
+        let parts = coordinate.split(':').collect::<Vec<_>>();
+        if !(3..=4).contains(&parts.len()) || parts.iter().any(|x| x.is_empty()) {
+            return Err(invalid());
+        }
+        Ok((
+            Self {
+                group_id: parts[0].to_string(),
+                artifact_id: parts[1].to_string(),
+                version: parts[2].to_string(),
+                kind,
+            },
+            parts.get(3).map(|x| x.to_string()),
+        ))
+    }
+}
+
+#[cfg(feature = "download")]
+#[doc = "Download a single artifact given as a `group:artifact:version[:classifier]` string, without writing out a dependency struct. "]
+#[doc = "`kind` decides how it is downloaded: a [`JavaDependency`] jar into `out_folder` (the classifier being the Maven classifier), "]
+#[doc = "a [`JniDependency`] for `platform` into `out_folder`, or a [`CppDependency`]'s headers and libraries for `platform` following [`DownloadConfig::layout`] (the classifier replacing `headers` as the header classifier). "]
+#[doc = "JNI artifacts take their classifier from `platform`, so giving one fails with [`error::Error::InvalidCoordinate`]."]
+pub async fn download_coordinate<P: AsRef<Path>>(
+    coordinate: &str,
+    kind: ArtifactKind,
+    out_folder: P,
+    maven_url: &str,
+    platform: BinaryPlatform,
+    is_static: bool,
+    is_debug: bool,
+) -> Result<()> {
+    download_coordinate_with_config(
+        coordinate,
+        kind,
+        out_folder,
+        maven_url,
+        platform,
+        is_static,
+        is_debug,
+        &DownloadConfig::default(),
+    )
+    .await
+}
+
+#[cfg(feature = "download")]
+#[doc = "Like [`download_coordinate`], with additional options."]
+#[allow(clippy::too_many_arguments)]
+pub async fn download_coordinate_with_config<P: AsRef<Path>>(
+    coordinate: &str,
+    kind: ArtifactKind,
+    out_folder: P,
+    maven_url: &str,
+    platform: BinaryPlatform,
+    is_static: bool,
+    is_debug: bool,
+    config: &DownloadConfig,
+) -> Result<()> {
+    let out_folder = out_folder.as_ref();
+    let (parsed, classifier) = Coordinate::parse(coordinate, kind)?;
+    let Coordinate {
+        group_id,
+        artifact_id,
+        version,
+        ..
+    } = parsed;
+    match kind {
+        ArtifactKind::Java => {
+            JavaDependency {
+                group_id,
+                artifact_id,
+                version,
+                classifier,
+                packaging: "jar".to_string(),
+                maven_urls: None,
+            }
+            .download_library_to_folder_with_config(out_folder, maven_url, config)
+            .await
+        }
+        ArtifactKind::Jni => {
+            if classifier.is_some() {
+                return Err(error::Error::InvalidCoordinate(coordinate.to_string()));
+            }
+            JniDependency {
+                group_id,
+                artifact_id,
+                version,
+                is_jar: false,
+                skip_invalid_platforms: false,
+                valid_platforms: vec![],
+                sim_mode: None,
+                classifier_template: None,
+                maven_urls: None,
+            }
+            .download_library_to_folder_with_config(
+                out_folder, maven_url, platform, is_debug, config,
+            )
+            .await
+        }
+        ArtifactKind::Cpp => {
+            let dep = CppDependency {
+                group_id,
+                artifact_id,
+                version,
+                header_classifier: classifier.unwrap_or_else(|| "headers".to_string()),
+                binary_platforms: vec![],
+                classifier_template: None,
+                maven_urls: None,
+                packaging: None,
+            };
+            dep.download_headers_to_folder_with_config(
+                config.layout.include_dir(out_folder, &dep),
+                maven_url,
+                config,
+            )
+            .await?;
+            dep.download_library_to_folder_with_config(
+                config.layout.libs_dir(out_folder, &dep),
+                maven_url,
+                platform,
+                is_static,
+                is_debug,
+                config,
+            )
+            .await
+        }
+    }
+}
+
+#[doc = "Vendor Dependency Format."]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorDep {
+    #[doc = "File name that GradleRIO will write to `vendordeps/` directory."]
+    pub file_name: String,
+    #[doc = "Name of vendor library."]
+    pub name: String,
+    #[doc = "Vendor library version. Usually is the same as each artifact's Maven version."]
+    pub version: String,
+    #[doc = "Supported year."]
+    pub frc_year: FrcYear,
+    #[doc = "UUID used for checking compatibility. Rejected during deserialization if it is not a well-formed UUID."]
+    #[serde(deserialize_with = "__private::deserialize_uuid")]
+    pub uuid: String,
+    #[doc = "List of Maven repositories to search for Maven artifacts. `file://` URLs and absolute paths are read from the local filesystem."]
+    pub maven_urls: Vec<String>,
+    #[doc = "URL for this. If up to date, the contents of the url should reproduce this [`VendorDep`] value; see [`VendorDep::verify_json_url`]."]
+    pub json_url: String,
+    #[doc = "When this JSON was generated, as written by the vendor. Read from either `date` or `fileDate`, and written back as `date`. Most vendordeps don't carry it."]
+    #[serde(default, alias = "fileDate", skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[doc = "A list of other [`VendorDep`]s this is explicitly incompatible with. Generally this includes older versions which would introduce name collisions."]
+    #[serde(default)]
+    pub conflicts_with: Vec<PackageSpec>,
+    #[doc = "A list of other [`VendorDep`]s that must be installed alongside this one. See [`VendorDep::check_requirements`]."]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires: Vec<PackageSpec>,
+    #[doc = "A list of Java source dependencies."]
+    pub java_dependencies: Vec<JavaDependency>,
+    #[doc = "A list of Java native dependencies."]
+    pub jni_dependencies: Vec<JniDependency>,
+    #[doc = "A list of C++ dependencies."]
+    pub cpp_dependencies: Vec<CppDependency>,
+}
+
+impl VendorDep {
+    #[doc = "Parse vendordep JSON from bytes, such as those embedded with `include_bytes!`. "]
+    #[doc = "A leading UTF-8 byte order mark, which some Windows tools write, and surrounding whitespace are ignored."]
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+        let start = bytes
+            .iter()
+            .position(|x| !x.is_ascii_whitespace())
+            .unwrap_or(bytes.len());
+        let end = bytes
+            .iter()
+            .rposition(|x| !x.is_ascii_whitespace())
+            .map_or(start, |x| x + 1);
+        Ok(serde_json::from_slice(&bytes[start..end])?)
+    }
+
+    #[doc = "Read and parse a vendordep JSON file, such as one in a GradleRIO project's `vendordeps/` directory."]
+    pub fn from_path<P: AsRef<Path>>(p: P) -> Result<Self> {
+        Self::from_slice(&std::fs::read(p)?)
     }
 
     #[cfg(feature = "download")]
-    #[doc = "Download all JNI dependencies. Directory structure follows `<output_folder>/<cpp_dependency_name>/`."]
-    pub async fn download_all_jni_deps_to_folder<P: AsRef<Path>>(
+    #[doc = "Download JSON from url and parse it. `file://` URLs and absolute paths are read from the local filesystem. "]
+    #[doc = "A vendor's `latest` URL works too, and yields whatever version it currently points to; see [`VendorDep::from_latest`] to resolve it to that version's own JSON."]
+    pub async fn from_url(url: &str) -> Result<Self> {
+        Self::from_slice(&fetch::fetch_bytes(url, &DownloadConfig::default()).await?)
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`VendorDep::from_url`], also returning the JSON exactly as it was downloaded, byte order mark and all. "]
+    #[doc = "Serializing the parsed vendordep again may format it differently and drops fields this crate doesn't know, so keep this as the source of truth when caching or auditing."]
+    pub async fn from_url_with_raw(url: &str) -> Result<(Self, String)> {
+        let bytes = fetch::fetch_bytes(url, &DownloadConfig::default()).await?;
+        let dep = Self::from_slice(&bytes)?;
+        // JSON that parses is valid UTF-8, so this is lossless.
+        Ok((dep, String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Download and parse several vendordeps concurrently, such as a curated catalog of `json_url`s, with at most 8 requests in flight. "]
+    #[doc = "Returns one result per URL, in the same order, so one failure doesn't lose the others."]
+    pub async fn from_urls(urls: &[&str]) -> Vec<Result<Self>> {
+        let config = DownloadConfig {
+            throttle: Throttle::new(8, None),
+            ..Default::default()
+        };
+        Self::from_urls_with_config(urls, &config).await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`VendorDep::from_urls`], limiting requests with the [`DownloadConfig::throttle`] of `config` instead."]
+    pub async fn from_urls_with_config(
+        urls: &[&str],
+        config: &DownloadConfig,
+    ) -> Vec<Result<Self>> {
+        futures::future::join_all(urls.iter().map(|url| async move {
+            Self::from_slice(&fetch::fetch_bytes_uncached(url, config).await?)
+        }))
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Download a vendordep published under the `latest` convention, such as `https://maven.ctr-electronics.com/release/com/ctre/phoenix6/latest/Phoenix6-frc2024-latest.json`. "]
+    #[doc = "The `latest` JSON is fetched first; if the vendor also publishes the JSON of that version at [`VendorDep::pin_latest_url`], that pinned copy is returned instead, so its `json_url` keeps referring to the same version. "]
+    #[doc = "Otherwise, this behaves like [`VendorDep::from_url`]."]
+    pub async fn from_latest(url: &str) -> Result<Self> {
+        let config = DownloadConfig::default();
+        let latest = Self::from_slice(&fetch::fetch_bytes(url, &config).await?)?;
+        let Some(pinned_url) = Self::pin_latest_url(url, &latest.version) else {
+            return Ok(latest);
+        };
+        match fetch::fetch_bytes_if_exists(&pinned_url, &config).await {
+            Ok(Some(bytes)) => Self::from_slice(&bytes),
+            Ok(None) => {
+                log_debug!("{} does not exist, using {}", pinned_url, url);
+                Ok(latest)
+            }
+            Err(e) => {
+                log_warn!("Failed to fetch {}, using {}: {}", pinned_url, url, e);
+                Ok(latest)
+            }
+        }
+    }
+
+    #[doc = "Rewrite a `latest` vendordep URL to the URL of a concrete `version`: `latest` directories become `version`, and a file name ending in `-latest.json` ends in `-<version>.json` instead. "]
+    #[doc = "For example, `.../phoenix6/latest/Phoenix6-frc2024-latest.json` becomes `.../phoenix6/24.3.0/Phoenix6-frc2024-24.3.0.json`. Returns `None` if `url` does not follow the convention."]
+    pub fn pin_latest_url(url: &str, version: &str) -> Option<String> {
+        let (dir, file_name) = url.rsplit_once('/')?;
+        let mut changed = false;
+        let dir = dir
+            .split('/')
+            .map(|x| {
+                if x == "latest" {
+                    changed = true;
+                    version
+                } else {
+                    x
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        let file_name = match file_name.strip_suffix("-latest.json") {
+            Some(stem) => {
+                changed = true;
+                format!("{}-{}.json", stem, version)
+            }
+            None => file_name.to_string(),
+        };
+        changed.then(|| format!("{}/{}", dir, file_name))
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Resolve the URLs of every artifact the `download_all_*` methods would fetch. Each entry holds the dependency and the candidate URLs of one of its artifacts, one per Maven repository. "]
+    #[doc = "JNI dependencies the download would skip on `binary_platform` are left out."]
+    fn artifact_urls(
         &self,
-        p: P,
         binary_platform: BinaryPlatform,
+        is_static: bool,
         is_debug: bool,
-        skip_failed_packages: bool,
-    ) -> Result<CppInfo> {
-        let path = p.as_ref();
-        let mut library_search_paths = Vec::new();
-        let mut libraries = Vec::new();
+    ) -> Vec<(Coordinate, Vec<String>)> {
+        let coordinate = |group_id: &str, artifact_id: &str, version: &str, kind| Coordinate {
+            group_id: group_id.to_string(),
+            artifact_id: artifact_id.to_string(),
+            version: version.to_string(),
+            kind,
+        };
+        let mut artifacts = Vec::new();
+        for dep in &self.java_dependencies {
+            artifacts.push((
+                coordinate(
+                    &dep.group_id,
+                    &dep.artifact_id,
+                    &dep.version,
+                    ArtifactKind::Java,
+                ),
+                dep.effective_maven_urls(&self.maven_urls)
+                    .iter()
+                    .map(|x| dep.get_url(x))
+                    .collect(),
+            ));
+        }
         for dep in &self.jni_dependencies {
-            let dep_path = path.join(&dep.artifact_id);
-            'outer: loop {
-                for maven_url in &self.maven_urls {
-                    match dep
-                        .download_library_to_folder(
-                            &dep_path,
-                            maven_url.as_str(),
-                            binary_platform,
-                            is_debug,
-                        )
-                        .await
-                    {
-                        Ok(_) => break 'outer,
-                        _ => {}
-                    }
-                }
-                if !skip_failed_packages {
-                    return Err(crate::error::Error::NotFoundError(format!(
-                        "{}:{}:{}",
-                        dep.group_id, dep.artifact_id, dep.version
-                    )));
-                }
+            if dep.skip_invalid_platforms && !dep.supports_platform(&binary_platform) {
+                continue;
             }
-            let mut temp_search_paths = HashSet::new();
-            for item in jwalk::WalkDir::new(dep_path) {
-                let item = item?;
-                if let Some(stem) = item.path().file_stem() {
-                    let stem = stem.to_string_lossy();
-                    match item.path().extension().and_then(|x| x.to_str()) {
-                        Some("so") => {
-                            temp_search_paths.insert(item.parent_path().to_path_buf());
-                            libraries.push(stem[3..].to_string());
-                        }
-                        Some("dll") => {
-                            temp_search_paths.insert(item.parent_path().to_path_buf());
-                            libraries.push(stem.to_string());
-                        }
-                        _ => {}
-                    }
-                }
+            let platform = dep
+                .published_platform(&binary_platform)
+                .unwrap_or_else(|| binary_platform.clone());
+            artifacts.push((
+                coordinate(
+                    &dep.group_id,
+                    &dep.artifact_id,
+                    &dep.version,
+                    ArtifactKind::Jni,
+                ),
+                dep.effective_maven_urls(&self.maven_urls)
+                    .iter()
+                    .map(|x| dep.get_url(x, platform.to_str(), is_debug))
+                    .collect(),
+            ));
+        }
+        for dep in &self.cpp_dependencies {
+            let artifact = coordinate(
+                &dep.group_id,
+                &dep.artifact_id,
+                &dep.version,
+                ArtifactKind::Cpp,
+            );
+            if dep.has_headers() {
+                artifacts.push((
+                    artifact.clone(),
+                    dep.effective_maven_urls(&self.maven_urls)
+                        .iter()
+                        .map(|x| dep.classifier_url(x, &dep.header_classifier))
+                        .collect(),
+                ));
             }
-            library_search_paths.extend(temp_search_paths);
+            let platform = dep
+                .published_platform(&binary_platform)
+                .unwrap_or_else(|| binary_platform.clone());
+            artifacts.push((
+                artifact,
+                dep.effective_maven_urls(&self.maven_urls)
+                    .iter()
+                    .map(|x| dep.get_url(x, platform.to_str(), is_static, is_debug))
+                    .collect(),
+            ));
         }
-        Ok(CppInfo {
-            include_dirs: vec![],
-            library_search_paths,
-            libraries,
-        })
+        artifacts
     }
 
     #[cfg(feature = "download")]
-    #[doc = "Download all java dependencies. Note this does *not* include JNI dependencies. Directory structure follows `<output_folder>/<java_dependency_name>-<java_dependency_version>.jar`."]
-    pub async fn download_all_java_deps_to_folder<P: AsRef<Path>>(
+    #[doc = "Find the dependencies with an artifact that [`Self::download_all_to_folder`] would need on `binary_platform` but that no Maven repository has, "]
+    #[doc = "by sending a `HEAD` request for each artifact instead of downloading it. Returns each such dependency once, in the order they are declared; an empty list means everything is published. "]
+    #[doc = "Missing means every repository answered that the artifact doesn't exist: if a repository can't be reached and no other one has the artifact, the error is returned instead, "]
+    #[doc = "since the artifact may well be there."]
+    pub async fn missing_artifacts(
         &self,
-        p: P,
-        skip_failed_packages: bool,
-    ) -> Result<Vec<PathBuf>> {
-        let path = p.as_ref();
-        for dep in &self.java_dependencies {
-            'outer: loop {
-                for maven_url in &self.maven_urls {
-                    match dep.download_library_to_folder(path, maven_url).await {
-                        Ok(_) => break 'outer,
-                        _ => {}
-                    };
-                }
-                if !skip_failed_packages {
-                    return Err(crate::error::Error::NotFoundError(format!(
-                        "{}:{}:{}",
-                        dep.group_id, dep.artifact_id, dep.version
-                    )));
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+    ) -> Result<Vec<Coordinate>> {
+        binary_platform.ensure_library_platform()?;
+        let config = DownloadConfig::default();
+        let mut missing = Vec::new();
+        for (artifact, urls) in self.artifact_urls(binary_platform, is_static, is_debug) {
+            let mut unreachable = None;
+            let mut found = false;
+            for url in urls {
+                match fetch::head(&url, &config).await {
+                    Ok(fetch::Head::Found(_)) => {
+                        found = true;
+                        break;
+                    }
+                    Ok(fetch::Head::Missing) => {}
+                    Err(e) => unreachable = Some(e),
                 }
             }
+            if found {
+                continue;
+            }
+            if let Some(e) = unreachable {
+                return Err(e);
+            }
+            if !missing.contains(&artifact) {
+                missing.push(artifact);
+            }
         }
-
-        Ok(std::fs::read_dir(path)?
-            .into_iter()
-            .filter_map(|x| x.ok())
-            .filter_map(|x| {
-                if x.file_type().ok()?.is_file() {
-                    Some(x.path())
-                } else {
-                    None
-                }
-            })
-            .collect())
+        Ok(missing)
     }
-}
-
-#[doc(hidden)]
-mod __private {
-    use serde::{Deserialize, Deserializer};
 
-    pub fn deserialize_string_or_u32_for_u32<'de, D>(deserializer: D) -> Result<u32, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        #[derive(serde::Deserialize)]
-        #[serde(untagged)]
-        enum Inner {
-            Int(u32),
-            String(String),
+    #[cfg(feature = "download")]
+    #[doc = "Estimate how much [`Self::download_all_to_folder`] would download by requesting the size of each artifact from the first repository that has it. "]
+    #[doc = "Artifacts whose size the server does not report, or that are missing from every Maven repository, are counted separately in the [`SizeEstimate`] rather than as zero bytes."]
+    pub async fn estimated_download_size(
+        &self,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+    ) -> Result<SizeEstimate> {
+        let mut estimate = SizeEstimate::default();
+        for (_, urls) in self.artifact_urls(binary_platform, is_static, is_debug) {
+            let mut head = None;
+            for url in urls {
+                if let Ok(found @ fetch::Head::Found(_)) =
+                    fetch::head(&url, &DownloadConfig::default()).await
+                {
+                    head = Some(found);
+                    break;
+                }
+            }
+            estimate.add(head);
         }
+        Ok(estimate)
+    }
 
-        let res = Inner::deserialize(deserializer)?;
-        match res {
-            Inner::Int(x) => Ok(x),
-            Inner::String(x) => x
-                .parse()
-                .map_err(|x| <D::Error as serde::de::Error>::custom(x)),
-        }
+    #[cfg(feature = "download")]
+    #[doc = "Download all java, JNI, and cpp dependencies. Directory structure follows `<output_folder>/(java|jni|cpp)/`, with each subdirectory laid out like the individual `download_all_*` methods."]
+    pub async fn download_all_to_folder<P: AsRef<Path>>(
+        &self,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+    ) -> Result<DownloadReport> {
+        self.download_all_to_folder_with_config(
+            p,
+            binary_platform.clone(),
+            is_static,
+            is_debug,
+            skip_failed_packages,
+            &DownloadConfig::default(),
+        )
+        .await
     }
-}
 
-#[allow(unused)]
-#[cfg(test)]
-mod test {
-    use tempfile::tempdir;
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_all_to_folder`], with additional options."]
+    pub async fn download_all_to_folder_with_config<P: AsRef<Path>>(
+        &self,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+    ) -> Result<DownloadReport> {
+        let path = p.as_ref();
+        let (java_jars, jni, cpp) = futures::try_join!(
+            self.download_all_java_deps_to_folder_with_config(
+                path.join("java"),
+                skip_failed_packages,
+                config,
+            ),
+            self.download_all_jni_deps_to_folder_with_config(
+                path.join("jni"),
+                binary_platform.clone(),
+                is_debug,
+                skip_failed_packages,
+                config,
+            ),
+            self.download_all_cpp_deps_to_folder_with_config(
+                path.join("cpp"),
+                binary_platform,
+                is_static,
+                is_debug,
+                skip_failed_packages,
+                config,
+            ),
+        )?;
+        Ok(DownloadReport {
+            java_jars,
+            cpp,
+            jni,
+        })
+    }
 
-    use crate::VendorDep;
+    #[cfg(feature = "download")]
+    #[doc = "Download all cpp dependencies. Directory structure follows `<output_folder>/<cpp_dependency_name>/(lib|include)`."]
+    pub async fn download_all_cpp_deps_to_folder<P: AsRef<Path>>(
+        &self,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+    ) -> Result<CppInfo> {
+        self.download_all_cpp_deps_to_folder_with_config(
+            p,
+            binary_platform.clone(),
+            is_static,
+            is_debug,
+            skip_failed_packages,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
 
     #[cfg(feature = "download")]
-    #[test]
-    fn ctre_2024_headers() {
-        tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()
+    #[doc = "Like [`Self::download_all_cpp_deps_to_folder`], with additional options."]
+    pub async fn download_all_cpp_deps_to_folder_with_config<P: AsRef<Path>>(
+        &self,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+    ) -> Result<CppInfo> {
+        self.download_cpp_deps_filtered(
+            p,
+            |_| true,
+            binary_platform,
+            is_static,
+            is_debug,
+            skip_failed_packages,
+            config,
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_all_cpp_deps_to_folder_with_config`], but only downloads the cpp dependencies `predicate` returns true for. "]
+    #[doc = "The returned [`CppInfo`] only covers those dependencies."]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn download_cpp_deps_filtered<P: AsRef<Path>>(
+        &self,
+        p: P,
+        predicate: impl Fn(&CppDependency) -> bool,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+    ) -> Result<CppInfo> {
+        let path = p.as_ref();
+        // Created up front, so every dependency being skipped still leaves the folder behind.
+        std::fs::create_dir_all(path)?;
+        let deps = self.cpp_dependencies.iter().filter(|&x| predicate(x));
+        let claims = sink::FileClaims::default();
+        let parts = futures::future::try_join_all(deps.map(|dep| {
+            self.download_cpp_dep(
+                dep,
+                path,
+                binary_platform.clone(),
+                is_static,
+                is_debug,
+                skip_failed_packages,
+                config,
+                &claims,
+                None,
+            )
+        }))
+        .await?;
+        let mut info = CppInfo::new_empty();
+        for part in parts {
+            info.extend(part);
+        }
+        info.dedup();
+        Ok(info)
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Download the headers and libraries of the one cpp dependency with the given `artifact_id` into `<output_folder>/<artifact_id>/`, "]
+    #[doc = "as [`Self::download_all_cpp_deps_to_folder`] would, leaving the others alone. Fails with [`error::Error::NoSuchDependency`] if there is no such dependency, "]
+    #[doc = "and with [`error::Error::NotFoundError`] if it can't be downloaded."]
+    pub async fn download_cpp_dep_by_id<P: AsRef<Path>>(
+        &self,
+        artifact_id: &str,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+    ) -> Result<CppInfo> {
+        self.download_cpp_dep_by_id_with_config(
+            artifact_id,
+            p,
+            binary_platform,
+            is_static,
+            is_debug,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_cpp_dep_by_id`], with additional options."]
+    pub async fn download_cpp_dep_by_id_with_config<P: AsRef<Path>>(
+        &self,
+        artifact_id: &str,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        config: &DownloadConfig,
+    ) -> Result<CppInfo> {
+        if !self
+            .cpp_dependencies
+            .iter()
+            .any(|x| x.artifact_id == artifact_id)
+        {
+            return Err(crate::error::Error::NoSuchDependency {
+                vendordep: self.name.clone(),
+                artifact_id: artifact_id.to_string(),
+            });
+        }
+        self.download_cpp_deps_filtered(
+            p,
+            |x| x.artifact_id == artifact_id,
+            binary_platform,
+            is_static,
+            is_debug,
+            false,
+            config,
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_all_cpp_deps_to_folder`], but returns the [`CppInfo`] of each dependency instead of merging them, "]
+    #[doc = "in the order of [`Self::cpp_dependencies`], so a link line can name only the libraries of the dependencies that are used. Skipped dependencies have an empty [`CppInfo`]."]
+    pub async fn download_all_cpp_deps_by_dependency<P: AsRef<Path>>(
+        &self,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+    ) -> Result<Vec<(Coordinate, CppInfo)>> {
+        self.download_all_cpp_deps_by_dependency_with_config(
+            p,
+            binary_platform,
+            is_static,
+            is_debug,
+            skip_failed_packages,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_all_cpp_deps_by_dependency`], with additional options."]
+    pub async fn download_all_cpp_deps_by_dependency_with_config<P: AsRef<Path>>(
+        &self,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+    ) -> Result<Vec<(Coordinate, CppInfo)>> {
+        let path = p.as_ref();
+        std::fs::create_dir_all(path)?;
+        let claims = sink::FileClaims::default();
+        let parts = futures::future::try_join_all(self.cpp_dependencies.iter().map(|dep| {
+            self.download_cpp_dep(
+                dep,
+                path,
+                binary_platform.clone(),
+                is_static,
+                is_debug,
+                skip_failed_packages,
+                config,
+                &claims,
+                None,
+            )
+        }))
+        .await?;
+        Ok(self
+            .cpp_dependencies
+            .iter()
+            .zip(parts)
+            .map(|(dep, mut info)| {
+                info.dedup();
+                let artifact = Coordinate {
+                    group_id: dep.group_id.clone(),
+                    artifact_id: dep.artifact_id.clone(),
+                    version: dep.version.clone(),
+                    kind: ArtifactKind::Cpp,
+                };
+                (artifact, info)
+            })
+            .collect())
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Download all cpp dependencies like [`Self::download_all_cpp_deps_to_folder`] with `is_debug` false, and also download their debug artifacts into `<output_folder>/debug/`, "]
+    #[doc = "so the symbols are at hand for a symbolizer. Returns the [`CppInfo`] of the release libraries, which is what should be built against, and one for the debug libraries, which has no include directories."]
+    pub async fn download_all_cpp_deps_with_debug_symbols<P: AsRef<Path>>(
+        &self,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        skip_failed_packages: bool,
+    ) -> Result<(CppInfo, CppInfo)> {
+        self.download_all_cpp_deps_with_debug_symbols_with_config(
+            p,
+            binary_platform,
+            is_static,
+            skip_failed_packages,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_all_cpp_deps_with_debug_symbols`], with additional options."]
+    pub async fn download_all_cpp_deps_with_debug_symbols_with_config<P: AsRef<Path>>(
+        &self,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+    ) -> Result<(CppInfo, CppInfo)> {
+        let path = p.as_ref();
+        let release = self
+            .download_all_cpp_deps_to_folder_with_config(
+                path,
+                binary_platform.clone(),
+                is_static,
+                false,
+                skip_failed_packages,
+                config,
+            )
+            .await?;
+        let debug_path = path.join("debug");
+        // The release download already has the headers, so only fetch the libraries again.
+        let without_headers = self
+            .cpp_dependencies
+            .iter()
+            .map(|dep| CppDependency {
+                group_id: dep.group_id.clone(),
+                artifact_id: dep.artifact_id.clone(),
+                version: dep.version.clone(),
+                header_classifier: String::new(),
+                binary_platforms: dep.binary_platforms.clone(),
+                classifier_template: dep.classifier_template.clone(),
+                maven_urls: dep.maven_urls.clone(),
+                packaging: dep.packaging.clone(),
+            })
+            .collect::<Vec<_>>();
+        let claims = sink::FileClaims::default();
+        let parts = futures::future::try_join_all(without_headers.iter().map(|dep| {
+            self.download_cpp_dep(
+                dep,
+                &debug_path,
+                binary_platform.clone(),
+                is_static,
+                true,
+                skip_failed_packages,
+                config,
+                &claims,
+                None,
+            )
+        }))
+        .await?;
+        let mut debug = CppInfo::new_empty();
+        for part in parts {
+            debug.extend(part);
+        }
+        debug.dedup();
+        Ok((release, debug))
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Download all cpp dependencies of every vendordep in `deps` into one shared folder, laid out like [`Self::download_all_cpp_deps_to_folder`]. "]
+    #[doc = "An artifact referenced by several vendordeps is only downloaded once, from the first vendordep that references it, and the returned [`CppInfo`] is deduplicated."]
+    pub async fn download_all_cpp_deps_for_set<P: AsRef<Path>>(
+        deps: &[VendorDep],
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+    ) -> Result<CppInfo> {
+        let path = p.as_ref();
+        let mut seen = HashSet::new();
+        let claims = sink::FileClaims::default();
+        let mut downloads = Vec::new();
+        for vendordep in deps {
+            for dep in &vendordep.cpp_dependencies {
+                if !seen.insert((dep.group_id.as_str(), dep.artifact_id.as_str())) {
+                    log_debug!(
+                        "Skipping {}:{}:{} of {}, already downloaded",
+                        dep.group_id,
+                        dep.artifact_id,
+                        dep.version,
+                        vendordep.name
+                    );
+                    continue;
+                }
+                downloads.push(vendordep.download_cpp_dep(
+                    dep,
+                    path,
+                    binary_platform.clone(),
+                    is_static,
+                    is_debug,
+                    skip_failed_packages,
+                    config,
+                    &claims,
+                    None,
+                ));
+            }
+        }
+        let mut info = CppInfo::new_empty();
+        for part in futures::future::try_join_all(downloads).await? {
+            info.extend(part);
+        }
+        info.dedup();
+        Ok(info)
+    }
+
+    #[cfg(feature = "download")]
+    #[allow(clippy::too_many_arguments)]
+    async fn download_cpp_dep(
+        &self,
+        dep: &CppDependency,
+        path: &Path,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+        claims: &sink::FileClaims,
+        events: Option<events::Events<'_>>,
+    ) -> Result<CppInfo> {
+        binary_platform.ensure_library_platform()?;
+        config.cancel.check()?;
+        let coordinate = Coordinate {
+            group_id: dep.group_id.clone(),
+            artifact_id: dep.artifact_id.clone(),
+            version: dep.version.clone(),
+            kind: ArtifactKind::Cpp,
+        };
+        let config = &DownloadConfig {
+            sink: claims.store_for(&config.sink, coordinate.to_string()),
+            ..config.clone()
+        };
+        let mut info = CppInfo::new_empty();
+        let header_path = config.layout.include_dir(path, dep);
+        let libs_path = config.layout.libs_dir(path, dep);
+        // Don't leave a partially extracted dependency behind.
+        let remove_partial = || {
+            // Nothing partial to remove, and the previous copy should stay. Shared directories hold other dependencies too.
+            if config.atomic || config.layout.shares_directories() {
+                return;
+            }
+            _ = std::fs::remove_dir_all(&header_path);
+            _ = std::fs::remove_dir_all(&libs_path);
+        };
+        let mut tried_urls = Vec::new();
+        let mut headers_missing = false;
+        if dep.has_headers() {
+            events::emit(
+                events,
+                DownloadEvent::ResolvingHeaders {
+                    artifact: coordinate.clone(),
+                },
+            );
+            'outer: {
+                for maven_url in
+                    fetch::mirror_order(dep.effective_maven_urls(&self.maven_urls), config, |x| {
+                        dep.classifier_url(x, &dep.header_classifier)
+                    })
+                    .await
+                {
+                    tried_urls.push(dep.classifier_url(maven_url, &dep.header_classifier));
+                    match config
+                        .retry
+                        .run(&config.cancel, || {
+                            dep.download_headers_to_folder_with_config(
+                                &header_path,
+                                maven_url.as_str(),
+                                config,
+                            )
+                        })
+                        .await
+                    {
+                        Ok(_) => {
+                            log_info!(
+                                "Resolved headers of {}:{}:{} from {}",
+                                dep.group_id,
+                                dep.artifact_id,
+                                dep.version,
+                                maven_url
+                            );
+                            break 'outer;
+                        }
+                        Err(crate::error::Error::Cancelled) => {
+                            remove_partial();
+                            return Err(crate::error::Error::Cancelled);
+                        }
+                        Err(e @ crate::error::Error::FileCollision { .. }) => return Err(e),
+                        Err(e) => {
+                            log_warn!(
+                                "Failed to download headers of {}:{}:{} from {}: {}",
+                                dep.group_id,
+                                dep.artifact_id,
+                                dep.version,
+                                maven_url,
+                                e
+                            );
+                            config.diagnostics.push(Diagnostic::RepositoryFailed {
+                                artifact: coordinate.clone(),
+                                maven_url: maven_url.to_string(),
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                }
+                // Some dependencies ship their headers inside the platform artifacts instead, so this only fails if the libraries are missing too.
+                log_info!(
+                    "No header artifact of {}:{}:{} was found, looking for headers among its libraries",
+                    dep.group_id,
+                    dep.artifact_id,
+                    dep.version
+                );
+                headers_missing = true;
+                config.diagnostics.push(Diagnostic::HeadersMissing {
+                    artifact: coordinate.clone(),
+                });
+            }
+            if !headers_missing {
+                info.include_dirs.push(header_path.clone());
+            }
+        }
+        let platforms = if config.platform_fallbacks {
+            binary_platform.fallbacks()
+        } else {
+            vec![binary_platform.clone()]
+        };
+        let progress: fetch::ProgressFn = &|bytes_downloaded, total_bytes| {
+            events::progress(events, &coordinate, bytes_downloaded, total_bytes)
+        };
+        let extract_progress: extract::ExtractProgressFn =
+            &|entries_extracted, total_entries, entry| {
+                events::extracting(events, &coordinate, entries_extracted, total_entries, entry)
+            };
+        let platforms = published_platforms(&platforms, |x| dep.published_platform(x));
+        // The requested linkage on every platform first, then the other one if falling back is allowed.
+        let linkages = if config.linkage_fallback {
+            vec![is_static, !is_static]
+        } else {
+            vec![is_static]
+        };
+        'outer: {
+            for (linkage, platform) in linkages
+                .iter()
+                .flat_map(|x| platforms.iter().map(move |platform| (*x, platform)))
+            {
+                for maven_url in
+                    fetch::mirror_order(dep.effective_maven_urls(&self.maven_urls), config, |x| {
+                        dep.get_url(x, platform.to_str(), linkage, is_debug)
+                    })
+                    .await
+                {
+                    let url = dep.get_url(maven_url, platform.to_str(), linkage, is_debug);
+                    tried_urls.push(url.clone());
+                    match config
+                        .retry
+                        .run(&config.cancel, || {
+                            fetch::fetch_and_extract_with_progress(
+                                &url,
+                                &libs_path,
+                                config,
+                                events.is_some().then_some(progress),
+                                events.is_some().then_some(extract_progress),
+                            )
+                        })
+                        .await
+                    {
+                        Ok(bytes) => {
+                            events::emit(
+                                events,
+                                DownloadEvent::DownloadedLib {
+                                    artifact: coordinate.clone(),
+                                    bytes,
+                                },
+                            );
+                            if linkage != is_static {
+                                log_warn!(
+                                    "{}:{}:{} has no {} build for {}, using the {} one",
+                                    dep.group_id,
+                                    dep.artifact_id,
+                                    dep.version,
+                                    linkage_name(is_static),
+                                    platform.to_str(),
+                                    linkage_name(linkage)
+                                );
+                                config.diagnostics.push(Diagnostic::LinkageFallback {
+                                    artifact: coordinate.clone(),
+                                    platform: platform.clone(),
+                                    is_static: linkage,
+                                });
+                            }
+                            if *platform != binary_platform {
+                                config.diagnostics.push(Diagnostic::PlatformFallback {
+                                    artifact: coordinate.clone(),
+                                    requested: binary_platform.clone(),
+                                    used: platform.clone(),
+                                });
+                            }
+                            log_info!(
+                                "Resolved {}:{}:{} from {}",
+                                dep.group_id,
+                                dep.artifact_id,
+                                dep.version,
+                                maven_url
+                            );
+                            break 'outer;
+                        }
+                        Err(crate::error::Error::Cancelled) => {
+                            remove_partial();
+                            return Err(crate::error::Error::Cancelled);
+                        }
+                        Err(e @ crate::error::Error::FileCollision { .. }) => return Err(e),
+                        Err(e) => {
+                            log_warn!(
+                                "Failed to download {}:{}:{} from {}: {}",
+                                dep.group_id,
+                                dep.artifact_id,
+                                dep.version,
+                                maven_url,
+                                e
+                            );
+                            config.diagnostics.push(Diagnostic::RepositoryFailed {
+                                artifact: coordinate.clone(),
+                                maven_url: maven_url.to_string(),
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+            if !skip_failed_packages {
+                // Tell a missing dependency apart from one that only publishes the other linkage.
+                if !config.linkage_fallback {
+                    for platform in &platforms {
+                        for maven_url in dep.effective_maven_urls(&self.maven_urls) {
+                            let url =
+                                dep.get_url(maven_url, platform.to_str(), !is_static, is_debug);
+                            if fetch::exists(&url, config).await.unwrap_or(false) {
+                                return Err(crate::error::Error::LinkageUnavailable {
+                                    artifact: coordinate.to_string(),
+                                    platform: platform.to_str().to_string(),
+                                    requested: linkage_name(is_static).to_string(),
+                                    available: linkage_name(!is_static).to_string(),
+                                });
+                            }
+                        }
+                    }
+                }
+                return Err(crate::error::Error::NotFoundError {
+                    group_id: dep.group_id.clone(),
+                    artifact_id: dep.artifact_id.clone(),
+                    version: dep.version.clone(),
+                    tried_urls,
+                });
+            }
+            log_warn!(
+                "Skipping {}:{}:{}, it was not found in any repository",
+                dep.group_id,
+                dep.artifact_id,
+                dep.version
+            );
+            config.tally.skipped();
+            config.diagnostics.push(Diagnostic::Skipped {
+                artifact: coordinate.clone(),
+            });
+            events::emit(
+                events,
+                DownloadEvent::Skipped {
+                    artifact: coordinate,
+                },
+            );
+            return Ok(info);
+        }
+        info.add_libraries_in(&libs_path)?;
+        info.add_include_dirs_in(&libs_path)?;
+        if config.strict && info.include_dirs.is_empty() && info.libraries.is_empty() {
+            return Err(crate::error::Error::EmptyDependency(coordinate.to_string()));
+        }
+        Ok(info)
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Download all JNI dependencies. Directory structure follows `<output_folder>/<cpp_dependency_name>/`."]
+    pub async fn download_all_jni_deps_to_folder<P: AsRef<Path>>(
+        &self,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_debug: bool,
+        skip_failed_packages: bool,
+    ) -> Result<CppInfo> {
+        self.download_all_jni_deps_to_folder_with_config(
+            p,
+            binary_platform.clone(),
+            is_debug,
+            skip_failed_packages,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_all_jni_deps_to_folder`], with additional options."]
+    pub async fn download_all_jni_deps_to_folder_with_config<P: AsRef<Path>>(
+        &self,
+        p: P,
+        binary_platform: BinaryPlatform,
+        is_debug: bool,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+    ) -> Result<CppInfo> {
+        let path = p.as_ref();
+        std::fs::create_dir_all(path)?;
+        let platforms = if config.platform_fallbacks {
+            binary_platform.fallbacks()
+        } else {
+            vec![binary_platform]
+        };
+        let parts = futures::future::try_join_all(self.jni_dependencies.iter().map(|dep| {
+            self.download_jni_dep(
+                dep,
+                path,
+                &platforms,
+                is_debug,
+                skip_failed_packages,
+                config,
+            )
+        }))
+        .await?;
+        let mut info = CppInfo::new_empty();
+        for part in parts {
+            info.extend(part);
+        }
+        info.dedup();
+        Ok(info)
+    }
+
+    #[cfg(feature = "download")]
+    async fn download_jni_dep(
+        &self,
+        dep: &JniDependency,
+        path: &Path,
+        platforms: &[BinaryPlatform],
+        is_debug: bool,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+    ) -> Result<CppInfo> {
+        for platform in platforms {
+            platform.ensure_library_platform()?;
+        }
+        config.cancel.check()?;
+        let coordinate = Coordinate {
+            group_id: dep.group_id.clone(),
+            artifact_id: dep.artifact_id.clone(),
+            version: dep.version.clone(),
+            kind: ArtifactKind::Jni,
+        };
+        if dep.skip_invalid_platforms && !platforms.iter().any(|x| dep.supports_platform(x)) {
+            log_info!(
+                "Skipping {}:{}:{}, it is not published for {}",
+                dep.group_id,
+                dep.artifact_id,
+                dep.version,
+                platforms[0].to_str()
+            );
+            config.diagnostics.push(Diagnostic::NotPublished {
+                artifact: coordinate,
+                platform: platforms[0].clone(),
+            });
+            return Ok(CppInfo::new_empty());
+        }
+        let dep_path = path.join(&dep.artifact_id);
+        let packagings = if config.detect_jni_packaging {
+            vec![true, false]
+        } else {
+            vec![dep.is_jar]
+        };
+        let requested = &platforms[0];
+        let platforms = published_platforms(platforms, |x| dep.published_platform(x));
+        let mut tried_urls = Vec::new();
+        'outer: {
+            for platform in &platforms {
+                for (maven_url, &is_jar) in
+                    fetch::mirror_order(dep.effective_maven_urls(&self.maven_urls), config, |x| {
+                        dep.get_url(x, platform.to_str(), is_debug)
+                    })
+                    .await
+                    .into_iter()
+                    .flat_map(|x| packagings.iter().map(move |p| (x, p)))
+                {
+                    let url = dep.packaged_url(maven_url, platform.to_str(), is_debug, is_jar);
+                    tried_urls.push(url.clone());
+                    match config
+                        .retry
+                        .run(&config.cancel, || {
+                            fetch::fetch_and_extract(&url, &dep_path, config)
+                        })
+                        .await
+                    {
+                        Ok(_) => {
+                            log_info!(
+                                "Resolved {}:{}:{} from {}",
+                                dep.group_id,
+                                dep.artifact_id,
+                                dep.version,
+                                url
+                            );
+                            if platform != requested {
+                                config.diagnostics.push(Diagnostic::PlatformFallback {
+                                    artifact: coordinate.clone(),
+                                    requested: requested.clone(),
+                                    used: platform.clone(),
+                                });
+                            }
+                            break 'outer;
+                        }
+                        Err(crate::error::Error::Cancelled) => {
+                            // Don't leave a partially extracted dependency behind.
+                            if !config.atomic {
+                                _ = std::fs::remove_dir_all(&dep_path);
+                            }
+                            return Err(crate::error::Error::Cancelled);
+                        }
+                        Err(e) => {
+                            log_warn!(
+                                "Failed to download {}:{}:{} from {}: {}",
+                                dep.group_id,
+                                dep.artifact_id,
+                                dep.version,
+                                maven_url,
+                                e
+                            );
+                            config.diagnostics.push(Diagnostic::RepositoryFailed {
+                                artifact: coordinate.clone(),
+                                maven_url: maven_url.to_string(),
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+            if !skip_failed_packages {
+                return Err(crate::error::Error::NotFoundError {
+                    group_id: dep.group_id.clone(),
+                    artifact_id: dep.artifact_id.clone(),
+                    version: dep.version.clone(),
+                    tried_urls,
+                });
+            }
+            log_warn!(
+                "Skipping {}:{}:{}, it was not found in any repository",
+                dep.group_id,
+                dep.artifact_id,
+                dep.version
+            );
+            config.tally.skipped();
+            config.diagnostics.push(Diagnostic::Skipped {
+                artifact: coordinate.clone(),
+            });
+            return Ok(CppInfo::new_empty());
+        }
+        let mut info = CppInfo::new_empty();
+        info.add_libraries_in(&dep_path)?;
+        if config.jni_headers {
+            let include_dir = dep_path.join("include");
+            for maven_url in
+                fetch::mirror_order(dep.effective_maven_urls(&self.maven_urls), config, |x| {
+                    dep.headers_url(x)
+                })
+                .await
+            {
+                match fetch::fetch_and_extract(&dep.headers_url(maven_url), &include_dir, config)
+                    .await
+                {
+                    Ok(_) => {
+                        info.include_dirs.push(include_dir);
+                        break;
+                    }
+                    Err(crate::error::Error::Cancelled) => {
+                        if !config.atomic {
+                            _ = std::fs::remove_dir_all(&dep_path);
+                        }
+                        return Err(crate::error::Error::Cancelled);
+                    }
+                    // Most JNI artifacts don't publish headers.
+                    Err(e) => log_debug!(
+                        "No headers for {}:{}:{} in {}: {}",
+                        dep.group_id,
+                        dep.artifact_id,
+                        dep.version,
+                        maven_url,
+                        e
+                    ),
+                }
+            }
+        }
+        Ok(info)
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Download only the headers of every C++ dependency, for IDEs that need headers long before anything is built. No libraries, JNI or Java artifacts are fetched. "]
+    #[doc = "Headers go where [`Self::download_all_cpp_deps_to_folder`] puts them. Returns the include directories, in order, leaving out dependencies without headers and skipped ones."]
+    pub async fn download_all_headers_to_folder<P: AsRef<Path>>(
+        &self,
+        p: P,
+        skip_failed_packages: bool,
+    ) -> Result<Vec<PathBuf>> {
+        self.download_all_headers_to_folder_with_config(
+            p,
+            skip_failed_packages,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_all_headers_to_folder`], with additional options."]
+    pub async fn download_all_headers_to_folder_with_config<P: AsRef<Path>>(
+        &self,
+        p: P,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+    ) -> Result<Vec<PathBuf>> {
+        let path = p.as_ref();
+        let claims = sink::FileClaims::default();
+        let include_dirs = futures::future::try_join_all(
+            self.cpp_dependencies
+                .iter()
+                .filter(|dep| dep.has_headers())
+                .map(|dep| {
+                    self.download_headers_dep(dep, path, skip_failed_packages, config, &claims)
+                }),
+        )
+        .await?;
+        Ok(include_dirs.into_iter().flatten().collect())
+    }
+
+    #[cfg(feature = "download")]
+    async fn download_headers_dep(
+        &self,
+        dep: &CppDependency,
+        path: &Path,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+        claims: &sink::FileClaims,
+    ) -> Result<Option<PathBuf>> {
+        config.cancel.check()?;
+        let coordinate = Coordinate {
+            group_id: dep.group_id.clone(),
+            artifact_id: dep.artifact_id.clone(),
+            version: dep.version.clone(),
+            kind: ArtifactKind::Cpp,
+        };
+        let config = &DownloadConfig {
+            sink: claims.store_for(&config.sink, coordinate.to_string()),
+            ..config.clone()
+        };
+        let header_path = config.layout.include_dir(path, dep);
+        let mut tried_urls = Vec::new();
+        for maven_url in
+            fetch::mirror_order(dep.effective_maven_urls(&self.maven_urls), config, |x| {
+                dep.classifier_url(x, &dep.header_classifier)
+            })
+            .await
+        {
+            tried_urls.push(dep.classifier_url(maven_url, &dep.header_classifier));
+            match config
+                .retry
+                .run(&config.cancel, || {
+                    dep.download_headers_to_folder_with_config(&header_path, maven_url, config)
+                })
+                .await
+            {
+                Ok(_) => {
+                    log_info!(
+                        "Resolved headers of {}:{}:{} from {}",
+                        dep.group_id,
+                        dep.artifact_id,
+                        dep.version,
+                        maven_url
+                    );
+                    return Ok(Some(header_path));
+                }
+                Err(crate::error::Error::Cancelled) => {
+                    if !config.atomic && !config.layout.shares_directories() {
+                        _ = std::fs::remove_dir_all(&header_path);
+                    }
+                    return Err(crate::error::Error::Cancelled);
+                }
+                Err(e @ crate::error::Error::FileCollision { .. }) => return Err(e),
+                Err(e) => {
+                    log_warn!(
+                        "Failed to download headers of {}:{}:{} from {}: {}",
+                        dep.group_id,
+                        dep.artifact_id,
+                        dep.version,
+                        maven_url,
+                        e
+                    );
+                    config.diagnostics.push(Diagnostic::RepositoryFailed {
+                        artifact: coordinate.clone(),
+                        maven_url: maven_url.to_string(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+        if !skip_failed_packages {
+            return Err(crate::error::Error::NotFoundError {
+                group_id: dep.group_id.clone(),
+                artifact_id: dep.artifact_id.clone(),
+                version: dep.version.clone(),
+                tried_urls,
+            });
+        }
+        log_warn!(
+            "Skipping headers of {}:{}:{}, they were not found in any repository",
+            dep.group_id,
+            dep.artifact_id,
+            dep.version
+        );
+        config.tally.skipped();
+        config.diagnostics.push(Diagnostic::Skipped {
+            artifact: coordinate.clone(),
+        });
+        Ok(None)
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Download all java dependencies. Note this does *not* include JNI dependencies. Directory structure follows `<output_folder>/<java_dependency_name>-<java_dependency_version>.jar`. "]
+    #[doc = "Returns the jars of this vendordep's dependencies, in order, leaving out skipped ones and anything else already in the folder."]
+    pub async fn download_all_java_deps_to_folder<P: AsRef<Path>>(
+        &self,
+        p: P,
+        skip_failed_packages: bool,
+    ) -> Result<Vec<PathBuf>> {
+        self.download_all_java_deps_to_folder_with_config(
+            p,
+            skip_failed_packages,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Like [`Self::download_all_java_deps_to_folder`], with additional options."]
+    pub async fn download_all_java_deps_to_folder_with_config<P: AsRef<Path>>(
+        &self,
+        p: P,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+    ) -> Result<Vec<PathBuf>> {
+        let path = p.as_ref();
+        std::fs::create_dir_all(path)?;
+        let deps = self.java_dependencies.iter().filter(|dep| {
+            let Some(platform) = &config.java_platform else {
+                return true;
+            };
+            let supported = dep.supports_platform(platform);
+            if !supported {
+                log_debug!(
+                    "Leaving out {}, which is for another platform than {}",
+                    dep.file_name(),
+                    platform.to_str()
+                );
+            }
+            supported
+        });
+        let jars = futures::future::try_join_all(
+            deps.map(|dep| self.download_java_dep(dep, path, skip_failed_packages, config)),
+        )
+        .await?;
+        Ok(jars.into_iter().flatten().collect())
+    }
+
+    #[cfg(feature = "download")]
+    async fn download_java_dep(
+        &self,
+        dep: &JavaDependency,
+        path: &Path,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+    ) -> Result<Option<PathBuf>> {
+        config.cancel.check()?;
+        let coordinate = Coordinate {
+            group_id: dep.group_id.clone(),
+            artifact_id: dep.artifact_id.clone(),
+            version: dep.version.clone(),
+            kind: ArtifactKind::Java,
+        };
+        let mut tried_urls = Vec::new();
+        'outer: {
+            for maven_url in
+                fetch::mirror_order(dep.effective_maven_urls(&self.maven_urls), config, |x| {
+                    dep.get_url(x)
+                })
+                .await
+            {
+                tried_urls.push(dep.get_url(maven_url));
+                match config
+                    .retry
+                    .run(&config.cancel, || {
+                        dep.download_library_to_folder_with_config(path, maven_url, config)
+                    })
+                    .await
+                {
+                    Ok(_) => {
+                        log_info!(
+                            "Resolved {}:{}:{} from {}",
+                            dep.group_id,
+                            dep.artifact_id,
+                            dep.version,
+                            maven_url
+                        );
+                        break 'outer;
+                    }
+                    Err(crate::error::Error::Cancelled) => {
+                        return Err(crate::error::Error::Cancelled);
+                    }
+                    Err(e) => {
+                        log_warn!(
+                            "Failed to download {}:{}:{} from {}: {}",
+                            dep.group_id,
+                            dep.artifact_id,
+                            dep.version,
+                            maven_url,
+                            e
+                        );
+                        config.diagnostics.push(Diagnostic::RepositoryFailed {
+                            artifact: coordinate.clone(),
+                            maven_url: maven_url.to_string(),
+                            error: e.to_string(),
+                        });
+                    }
+                }
+            }
+            if !skip_failed_packages {
+                return Err(crate::error::Error::NotFoundError {
+                    group_id: dep.group_id.clone(),
+                    artifact_id: dep.artifact_id.clone(),
+                    version: dep.version.clone(),
+                    tried_urls,
+                });
+            }
+            log_warn!(
+                "Skipping {}:{}:{}, it was not found in any repository",
+                dep.group_id,
+                dep.artifact_id,
+                dep.version
+            );
+            config.tally.skipped();
+            config.diagnostics.push(Diagnostic::Skipped {
+                artifact: coordinate.clone(),
+            });
+            return Ok(None);
+        }
+        Ok(Some(path.join(dep.file_name())))
+    }
+
+    #[doc = "List the `group:artifact:version` of every Java, JNI and C++ dependency, in that order. Does not deduplicate."]
+    pub fn coordinates(&self) -> Vec<Coordinate> {
+        let java = self.java_dependencies.iter().map(|x| Coordinate {
+            group_id: x.group_id.clone(),
+            artifact_id: x.artifact_id.clone(),
+            version: x.version.clone(),
+            kind: ArtifactKind::Java,
+        });
+        let jni = self.jni_dependencies.iter().map(|x| Coordinate {
+            group_id: x.group_id.clone(),
+            artifact_id: x.artifact_id.clone(),
+            version: x.version.clone(),
+            kind: ArtifactKind::Jni,
+        });
+        let cpp = self.cpp_dependencies.iter().map(|x| Coordinate {
+            group_id: x.group_id.clone(),
+            artifact_id: x.artifact_id.clone(),
+            version: x.version.clone(),
+            kind: ArtifactKind::Cpp,
+        });
+        java.chain(jni).chain(cpp).collect()
+    }
+
+    #[doc = "Serialize to vendordep JSON, indented with one field per line if `pretty` is set and on a single line otherwise. "]
+    #[doc = "Fields are always written in declaration order."]
+    pub fn to_json(&self, pretty: bool) -> Result<String> {
+        if pretty {
+            Ok(serde_json::to_string_pretty(self)?)
+        } else {
+            Ok(serde_json::to_string(self)?)
+        }
+    }
+
+    #[doc = "Sort dependencies by `group:artifact`, conflicts by `offline_file_name` and platform lists alphabetically, "]
+    #[doc = "so that regenerating a vendordep produces the same JSON regardless of the order its parts were discovered in."]
+    pub fn canonicalize(&mut self) {
+        self.conflicts_with
+            .sort_by(|a, b| (&a.offline_file_name, &a.uuid).cmp(&(&b.offline_file_name, &b.uuid)));
+        self.java_dependencies.sort_by(|a, b| {
+            (&a.group_id, &a.artifact_id, &a.classifier).cmp(&(
+                &b.group_id,
+                &b.artifact_id,
+                &b.classifier,
+            ))
+        });
+        self.jni_dependencies
+            .sort_by(|a, b| (&a.group_id, &a.artifact_id).cmp(&(&b.group_id, &b.artifact_id)));
+        self.cpp_dependencies
+            .sort_by(|a, b| (&a.group_id, &a.artifact_id).cmp(&(&b.group_id, &b.artifact_id)));
+        for dep in &mut self.jni_dependencies {
+            dep.valid_platforms.sort();
+        }
+        for dep in &mut self.cpp_dependencies {
+            dep.binary_platforms.sort();
+        }
+    }
+
+    #[doc = "A hash of everything this vendordep declares, for detecting changes or keying a download cache. "]
+    #[doc = "It is computed over the fields rather than the JSON text, with dependencies and platforms sorted first, so formatting and the order of dependencies and platforms don't affect it, "]
+    #[doc = "and it is stable across runs and builds. See [`Self::content_hash_without_uuid`] to leave out [`Self::uuid`]."]
+    pub fn content_hash(&self) -> u64 {
+        self.content_hash_with(true)
+    }
+
+    #[doc = "Like [`Self::content_hash`], but ignoring [`Self::uuid`], for vendordeps generated with a fresh UUID each time."]
+    pub fn content_hash_without_uuid(&self) -> u64 {
+        self.content_hash_with(false)
+    }
+
+    fn content_hash_with(&self, include_uuid: bool) -> u64 {
+        let mut value = serde_json::to_value(self).expect("VendorDep always serializes to JSON");
+        if let Some(fields) = value.as_object_mut() {
+            if !include_uuid {
+                fields.remove("uuid");
+            }
+            // Any fixed order will do, so rather than deserializing to `Self::canonicalize`, sort whole entries by their JSON.
+            for key in [
+                "conflictsWith",
+                "javaDependencies",
+                "jniDependencies",
+                "cppDependencies",
+            ] {
+                if let Some(serde_json::Value::Array(entries)) = fields.get_mut(key) {
+                    for entry in entries.iter_mut() {
+                        for key in ["validPlatforms", "binaryPlatforms"] {
+                            if let Some(serde_json::Value::Array(platforms)) = entry.get_mut(key) {
+                                platforms.sort_by_cached_key(|x| x.to_string());
+                            }
+                        }
+                    }
+                    entries.sort_by_cached_key(|x| x.to_string());
+                }
+            }
+        }
+        // 64-bit FNV-1a, which unlike `DefaultHasher` doesn't change between Rust versions.
+        value
+            .to_string()
+            .bytes()
+            .fold(0xcbf29ce484222325, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+            })
+    }
+
+    #[doc = "Replace `from_prefix` with `to_prefix` at the start of every one of [`Self::maven_urls`] that begins with it, such as to redirect downloads to a local mirror "]
+    #[doc = "without editing the vendordep JSON. Every download reads [`Self::maven_urls`], so this applies to headers, libraries, JNI and Java artifacts alike. "]
+    #[doc = "The `maven_urls` overrides of individual dependencies are rewritten too. Returns how many URLs were rewritten."]
+    pub fn with_maven_override(&mut self, from_prefix: &str, to_prefix: &str) -> usize {
+        let overrides = self
+            .java_dependencies
+            .iter_mut()
+            .filter_map(|x| x.maven_urls.as_mut())
+            .chain(
+                self.jni_dependencies
+                    .iter_mut()
+                    .filter_map(|x| x.maven_urls.as_mut()),
+            )
+            .chain(
+                self.cpp_dependencies
+                    .iter_mut()
+                    .filter_map(|x| x.maven_urls.as_mut()),
+            );
+        let mut rewritten = 0;
+        for url in self.maven_urls.iter_mut().chain(overrides.flatten()) {
+            if let Some(rest) = url.strip_prefix(from_prefix) {
+                *url = format!("{}{}", to_prefix, rest);
+                rewritten += 1;
+            }
+        }
+        rewritten
+    }
+
+    #[doc = "Point [`Self::json_url`] at `new_base`, keeping its file name, for an index that moved to other hosting: "]
+    #[doc = "`https://example.com/index/Phoenix6.json` becomes `<new_base>/Phoenix6.json`. A trailing `/` on `new_base` is optional. "]
+    #[doc = "Returns whether `json_url` changed; an empty one is left alone."]
+    pub fn rewrite_json_url_base(&mut self, new_base: &str) -> bool {
+        let Some(file_name) = self.json_url.rsplit('/').next().filter(|x| !x.is_empty()) else {
+            return false;
+        };
+        let json_url = format!("{}/{}", new_base.trim_end_matches('/'), file_name);
+        if json_url == self.json_url {
+            return false;
+        }
+        self.json_url = json_url;
+        true
+    }
+
+    #[doc = "Find every `conflicts_with` entry in `deps` whose `offline_file_name` is not the `file_name` of another vendordep in `deps`."]
+    pub fn dangling_conflicts(deps: &[VendorDep]) -> Vec<DanglingConflict> {
+        let file_names = deps
+            .iter()
+            .map(|x| x.file_name.as_str())
+            .collect::<HashSet<_>>();
+        deps.iter()
+            .flat_map(|dep| {
+                dep.conflicts_with
+                    .iter()
+                    .filter(|x| !file_names.contains(x.offline_file_name.as_str()))
+                    .map(|x| DanglingConflict {
+                        file_name: dep.file_name.clone(),
+                        offline_file_name: x.offline_file_name.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    #[doc = "Write each of `deps` to `dir`/`file_name`, laid out like a GradleRIO `vendordeps/` directory. "]
+    #[doc = "Returns the `conflicts_with` entries that don't refer to any of `deps` (see [`VendorDep::dangling_conflicts`]), which are also logged as warnings."]
+    pub fn write_vendordeps_dir(deps: &[VendorDep], dir: &Path) -> Result<Vec<DanglingConflict>> {
+        std::fs::create_dir_all(dir)?;
+        for dep in deps {
+            // GradleRIO only ever reads vendordeps directly inside the directory.
+            if Path::new(&dep.file_name).file_name() != Some(std::ffi::OsStr::new(&dep.file_name)) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("{} is not a plain file name", dep.file_name),
+                )
+                .into());
+            }
+            let json = dep.to_json(true)?;
+            std::fs::write(dir.join(&dep.file_name), json)?;
+        }
+        let dangling = Self::dangling_conflicts(deps);
+        for x in &dangling {
+            log_warn!(
+                "{} conflicts with {}, which is not in {}",
+                x.file_name,
+                x.offline_file_name,
+                dir.display()
+            );
+        }
+        Ok(dangling)
+    }
+
+    #[doc = "Read every `.json` file directly inside `dir`, such as a GradleRIO project's `vendordeps/` directory, sorted by file name. "]
+    #[doc = "Fails on the first file that isn't a valid vendordep; see [`VendorDep::load_vendordeps_dir_with_errors`] to keep going."]
+    pub fn load_vendordeps_dir<P: AsRef<Path>>(dir: P) -> Result<Vec<VendorDep>> {
+        Self::vendordep_files(dir.as_ref())?
+            .iter()
+            .map(Self::from_path)
+            .collect()
+    }
+
+    #[doc = "Like [`VendorDep::load_vendordeps_dir`], but collects the files that couldn't be read or parsed along with their errors instead of failing. "]
+    #[doc = "Only listing `dir` itself can fail."]
+    pub fn load_vendordeps_dir_with_errors<P: AsRef<Path>>(
+        dir: P,
+    ) -> Result<(Vec<VendorDep>, Vec<(PathBuf, error::Error)>)> {
+        let mut deps = Vec::new();
+        let mut errors = Vec::new();
+        for path in Self::vendordep_files(dir.as_ref())? {
+            match Self::from_path(&path) {
+                Ok(dep) => deps.push(dep),
+                Err(e) => errors.push((path, e)),
+            }
+        }
+        Ok((deps, errors))
+    }
+
+    // The `.json` files directly in `dir`, sorted.
+    fn vendordep_files(dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        for item in std::fs::read_dir(dir)? {
+            let item = item?;
+            let path = item.path();
+            if item.file_type()?.is_file() && path.extension().is_some_and(|x| x == "json") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    #[doc = "Remove everything under a directory laid out by [`VendorDep::download_all_to_folder`] that none of `deps` would produce: "]
+    #[doc = "jars in `java/` other than their Java dependencies' file names (and the `.meta` files of [`DownloadConfig::conditional`]), and directories in `jni/` and `cpp/` other than their artifact ids. "]
+    #[doc = "Anything outside those three directories is left alone. With `dry_run`, nothing is deleted. Returns the (would-be) deleted paths, sorted."]
+    pub fn prune_unreferenced(
+        root: &Path,
+        deps: &[VendorDep],
+        dry_run: bool,
+    ) -> Result<Vec<PathBuf>> {
+        let mut referenced = HashSet::new();
+        for dep in deps {
+            for x in &dep.java_dependencies {
+                let path = root.join("java").join(x.file_name());
+                // Headers recorded for conditional downloads.
+                let mut meta = path.clone().into_os_string();
+                meta.push(".meta");
+                referenced.insert(PathBuf::from(meta));
+                referenced.insert(path);
+            }
+            referenced.extend(
+                dep.jni_dependencies
+                    .iter()
+                    .map(|x| root.join("jni").join(&x.artifact_id)),
+            );
+            referenced.extend(
+                dep.cpp_dependencies
+                    .iter()
+                    .map(|x| root.join("cpp").join(&x.artifact_id)),
+            );
+        }
+        let mut stale = Vec::new();
+        for dir in ["java", "jni", "cpp"] {
+            let entries = match std::fs::read_dir(root.join(dir)) {
+                Ok(x) => x,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            for entry in entries {
+                let path = entry?.path();
+                if !referenced.contains(&path) {
+                    stale.push(path);
+                }
+            }
+        }
+        stale.sort();
+        for path in &stale {
+            log_info!(
+                "{} {}",
+                if dry_run { "Would remove" } else { "Removing" },
+                path.display()
+            );
+            if dry_run {
+                continue;
+            }
+            if path.is_dir() {
+                std::fs::remove_dir_all(path)?;
+            } else {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Ok(stale)
+    }
+}
+
+impl std::str::FromStr for VendorDep {
+    type Err = error::Error;
+
+    #[doc = "Parse vendordep JSON from a string, such as one embedded with `include_str!`. Like [`VendorDep::from_slice`], a byte order mark and surrounding whitespace are ignored."]
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_slice(s.as_bytes())
+    }
+}
+
+#[doc(hidden)]
+mod __private {
+    use serde::{Deserialize, Deserializer};
+
+    pub fn default_packaging() -> String {
+        "jar".to_string()
+    }
+
+    pub fn is_default_packaging(packaging: &str) -> bool {
+        packaging == "jar"
+    }
+
+    pub fn deserialize_string_or_u32_for_u32<'de, D>(deserializer: D) -> Result<u32, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Inner {
+            Int(u32),
+            String(String),
+        }
+
+        let res = Inner::deserialize(deserializer)?;
+        match res {
+            Inner::Int(x) => Ok(x),
+            Inner::String(x) => x
+                .parse()
+                .map_err(|x| <D::Error as serde::de::Error>::custom(x)),
+        }
+    }
+
+    pub fn deserialize_uuid<'de, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let res = String::deserialize(deserializer)?;
+        match uuid::Uuid::parse_str(&res) {
+            Ok(_) => Ok(res),
+            Err(e) => Err(<D::Error as serde::de::Error>::custom(format_args!(
+                "invalid uuid {:?}: {}",
+                res, e
+            ))),
+        }
+    }
+}
+
+#[allow(unused)]
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    #[cfg(all(feature = "download", unix))]
+    use crate::test_util::{cpp_dep, java_dep, jni_dep, platforms, Mirror};
+    #[cfg(feature = "download")]
+    use crate::test_util::{runtime, UUID};
+    use crate::{
+        java_classpath, ArtifactKind, BinaryPlatform, Coordinate, CppDependency, CppInfo,
+        DanglingConflict, JavaDependency, JniDependency, JniInfo, VendorDep,
+        WPILIB_RELEASE_MAVEN_REPO,
+    };
+
+    #[cfg(feature = "download")]
+    #[test]
+    #[ignore = "downloads from the CTRE Maven repository"]
+    fn ctre_2024_headers() {
+        runtime()
+            .block_on(async {
+                let res = VendorDep::from_url("https://maven.ctr-electronics.com/release/com/ctre/phoenix6/latest/Phoenix6-frc2024-latest.json").await;
+                assert!(res.is_ok(), "Failed to download from url");
+                let ctre_vendordep = res.unwrap();
+                let temp_dir = tempdir().unwrap();
+                let res = ctre_vendordep.cpp_dependencies[0].download_headers_to_folder(temp_dir.path(), &ctre_vendordep.maven_urls[0]).await;
+                assert!(res.is_ok(), "Failed to download headers");
+                assert!(temp_dir.path().join("ctre/phoenix6/CANcoder.hpp").exists(), "Did not unzip properly!");
+            })
+    }
+
+    #[test]
+    fn phoenix6_urls() {
+        let phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let maven_url = &phoenix.maven_urls[0];
+        assert_eq!(
+            phoenix.java_dependencies[0].get_url(maven_url),
+            "https://maven.ctr-electronics.com/release/com/ctre/phoenix6/wpiapi-java/24.3.0/wpiapi-java-24.3.0.jar"
+        );
+        assert_eq!(
+            phoenix.jni_dependencies[1].get_url(maven_url, "osxuniversal", false),
+            "https://maven.ctr-electronics.com/release/com/ctre/phoenix6/sim/tools-sim/24.3.0/tools-sim-24.3.0-osxuniversal.zip"
+        );
+        let cpp = &phoenix.cpp_dependencies[0];
+        assert_eq!(
+            cpp.get_url(maven_url, "linuxathena", true, false),
+            "https://maven.ctr-electronics.com/release/com/ctre/phoenix6/wpiapi-cpp/24.3.0/wpiapi-cpp-24.3.0-linuxathenastatic.zip"
+        );
+        assert_eq!(
+            cpp.get_url(maven_url, &cpp.header_classifier, false, false),
+            "https://maven.ctr-electronics.com/release/com/ctre/phoenix6/wpiapi-cpp/24.3.0/wpiapi-cpp-24.3.0-headers.zip"
+        );
+    }
+
+    #[test]
+    fn pin_latest_url() {
+        assert_eq!(
+            VendorDep::pin_latest_url(
+                "https://maven.ctr-electronics.com/release/com/ctre/phoenix6/latest/Phoenix6-frc2024-latest.json",
+                "24.3.0"
+            )
+            .unwrap(),
+            "https://maven.ctr-electronics.com/release/com/ctre/phoenix6/24.3.0/Phoenix6-frc2024-24.3.0.json"
+        );
+        assert_eq!(
+            VendorDep::pin_latest_url("https://example.com/vendordep/Example-latest.json", "1.2.0")
+                .unwrap(),
+            "https://example.com/vendordep/Example-1.2.0.json"
+        );
+        assert!(VendorDep::pin_latest_url(
+            "https://software-metadata.revrobotics.com/REVLib-2024.json",
+            "2024.2.4"
+        )
+        .is_none());
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn from_latest() {
+        let mirror = tempdir().unwrap();
+        let latest_dir = mirror.path().join("phoenix6/latest");
+        let pinned_dir = mirror.path().join("phoenix6/24.3.0");
+        std::fs::create_dir_all(&latest_dir).unwrap();
+        std::fs::create_dir_all(&pinned_dir).unwrap();
+        let json =
+            String::from_utf8(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json").to_vec())
+                .unwrap();
+        std::fs::write(latest_dir.join("Phoenix6-frc2024-latest.json"), &json).unwrap();
+        let url = format!(
+            "file://{}",
+            latest_dir.join("Phoenix6-frc2024-latest.json").display()
+        );
+        let runtime = runtime();
+        // Without a pinned copy, the latest JSON is used as is.
+        let dep = runtime.block_on(VendorDep::from_latest(&url)).unwrap();
+        assert_eq!(dep.version, "24.3.0");
+        assert_eq!(dep.name, "CTRE-Phoenix (v6)");
+        std::fs::write(
+            pinned_dir.join("Phoenix6-frc2024-24.3.0.json"),
+            json.replace("CTRE-Phoenix (v6)", "CTRE-Phoenix (v6, pinned)"),
+        )
+        .unwrap();
+        let dep = runtime.block_on(VendorDep::from_latest(&url)).unwrap();
+        assert_eq!(dep.name, "CTRE-Phoenix (v6, pinned)");
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn from_urls() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Phoenix6-frc2024.json");
+        std::fs::write(
+            &path,
+            include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"),
+        )
+        .unwrap();
+        let found = format!("file://{}", path.display());
+        let missing = format!("file://{}", dir.path().join("missing.json").display());
+        let deps = runtime().block_on(VendorDep::from_urls(&[&found, &missing, &found]));
+        assert_eq!(deps.len(), 3);
+        assert_eq!(deps[0].as_ref().unwrap().version, "24.3.0");
+        assert!(deps[1].is_err());
+        assert_eq!(deps[2].as_ref().unwrap().version, "24.3.0");
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn from_url_with_raw() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Phoenix6-frc2024.json");
+        let raw = include_str!("../tests/fixtures/Phoenix6-frc2024.json");
+        std::fs::write(&path, raw).unwrap();
+        let (dep, fetched) = runtime()
+            .block_on(VendorDep::from_url_with_raw(&format!(
+                "file://{}",
+                path.display()
+            )))
+            .unwrap();
+        assert_eq!(dep.version, "24.3.0");
+        assert_eq!(fetched, raw);
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn local_maven_repository() {
+        let mirror = tempdir().unwrap();
+        let dep = JavaDependency {
+            group_id: "edu.wpi.first.wpilibj".to_string(),
+            artifact_id: "wpilibj-java".to_string(),
+            version: "2024.3.2".to_string(),
+            classifier: None,
+            packaging: "jar".to_string(),
+            maven_urls: None,
+        };
+        let maven_url = format!("file://{}/", mirror.path().display());
+        let jar_path = mirror
+            .path()
+            .join("edu/wpi/first/wpilibj/wpilibj-java/2024.3.2/wpilibj-java-2024.3.2.jar");
+        std::fs::create_dir_all(jar_path.parent().unwrap()).unwrap();
+        std::fs::write(&jar_path, b"PK\x03\x04").unwrap();
+
+        let out = tempdir().unwrap();
+        runtime().block_on(async {
+            dep.download_library_to_folder(out.path(), &maven_url)
+                .await
+                .unwrap();
+            dep.download_library_to_folder(
+                out.path().join("abs"),
+                &format!("{}/", mirror.path().display()),
+            )
+            .await
+            .unwrap();
+        });
+        assert_eq!(
+            std::fs::read(out.path().join(dep.file_name())).unwrap(),
+            b"PK\x03\x04"
+        );
+        assert!(out.path().join("abs").join(dep.file_name()).exists());
+    }
+
+    #[test]
+    fn cppinfo_to_cmake() {
+        let info = CppInfo {
+            include_dirs: vec!["/opt/frc/My Vendor/include".into()],
+            library_search_paths: vec!["/opt/frc/libs".into()],
+            runtime_search_paths: vec![],
+            libraries: vec!["wpimath".to_string()],
+            static_libraries: vec![],
+        };
+        assert_eq!(
+            info.to_cmake("vendordeps"),
+            "if(NOT TARGET vendordeps)\n  add_library(vendordeps INTERFACE IMPORTED)\n  target_include_directories(vendordeps INTERFACE\n    \"/opt/frc/My Vendor/include\"\n  )\n  target_link_directories(vendordeps INTERFACE\n    \"/opt/frc/libs\"\n  )\n  target_link_libraries(vendordeps INTERFACE\n    \"wpimath\"\n  )\nendif()\n"
+        );
+        assert_eq!(
+            CppInfo::new_empty().to_cmake("empty"),
+            "if(NOT TARGET empty)\n  add_library(empty INTERFACE IMPORTED)\nendif()\n"
+        );
+    }
+
+    #[test]
+    fn cppinfo_to_pkg_config() {
+        let info = CppInfo {
+            include_dirs: vec!["/opt/frc/My Vendor/include".into()],
+            library_search_paths: vec!["/opt/frc/libs".into()],
+            runtime_search_paths: vec![],
+            libraries: vec!["wpimath".to_string(), "wpiutil".to_string()],
+            static_libraries: vec![],
+        };
+        assert_eq!(
+            info.to_pkg_config("wpilib", "2024.3.2"),
+            "Name: wpilib\nDescription: wpilib libraries downloaded by vendordeps\nVersion: 2024.3.2\nCflags: -I/opt/frc/My\\ Vendor/include\nLibs: -L/opt/frc/libs -lwpimath -lwpiutil\n"
+        );
+        assert!(CppInfo::new_empty()
+            .to_pkg_config("empty", "1.0")
+            .ends_with("Cflags: \nLibs: \n"));
+    }
+
+    #[test]
+    fn cppinfo_msvc_args() {
+        let info = CppInfo {
+            include_dirs: vec!["C:/frc/include".into()],
+            library_search_paths: vec!["C:/frc/libs".into()],
+            runtime_search_paths: vec![],
+            libraries: vec!["wpimath".to_string()],
+            static_libraries: vec![],
+        };
+        assert_eq!(
+            info.msvc_include_args().collect::<Vec<_>>(),
+            vec!["/IC:/frc/include"]
+        );
+        assert_eq!(
+            info.msvc_library_path_args().collect::<Vec<_>>(),
+            vec!["/LIBPATH:C:/frc/libs"]
+        );
+        assert_eq!(
+            info.msvc_library_args().collect::<Vec<_>>(),
+            vec!["wpimath.lib"]
+        );
+    }
+
+    #[test]
+    fn cppinfo_to_vscode_cpp_properties() {
+        let info = CppInfo {
+            include_dirs: vec!["/opt/frc/include".into()],
+            library_search_paths: vec![],
+            runtime_search_paths: vec![],
+            libraries: vec![],
+            static_libraries: vec![],
+        };
+        assert_eq!(
+            info.to_vscode_cpp_properties(false),
+            serde_json::json!({ "includePath": ["/opt/frc/include"] })
+        );
+        assert_eq!(
+            info.to_vscode_cpp_properties(true),
+            serde_json::json!({ "includePath": ["/opt/frc/include/**"] })
+        );
+    }
+
+    #[test]
+    fn cppinfo_cargo_directives() {
+        let info = CppInfo {
+            include_dirs: vec!["/opt/frc/include".into()],
+            library_search_paths: vec!["/opt/frc/libs".into()],
+            runtime_search_paths: vec![],
+            libraries: vec!["wpimath".to_string(), "wpiutil".to_string()],
+            static_libraries: vec!["wpiutil".to_string()],
+        };
+        assert_eq!(
+            info.cargo_directives(),
+            vec![
+                "cargo:rustc-link-search=native=/opt/frc/libs",
+                "cargo:rustc-link-lib=dylib=wpimath",
+                "cargo:rustc-link-lib=static=wpiutil",
+                "cargo:rerun-if-changed=/opt/frc/include",
+                "cargo:rerun-if-changed=/opt/frc/libs",
+            ]
+        );
+    }
+
+    #[cfg(feature = "cc")]
+    #[test]
+    fn cppinfo_configure_cc() {
+        let info = CppInfo::new(
+            vec!["/opt/frc/include".into()],
+            vec!["/opt/frc/libs".into()],
+            vec!["wpimath".to_string()],
+        );
+        let mut build = cc::Build::new();
+        build
+            .target("x86_64-unknown-linux-gnu")
+            .host("x86_64-unknown-linux-gnu")
+            .opt_level(0)
+            .debug(false)
+            .cargo_metadata(false);
+        info.configure_cc(&mut build);
+        let compiler = build.get_compiler();
+        assert!(compiler
+            .args()
+            .iter()
+            .any(|x| x == std::ffi::OsStr::new("/opt/frc/include")));
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn java_sources() {
+        let mirror = tempdir().unwrap();
+        let dep = JavaDependency {
+            group_id: "edu.wpi.first.wpilibj".to_string(),
+            artifact_id: "wpilibj-java".to_string(),
+            version: "2024.3.2".to_string(),
+            classifier: None,
+            packaging: "jar".to_string(),
+            maven_urls: None,
+        };
+        let maven_url = format!("file://{}/", mirror.path().display());
+        assert_eq!(
+            dep.sources_url("https://frcmaven.wpi.edu/artifactory/release/"),
+            "https://frcmaven.wpi.edu/artifactory/release/edu/wpi/first/wpilibj/wpilibj-java/2024.3.2/wpilibj-java-2024.3.2-sources.jar"
+        );
+
+        let out = tempdir().unwrap();
+        let runtime = runtime();
+        let res = runtime.block_on(dep.download_sources_to_folder(out.path(), &maven_url));
+        assert!(
+            res.unwrap().is_none(),
+            "Missing sources should not be an error"
+        );
+
+        let sources_path = mirror
+            .path()
+            .join("edu/wpi/first/wpilibj/wpilibj-java/2024.3.2/wpilibj-java-2024.3.2-sources.jar");
+        std::fs::create_dir_all(sources_path.parent().unwrap()).unwrap();
+        std::fs::write(&sources_path, b"PK\x03\x04").unwrap();
+        let res = runtime.block_on(dep.download_sources_to_folder(out.path(), &maven_url));
+        assert_eq!(
+            res.unwrap().unwrap(),
+            out.path().join("wpilibj-java-2024.3.2-sources.jar")
+        );
+    }
+
+    #[test]
+    fn java_classifier_url() {
+        let mut dep = JavaDependency {
+            group_id: "com.revrobotics.frc".to_string(),
+            artifact_id: "REVLib-java".to_string(),
+            version: "2024.2.4".to_string(),
+            classifier: None,
+            packaging: "jar".to_string(),
+            maven_urls: None,
+        };
+        assert_eq!(
+            dep.get_url("https://maven.revrobotics.com/"),
+            "https://maven.revrobotics.com/com/revrobotics/frc/REVLib-java/2024.2.4/REVLib-java-2024.2.4.jar"
+        );
+        dep.classifier = Some("linuxathena".to_string());
+        assert_eq!(
+            dep.get_url("https://maven.revrobotics.com/"),
+            "https://maven.revrobotics.com/com/revrobotics/frc/REVLib-java/2024.2.4/REVLib-java-2024.2.4-linuxathena.jar"
+        );
+    }
+
+    #[test]
+    fn mixed_case_coordinates() {
+        let cpp = CppDependency {
+            group_id: "edu.wpi.first.wpimath".to_string(),
+            artifact_id: "wpimath-cpp".to_string(),
+            version: "2024.3.2".to_string(),
+            header_classifier: "headers".to_string(),
+            binary_platforms: vec![],
+            classifier_template: None,
+            maven_urls: None,
+            packaging: None,
+        };
+        assert_eq!(
+            cpp.get_url(WPILIB_RELEASE_MAVEN_REPO, "linuxx86-64", true, true),
+            "https://frcmaven.wpi.edu/artifactory/release/edu/wpi/first/wpimath/wpimath-cpp/2024.3.2/wpimath-cpp-2024.3.2-linuxx86-64staticdebug.zip"
+        );
+        let cpp = CppDependency {
+            group_id: "edu.wpi.first.wpilibNewCommands".to_string(),
+            artifact_id: "wpilibNewCommands-cpp".to_string(),
+            ..cpp
+        };
+        assert_eq!(
+            cpp.get_url(WPILIB_RELEASE_MAVEN_REPO, "headers", false, false),
+            "https://frcmaven.wpi.edu/artifactory/release/edu/wpi/first/wpilibNewCommands/wpilibNewCommands-cpp/2024.3.2/wpilibNewCommands-cpp-2024.3.2-headers.zip"
+        );
+        let jni = JniDependency {
+            group_id: "com.revrobotics.frc".to_string(),
+            artifact_id: "REVLib-driver".to_string(),
+            version: "2024.2.4".to_string(),
+            is_jar: false,
+            skip_invalid_platforms: true,
+            valid_platforms: vec![],
+            sim_mode: None,
+            classifier_template: None,
+            maven_urls: None,
+        };
+        assert_eq!(
+            jni.get_url("https://maven.revrobotics.com/", "windowsx86-64", false),
+            "https://maven.revrobotics.com/com/revrobotics/frc/REVLib-driver/2024.2.4/REVLib-driver-2024.2.4-windowsx86-64.zip"
+        );
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn strict_downloads() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(cpp_dep("example-cpp"))
+            .build();
+        // Headers where the libraries should be.
+        mirror.publish(
+            &dep.cpp_dependencies[0].get_url(&mirror.maven_url, "linuxx86-64", false, false),
+            include_bytes!("../tests/fixtures/headers.zip"),
+        );
+
+        let runtime = runtime();
+        let download = |strict| {
+            let out = tempdir().unwrap();
+            runtime.block_on(dep.download_all_cpp_deps_to_folder_with_config(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                false,
+                &crate::DownloadConfig {
+                    strict,
+                    ..Default::default()
+                },
+            ))
+        };
+        assert!(download(false).unwrap().libraries.is_empty());
+        let err = download(true).unwrap_err();
+        assert!(
+            matches!(err, crate::error::Error::EmptyDependency(x) if x == "com.example:example-cpp:1.0.0")
+        );
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn shared_include_collision() {
+        let mirror = Mirror::new();
+        let cpp = |artifact_id: &str| CppDependency {
+            header_classifier: "headers".to_string(),
+            ..cpp_dep(artifact_id)
+        };
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(cpp("first-cpp"))
+            .add_cpp_dependency(cpp("second-cpp"))
+            .build();
+        for dep in &dep.cpp_dependencies {
+            for (classifier, contents) in [
+                (
+                    "headers",
+                    &include_bytes!("../tests/fixtures/headers.zip")[..],
+                ),
+                (
+                    "linuxx86-64",
+                    &include_bytes!("../tests/fixtures/executable.zip")[..],
+                ),
+            ] {
+                mirror.publish(
+                    &dep.get_url(&mirror.maven_url, classifier, false, false),
+                    contents,
+                );
+            }
+        }
+
+        let runtime = runtime();
+        let download = |layout| {
+            let out = tempdir().unwrap();
+            let res = runtime.block_on(dep.download_all_cpp_deps_to_folder_with_config(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                false,
+                &crate::DownloadConfig {
+                    layout,
+                    ..Default::default()
+                },
+            ));
+            (out, res)
+        };
+        let (_out, res) = download(crate::LayoutStrategy::default());
+        assert_eq!(res.unwrap().include_dirs.len(), 2);
+
+        let (out, res) = download(crate::LayoutStrategy::new(
+            |folder, _| folder.join("include"),
+            |folder, dep| folder.join(&dep.artifact_id).join("libs"),
+        ));
+        match res.unwrap_err() {
+            crate::error::Error::FileCollision {
+                path,
+                first,
+                second,
+            } => {
+                assert!(path.starts_with(out.path().join("include")));
+                let mut owners = [first, second];
+                owners.sort();
+                assert_eq!(
+                    owners,
+                    [
+                        "com.example:first-cpp:1.0.0".to_string(),
+                        "com.example:second-cpp:1.0.0".to_string()
+                    ]
+                );
+            }
+            e => panic!("unexpected error {}", e),
+        }
+    }
+
+    #[test]
+    fn classifier_template() {
+        let cpp: CppDependency = serde_json::from_str(
+            r#"{"groupId": "edu.wpi.first.ni-libraries", "artifactId": "chipobject", "version": "2024.2.1", "classifierTemplate": "{platform}"}"#,
+        )
+        .unwrap();
+        assert!(!cpp.has_headers());
+        assert_eq!(
+            cpp.library_classifier("linuxathena", true, true),
+            "linuxathena"
+        );
+        assert_eq!(
+            cpp.get_url(WPILIB_RELEASE_MAVEN_REPO, "linuxathena", false, true),
+            "https://frcmaven.wpi.edu/artifactory/release/edu/wpi/first/ni-libraries/chipobject/2024.2.1/chipobject-2024.2.1-linuxathena.zip"
+        );
+        assert!(serde_json::to_string(&cpp)
+            .unwrap()
+            .contains(r#""classifierTemplate":"{platform}""#));
+
+        let jni = JniDependency {
+            group_id: "com.example".to_string(),
+            artifact_id: "example-jni".to_string(),
+            version: "1.0.0".to_string(),
+            is_jar: false,
+            skip_invalid_platforms: true,
+            valid_platforms: vec![],
+            sim_mode: None,
+            classifier_template: Some("{platform}-{debug}{static}".to_string()),
+            maven_urls: None,
+        };
+        assert_eq!(
+            jni.file_name("linuxathena", true),
+            "example-jni-1.0.0-linuxathena-debug.zip"
+        );
+        assert_eq!(
+            jni.file_name("linuxathena", false),
+            "example-jni-1.0.0-linuxathena-.zip"
+        );
+        assert_eq!(
+            CppDependency::classifier("linuxathena", true, true),
+            "linuxathenastaticdebug"
+        );
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn arbitrary_classifier() {
+        let mirror = Mirror::new();
+        let maven_url = &mirror.maven_url;
+        let cpp = CppDependency {
+            header_classifier: "headers".to_string(),
+            ..cpp_dep("example-cpp")
+        };
+        assert_eq!(
+            CppDependency::classifier("linuxathena", true, true),
+            "linuxathenastaticdebug"
+        );
+        assert_eq!(
+            cpp.get_url(maven_url, "linuxathena", true, false),
+            cpp.classifier_url(maven_url, "linuxathenastatic")
+        );
+        let zip_path = cpp.classifier_url(maven_url, "linuxathenasymbols");
+        assert!(zip_path
+            .ends_with("com/example/example-cpp/1.0.0/example-cpp-1.0.0-linuxathenasymbols.zip"));
+        mirror.publish(
+            &zip_path,
+            include_bytes!("../tests/fixtures/executable.zip"),
+        );
+
+        let out = tempdir().unwrap();
+        runtime()
+            .block_on(cpp.download_classifier_to_folder(
+                out.path(),
+                &format!("file://{}", maven_url),
+                "linuxathenasymbols",
+            ))
+            .unwrap();
+        assert!(out.path().join("lib/libexample.so").exists());
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn download_to_memory() {
+        let mirror = Mirror::new();
+        let cpp = CppDependency {
+            header_classifier: "headers".to_string(),
+            ..cpp_dep("example-cpp")
+        };
+        for (classifier, contents) in [
+            (
+                "headers",
+                &include_bytes!("../tests/fixtures/headers.zip")[..],
+            ),
+            (
+                "linuxx86-64",
+                &include_bytes!("../tests/fixtures/executable.zip")[..],
+            ),
+        ] {
+            mirror.publish(&cpp.classifier_url(&mirror.maven_url, classifier), contents);
+        }
+
+        let runtime = runtime();
+        let maven_url = format!("file://{}", mirror.maven_url);
+        let headers = runtime
+            .block_on(cpp.download_headers_to_memory(&maven_url))
+            .unwrap();
+        let mut paths = headers.keys().cloned().collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                std::path::PathBuf::from("frc/Example.h"),
+                std::path::PathBuf::from("frc/detail/Impl.h")
+            ]
+        );
+        let libs = runtime
+            .block_on(cpp.download_library_to_memory(
+                &maven_url,
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+            ))
+            .unwrap();
+        assert!(libs.contains_key(std::path::Path::new("lib/libexample.so")));
+        assert!(libs.contains_key(std::path::Path::new("bin/halsim-tool")));
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn available_variants() {
+        let mirror = Mirror::new();
+        let dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let cpp = &dep.cpp_dependencies[0];
+        let jni = &dep.jni_dependencies[0];
+        // Only debug builds are published.
+        for path in [
+            cpp.get_url(&mirror.maven_url, "linuxathena", false, true),
+            cpp.get_url(&mirror.maven_url, "linuxathena", true, true),
+            jni.get_url(&mirror.maven_url, "linuxathena", true),
+        ] {
+            mirror.publish(&path, b"PK\x05\x06");
+        }
+
+        let runtime = runtime();
+        let maven_url = format!("file://{}", mirror.maven_url);
+        let variants = runtime
+            .block_on(cpp.available_variants(&maven_url, &BinaryPlatform::LinuxAthena))
+            .unwrap();
+        assert_eq!(
+            variants,
+            crate::VariantAvailability {
+                release: false,
+                debug: true,
+                static_release: false,
+                static_debug: true,
+            }
+        );
+        assert!(variants.has(true, true));
+        assert!(!variants.has(false, false));
+        assert_eq!(
+            runtime
+                .block_on(jni.available_variants(&maven_url, &BinaryPlatform::LinuxAthena))
+                .unwrap(),
+            crate::VariantAvailability {
+                debug: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            runtime
+                .block_on(cpp.available_variants(&maven_url, &BinaryPlatform::LinuxX86_64))
+                .unwrap(),
+            crate::VariantAvailability::default()
+        );
+    }
+
+    #[test]
+    fn parse_coordinate() {
+        let (coordinate, classifier) = Coordinate::parse(
+            "edu.wpi.first.wpimath:wpimath-cpp:2025.1.1",
+            ArtifactKind::Cpp,
+        )
+        .unwrap();
+        assert_eq!(
+            coordinate.to_string(),
+            "edu.wpi.first.wpimath:wpimath-cpp:2025.1.1"
+        );
+        assert_eq!(coordinate.kind, ArtifactKind::Cpp);
+        assert_eq!(classifier, None);
+        let (coordinate, classifier) =
+            Coordinate::parse("org.ejml:ejml-simple:0.43.1:sources", ArtifactKind::Java).unwrap();
+        assert_eq!(coordinate.artifact_id, "ejml-simple");
+        assert_eq!(classifier.as_deref(), Some("sources"));
+        for invalid in [
+            "",
+            "edu.wpi.first.wpimath:wpimath-cpp",
+            "edu.wpi.first.wpimath::2025.1.1",
+            "edu.wpi.first.wpimath:wpimath-cpp:2025.1.1:headers:extra",
+        ] {
+            assert!(
+                matches!(
+                    Coordinate::parse(invalid, ArtifactKind::Cpp),
+                    Err(crate::error::Error::InvalidCoordinate(x)) if x == invalid
+                ),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn download_coordinate() {
+        let mirror = Mirror::new();
+        let maven_url = &mirror.maven_url;
+        mirror.publish(&java_dep("example-java").get_url(maven_url), b"PK\x03\x04");
+
+        let out = tempdir().unwrap();
+        let runtime = runtime();
+        runtime
+            .block_on(crate::download_coordinate(
+                "com.example:example-java:1.0.0",
+                ArtifactKind::Java,
+                out.path(),
+                maven_url,
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+            ))
+            .unwrap();
+        assert!(out.path().join("example-java-1.0.0.jar").exists());
+        let err = runtime
+            .block_on(crate::download_coordinate(
+                "com.example:example-jni:1.0.0:linuxx86-64",
+                ArtifactKind::Jni,
+                out.path(),
+                maven_url,
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+            ))
+            .unwrap_err();
+        assert!(matches!(err, crate::error::Error::InvalidCoordinate(_)));
+    }
+
+    #[test]
+    fn file_date() {
+        let mut json: serde_json::Value =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let phoenix: VendorDep = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(phoenix.date, None);
+        assert!(serde_json::to_value(&phoenix)
             .unwrap()
-            .block_on(async {
-                let res = VendorDep::from_url("https://maven.ctr-electronics.com/release/com/ctre/phoenix6/latest/Phoenix6-frc2024-latest.json").await;
-                assert!(res.is_ok(), "Failed to download from url");
-                let ctre_vendordep = res.unwrap();
-                let temp_dir = tempdir().unwrap();
-                let res = ctre_vendordep.cpp_dependencies[0].download_headers_to_folder(temp_dir.path(), &ctre_vendordep.maven_urls[0]).await;
-                assert!(res.is_ok(), "Failed to download headers");
-                assert!(temp_dir.path().join("ctre/phoenix6/CANcoder.hpp").exists(), "Did not unzip properly!");
+            .get("date")
+            .is_none());
+
+        json["fileDate"] = "2024-03-01T12:00:00Z".into();
+        let phoenix: VendorDep = serde_json::from_value(json).unwrap();
+        assert_eq!(phoenix.date.as_deref(), Some("2024-03-01T12:00:00Z"));
+        let round_trip: VendorDep =
+            serde_json::from_value(serde_json::to_value(&phoenix).unwrap()).unwrap();
+        assert_eq!(round_trip.date, phoenix.date);
+    }
+
+    #[test]
+    fn native_file_names() {
+        let phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let jni = &phoenix.jni_dependencies[0];
+        assert_eq!(
+            jni.file_name("linuxx86-64", true),
+            "tools-24.3.0-linuxx86-64debug.zip"
+        );
+        assert!(jni
+            .get_url(
+                "https://maven.ctr-electronics.com/release/",
+                "linuxx86-64",
+                true
+            )
+            .ends_with("/com/ctre/phoenix6/tools/24.3.0/tools-24.3.0-linuxx86-64debug.zip"));
+        let cpp = &phoenix.cpp_dependencies[0];
+        assert_eq!(
+            cpp.file_name("linuxathena", true, true),
+            "wpiapi-cpp-24.3.0-linuxathenastaticdebug.zip"
+        );
+        assert_eq!(
+            cpp.file_name(&cpp.header_classifier, false, false),
+            "wpiapi-cpp-24.3.0-headers.zip"
+        );
+    }
+
+    #[test]
+    fn java_packaging() {
+        let dep: JavaDependency = serde_json::from_str(
+            r#"{"groupId": "com.example", "artifactId": "example-android", "version": "1.0.0", "packaging": "aar"}"#,
+        )
+        .unwrap();
+        assert_eq!(dep.file_name(), "example-android-1.0.0.aar");
+        let dep: JavaDependency = serde_json::from_str(
+            r#"{"groupId": "com.example", "artifactId": "example-java", "version": "1.0.0"}"#,
+        )
+        .unwrap();
+        assert_eq!(dep.file_name(), "example-java-1.0.0.jar");
+        assert!(!serde_json::to_string(&dep).unwrap().contains("packaging"));
+    }
+
+    #[test]
+    fn write_vendordeps_dir() {
+        let phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let dir = tempdir().unwrap();
+        let dangling = VendorDep::write_vendordeps_dir(&[phoenix], dir.path()).unwrap();
+        assert_eq!(
+            dangling,
+            vec![DanglingConflict {
+                file_name: "Phoenix6.json".to_string(),
+                offline_file_name: "Phoenix6And5.json".to_string(),
+            }]
+        );
+        let written: VendorDep =
+            serde_json::from_slice(&std::fs::read(dir.path().join("Phoenix6.json")).unwrap())
+                .unwrap();
+        assert_eq!(
+            written.conflicts_with[0].offline_file_name,
+            "Phoenix6And5.json"
+        );
+    }
+
+    #[test]
+    fn load_vendordeps_dir() {
+        let phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let wpilib = VendorDep::wpilib_core(2024, "2024.3.2");
+        let dir = tempdir().unwrap();
+        VendorDep::write_vendordeps_dir(&[phoenix, wpilib], dir.path()).unwrap();
+        std::fs::write(dir.path().join("README.md"), "not a vendordep").unwrap();
+        std::fs::create_dir(dir.path().join("nested.json")).unwrap();
+
+        let deps = VendorDep::load_vendordeps_dir(dir.path()).unwrap();
+        assert_eq!(
+            deps.iter()
+                .map(|x| x.file_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Phoenix6.json", "WPILib.json"]
+        );
+
+        std::fs::write(dir.path().join("Broken.json"), "{").unwrap();
+        assert!(VendorDep::load_vendordeps_dir(dir.path()).is_err());
+        let (deps, errors) = VendorDep::load_vendordeps_dir_with_errors(dir.path()).unwrap();
+        assert_eq!(deps.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, dir.path().join("Broken.json"));
+        assert!(matches!(errors[0].1, crate::error::Error::JsonError(_)));
+    }
+
+    #[test]
+    fn prune_unreferenced() {
+        let phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let jar = root
+            .join("java")
+            .join(phoenix.java_dependencies[0].file_name());
+        for path in [
+            jar.clone(),
+            root.join("java/wpiapi-java-23.0.0.jar"),
+            root.join("cpp/wpiapi-cpp/include/ctre/Phoenix.h"),
+            root.join("cpp/api-cpp/include/ctre/Phoenix.h"),
+            root.join("jni/tools/linux/x86-64/shared/libCTRE_PhoenixTools.so"),
+            root.join("other/keep.txt"),
+        ] {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+        let expected = vec![
+            root.join("cpp/api-cpp"),
+            root.join("java/wpiapi-java-23.0.0.jar"),
+        ];
+        assert_eq!(
+            VendorDep::prune_unreferenced(root, std::slice::from_ref(&phoenix), true).unwrap(),
+            expected
+        );
+        assert!(root.join("cpp/api-cpp").exists());
+        assert_eq!(
+            VendorDep::prune_unreferenced(root, &[phoenix], false).unwrap(),
+            expected
+        );
+        assert!(!root.join("cpp/api-cpp").exists());
+        assert!(!root.join("java/wpiapi-java-23.0.0.jar").exists());
+        assert!(jar.exists());
+        assert!(root.join("cpp/wpiapi-cpp").exists());
+        assert!(root.join("jni/tools").exists());
+        assert!(root.join("other/keep.txt").exists());
+    }
+
+    #[test]
+    fn invalid_uuid() {
+        let json =
+            String::from_utf8(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json").to_vec())
+                .unwrap();
+        let err = serde_json::from_str::<VendorDep>(
+            &json.replace("e995de00-2c64-4df5-8831-c1441420ff19", "not-a-uuid"),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid uuid \"not-a-uuid\""));
+        let err = serde_json::from_str::<VendorDep>(
+            &json.replace("3fcf3402-e646-4fa6-971e-18afe8173b1a", "not-a-uuid"),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid uuid \"not-a-uuid\""));
+    }
+
+    #[test]
+    fn to_json() {
+        let dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let compact = dep.to_json(false).unwrap();
+        assert!(!compact.contains('\n'));
+        assert!(compact.starts_with(r#"{"fileName":"Phoenix6.json","name":"CTRE-Phoenix (v6)","#));
+        let pretty = dep.to_json(true).unwrap();
+        assert!(pretty.starts_with("{\n  \"fileName\": \"Phoenix6.json\",\n"));
+        let compact_again: VendorDep = serde_json::from_str(&pretty).unwrap();
+        assert_eq!(compact_again.to_json(false).unwrap(), compact);
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        let bytes = include_bytes!("../tests/fixtures/Phoenix6-frc2024-bom.json");
+        assert!(serde_json::from_slice::<VendorDep>(bytes).is_err());
+        let dep = VendorDep::from_slice(bytes).unwrap();
+        assert_eq!(dep.version, "24.3.0");
+        let dep: VendorDep = std::str::from_utf8(bytes).unwrap().parse().unwrap();
+        assert_eq!(dep.version, "24.3.0");
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("Phoenix6.json"), bytes).unwrap();
+        let dep = VendorDep::from_path(dir.path().join("Phoenix6.json")).unwrap();
+        assert_eq!(dep.version, "24.3.0");
+    }
+
+    #[test]
+    fn maven_override() {
+        let mut dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        dep.maven_urls
+            .push("https://frcmaven.wpi.edu/artifactory/release/".to_string());
+        assert_eq!(
+            dep.with_maven_override(
+                "https://maven.ctr-electronics.com/",
+                "http://localmirror/artifactory/"
+            ),
+            1
+        );
+        assert_eq!(
+            dep.maven_urls,
+            vec![
+                "http://localmirror/artifactory/release/",
+                "https://frcmaven.wpi.edu/artifactory/release/"
+            ]
+        );
+        assert_eq!(
+            dep.cpp_dependencies[0].get_url(&dep.maven_urls[0], "headers", false, false),
+            "http://localmirror/artifactory/release/com/ctre/phoenix6/wpiapi-cpp/24.3.0/wpiapi-cpp-24.3.0-headers.zip"
+        );
+        assert_eq!(
+            dep.with_maven_override("https://example.com/", "/mirror/"),
+            0
+        );
+    }
+
+    #[test]
+    fn rewrite_json_url_base() {
+        let mut dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let file_name = dep.json_url.rsplit('/').next().unwrap().to_string();
+        assert!(dep.rewrite_json_url_base("https://example.com/fork/wpilib/"));
+        assert_eq!(
+            dep.json_url,
+            format!("https://example.com/fork/wpilib/{}", file_name)
+        );
+        assert!(!dep.rewrite_json_url_base("https://example.com/fork/wpilib"));
+
+        dep.json_url.clear();
+        assert!(!dep.rewrite_json_url_base("https://example.com/fork/wpilib"));
+        assert!(dep.json_url.is_empty());
+    }
+
+    #[test]
+    fn canonicalize() {
+        let mut dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let expected = {
+            dep.canonicalize();
+            dep.to_json(true).unwrap()
+        };
+        dep.cpp_dependencies.reverse();
+        dep.jni_dependencies.reverse();
+        dep.jni_dependencies[0].valid_platforms.reverse();
+        dep.cpp_dependencies[1].binary_platforms.reverse();
+        dep.canonicalize();
+        assert_eq!(dep.to_json(true).unwrap(), expected);
+        assert_eq!(dep.cpp_dependencies[0].artifact_id, "wpiapi-cpp");
+        assert_eq!(
+            dep.jni_dependencies[0].valid_platforms,
+            vec!["linuxathena", "linuxx86-64", "windowsx86-64"]
+        );
+    }
+
+    #[test]
+    fn content_hash() {
+        let json = include_str!("../tests/fixtures/Phoenix6-frc2024.json");
+        let dep: VendorDep = json.parse().unwrap();
+        let hash = dep.content_hash();
+
+        let mut reordered: VendorDep = dep.to_json(false).unwrap().parse().unwrap();
+        assert_eq!(reordered.content_hash(), hash);
+        reordered.cpp_dependencies.reverse();
+        reordered.jni_dependencies[0].valid_platforms.reverse();
+        assert_eq!(reordered.content_hash(), hash);
+
+        reordered.uuid = "0d8c4f2e-6b1a-4e3f-9c7d-5a2b8e1f3c60".to_string();
+        assert_ne!(reordered.content_hash(), hash);
+        assert_eq!(
+            reordered.content_hash_without_uuid(),
+            dep.content_hash_without_uuid()
+        );
+        reordered.cpp_dependencies[0].version = "24.3.1".to_string();
+        assert_ne!(
+            reordered.content_hash_without_uuid(),
+            dep.content_hash_without_uuid()
+        );
+
+        // Only parsing checks the UUID.
+        let built = VendorDep::builder("Example", "not-a-uuid").build();
+        assert_ne!(built.content_hash(), built.content_hash_without_uuid());
+    }
+
+    #[test]
+    fn coordinates() {
+        let dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let coordinates = dep.coordinates();
+        assert_eq!(
+            coordinates
+                .iter()
+                .map(|x| (x.to_string(), x.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "com.ctre.phoenix6:wpiapi-java:24.3.0".to_string(),
+                    ArtifactKind::Java
+                ),
+                (
+                    "com.ctre.phoenix6:tools:24.3.0".to_string(),
+                    ArtifactKind::Jni
+                ),
+                (
+                    "com.ctre.phoenix6.sim:tools-sim:24.3.0".to_string(),
+                    ArtifactKind::Jni
+                ),
+                (
+                    "com.ctre.phoenix6:wpiapi-cpp:24.3.0".to_string(),
+                    ArtifactKind::Cpp
+                ),
+                (
+                    "com.ctre.phoenix6.sim:wpiapi-cpp-sim:24.3.0".to_string(),
+                    ArtifactKind::Cpp
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn cppinfo_dedup() {
+        let mut info = CppInfo {
+            include_dirs: vec![
+                "/a/include".into(),
+                "/b/include".into(),
+                "/a/include".into(),
+            ],
+            library_search_paths: vec!["/a/lib".into(), "/a/lib".into()],
+            runtime_search_paths: vec![],
+            libraries: vec![
+                "wpiutil".to_string(),
+                "ntcore".to_string(),
+                "wpiutil".to_string(),
+            ],
+            static_libraries: vec![],
+        };
+        info.dedup();
+        assert_eq!(
+            info.include_dirs,
+            vec![std::path::PathBuf::from("/a/include"), "/b/include".into()]
+        );
+        assert_eq!(
+            info.library_search_paths,
+            vec![std::path::PathBuf::from("/a/lib")]
+        );
+        assert_eq!(info.libraries, vec!["wpiutil", "ntcore"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cppinfo_canonicalize() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("real/include")).unwrap();
+        std::fs::create_dir_all(root.join("real/lib")).unwrap();
+        std::os::unix::fs::symlink(root.join("real"), root.join("link")).unwrap();
+        let info = CppInfo {
+            include_dirs: vec![
+                root.join("link/include"),
+                root.join("real/lib/../include"),
+                root.join("missing/include"),
+            ],
+            library_search_paths: vec![root.join("link/lib")],
+            runtime_search_paths: vec![root.join("link/lib")],
+            libraries: vec!["example".to_string()],
+        };
+
+        let mut strict = info.clone();
+        assert!(matches!(
+            strict.canonicalize_strict(),
+            Err(crate::error::Error::IoError(_))
+        ));
+        assert_eq!(strict.include_dirs, info.include_dirs);
+
+        let mut info = info;
+        info.canonicalize().unwrap();
+        assert_eq!(
+            info.include_dirs,
+            vec![root.join("real/include"), root.join("real/include")]
+        );
+        assert_eq!(info.library_search_paths, vec![root.join("real/lib")]);
+        assert_eq!(info.runtime_search_paths, vec![root.join("real/lib")]);
+        assert_eq!(info.libraries, vec!["example"]);
+    }
+
+    #[cfg(feature = "download")]
+    #[test]
+    fn windows_import_libraries() {
+        let dir = tempdir().unwrap();
+        let libs = dir.path().join("wpiutil-cpp/libs/windows/x86-64/shared");
+        for path in [
+            libs.join("lib/wpiutil.lib"),
+            libs.join("bin/wpiutil.dll"),
+            libs.join("bin/wpiutil.pdb"),
+        ] {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+        let info = CppInfo::from_existing(dir.path()).unwrap();
+        assert_eq!(info.libraries, vec!["wpiutil"]);
+        assert_eq!(info.library_search_paths, vec![libs.join("lib")]);
+        assert_eq!(info.runtime_search_paths, vec![libs.join("bin")]);
+        assert_eq!(
+            info.msvc_library_args().collect::<Vec<_>>(),
+            vec!["wpiutil.lib"]
+        );
+        assert!(info.static_libraries.is_empty());
+    }
+
+    #[cfg(feature = "download")]
+    #[test]
+    fn static_libraries() {
+        let dir = tempdir().unwrap();
+        for path in [
+            "wpiutil-cpp/libs/windows/x86-64/static/wpiutil.lib",
+            "ntcore-cpp/libs/linux/x86-64/static/libntcore.a",
+            "wpimath-cpp/libs/linux/x86-64/shared/libwpimath.so",
+        ] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+        let mut info = CppInfo::from_existing(dir.path()).unwrap();
+        info.libraries.sort();
+        info.static_libraries.sort();
+        assert_eq!(info.libraries, vec!["ntcore", "wpimath", "wpiutil"]);
+        assert_eq!(info.static_libraries, vec!["ntcore", "wpiutil"]);
+        assert!(info
+            .cargo_directives()
+            .contains(&"cargo:rustc-link-lib=dylib=wpimath".to_string()));
+        assert!(info
+            .cargo_directives()
+            .contains(&"cargo:rustc-link-lib=static=wpiutil".to_string()));
+    }
+
+    #[test]
+    fn cppinfo_round_trip() {
+        let info = CppInfo {
+            include_dirs: vec!["/vendor/wpiutil-cpp/include".into()],
+            library_search_paths: vec!["/vendor/wpiutil-cpp/linux/x86-64/shared".into()],
+            runtime_search_paths: vec!["/vendor/wpiutil-cpp/linux/x86-64/shared".into()],
+            libraries: vec!["wpiutil".to_string()],
+            static_libraries: vec![],
+        };
+        let loaded: CppInfo = serde_json::from_str(&serde_json::to_string(&info).unwrap()).unwrap();
+        assert_eq!(
+            loaded.gcc_clang_args().collect::<Vec<_>>(),
+            info.gcc_clang_args().collect::<Vec<_>>()
+        );
+        assert_eq!(loaded.runtime_search_paths, info.runtime_search_paths);
+    }
+
+    #[test]
+    fn jni_info() {
+        let info = JniInfo::from(CppInfo {
+            include_dirs: vec![],
+            library_search_paths: vec![],
+            runtime_search_paths: vec!["/jni/wpiutil".into(), "/jni/ntcore".into()],
+            libraries: vec![
+                "wpiutiljni".to_string(),
+                "ntcorejni".to_string(),
+                "wpiutiljni".to_string(),
+            ],
+            static_libraries: vec![],
+        });
+        assert_eq!(info.library_names, vec!["wpiutiljni", "ntcorejni"]);
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        assert_eq!(
+            info.jvm_arg(),
+            format!("-Djava.library.path=/jni/wpiutil{}/jni/ntcore", separator)
+        );
+    }
+
+    #[test]
+    fn cpp_info_constructors() {
+        let info = CppInfo::new(
+            vec!["/opt/example/include".into()],
+            vec!["/opt/example/lib".into()],
+            vec!["example".to_string()],
+        )
+        .with_include_dir("/opt/other/include")
+        .with_library_search_path("/opt/other/lib")
+        .with_library("other");
+        assert_eq!(
+            info.gcc_clang_args().collect::<Vec<_>>(),
+            vec![
+                "-I/opt/example/include",
+                "-I/opt/other/include",
+                "-L/opt/example/lib",
+                "-L/opt/other/lib",
+                "-lexample",
+                "-lother",
+            ]
+        );
+        assert_eq!(info.runtime_search_paths, info.library_search_paths);
+    }
+
+    #[test]
+    fn runtime_env() {
+        let mut info = CppInfo::new_empty();
+        assert!(info.runtime_env(BinaryPlatform::LinuxX86_64).is_empty());
+        info.runtime_search_paths = vec!["/jni/wpiutil".into(), "/jni/ntcore".into()];
+        let env = info.runtime_env(BinaryPlatform::WindowsX86_64);
+        assert_eq!(env.len(), 1);
+        let inherited = std::env::var("PATH").unwrap_or_default();
+        let expected = if inherited.is_empty() {
+            "/jni/wpiutil;/jni/ntcore".to_string()
+        } else {
+            format!("{};/jni/wpiutil;/jni/ntcore", inherited)
+        };
+        assert_eq!(env["PATH"], expected);
+        assert!(info
+            .runtime_env(BinaryPlatform::OsxUniversal)
+            .contains_key("DYLD_LIBRARY_PATH"));
+        assert!(info
+            .runtime_env(BinaryPlatform::LinuxAthena)
+            .contains_key("LD_LIBRARY_PATH"));
+    }
+
+    #[test]
+    fn classpath() {
+        assert_eq!(java_classpath(&[]), "");
+        let jars = vec![
+            std::path::PathBuf::from("/java/wpiutil-java-2024.3.2.jar"),
+            "/java/ntcore-java-2024.3.2.jar".into(),
+        ];
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        assert_eq!(
+            java_classpath(&jars),
+            format!(
+                "/java/wpiutil-java-2024.3.2.jar{}/java/ntcore-java-2024.3.2.jar",
+                separator
+            )
+        );
+    }
+
+    #[test]
+    fn wpilib_round_trip() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("wpilib");
+        for item in std::fs::read_dir(dir).unwrap() {
+            let path = item.unwrap().path();
+            let json = std::fs::read_to_string(&path).unwrap();
+            let dep: VendorDep = json.parse().unwrap();
+            assert_eq!(path.file_name().unwrap().to_str().unwrap(), dep.file_name);
+            let original: serde_json::Value = serde_json::from_str(&json).unwrap();
+            let written: serde_json::Value =
+                serde_json::from_str(&dep.to_json(true).unwrap()).unwrap();
+            assert_eq!(original, written, "{}", path.display());
+            assert_eq!(
+                VendorDep::from_slice(json.as_bytes())
+                    .unwrap()
+                    .to_json(false)
+                    .unwrap(),
+                dep.to_json(false).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn binary_platform_other() {
+        let platforms: Vec<BinaryPlatform> =
+            serde_json::from_str(r#"["linuxathena", "linuxraspbian"]"#).unwrap();
+        assert_eq!(
+            platforms,
+            vec![
+                BinaryPlatform::LinuxAthena,
+                BinaryPlatform::Other("linuxraspbian".to_string())
+            ]
+        );
+        assert_eq!(platforms[1].to_str(), "linuxraspbian");
+        assert_eq!(
+            "windowsx86-64".parse::<BinaryPlatform>().unwrap(),
+            BinaryPlatform::WindowsX86_64
+        );
+    }
+
+    #[test]
+    fn binary_platform_classification() {
+        assert!(BinaryPlatform::LinuxAthena.is_roborio());
+        assert!(!BinaryPlatform::LinuxAthena.is_desktop());
+        assert!(!BinaryPlatform::LinuxAthena.is_simulation_capable());
+        assert!(BinaryPlatform::OsxUniversal.is_desktop());
+        assert!(BinaryPlatform::OsxUniversal.is_simulation_capable());
+        assert!(!BinaryPlatform::LinuxArm64.is_desktop());
+        assert!(BinaryPlatform::LinuxArm64.is_simulation_capable());
+        assert!(!BinaryPlatform::Headers.is_simulation_capable());
+        assert!(!BinaryPlatform::Other("linuxraspbian".to_string()).is_roborio());
+    }
+
+    #[test]
+    fn binary_platform_fallbacks() {
+        let mac_arm: BinaryPlatform = "osxarm64".parse().unwrap();
+        assert_eq!(
+            mac_arm.fallbacks(),
+            vec![mac_arm.clone(), BinaryPlatform::OsxUniversal]
+        );
+        assert_eq!(
+            BinaryPlatform::LinuxAthena.fallbacks(),
+            vec![BinaryPlatform::LinuxAthena]
+        );
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn linkage_fallback() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(CppDependency {
+                binary_platforms: platforms(&["linuxx86-64"]),
+                ..cpp_dep("example-cpp")
+            })
+            .build();
+        // Only the shared build is published.
+        mirror.publish(
+            &dep.cpp_dependencies[0].get_url(&mirror.maven_url, "linuxx86-64", false, false),
+            include_bytes!("../tests/fixtures/executable.zip"),
+        );
+        let runtime = runtime();
+
+        let out = tempdir().unwrap();
+        let err = runtime
+            .block_on(dep.download_all_cpp_deps_to_folder(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                true,
+                false,
+                false,
+            ))
+            .unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                crate::error::Error::LinkageUnavailable { artifact, platform, requested, available }
+                    if artifact == "com.example:example-cpp:1.0.0"
+                        && platform == "linuxx86-64"
+                        && requested == "static"
+                        && available == "shared"
+            ),
+            "{:?}",
+            err
+        );
+
+        let config = crate::DownloadConfig {
+            linkage_fallback: true,
+            ..Default::default()
+        };
+        let info = runtime
+            .block_on(dep.download_all_cpp_deps_to_folder_with_config(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                true,
+                false,
+                false,
+                &config,
+            ))
+            .unwrap();
+        assert_eq!(info.libraries, vec!["example"]);
+
+        // Nothing is published for debug builds, so that is still not found.
+        assert!(matches!(
+            runtime.block_on(dep.download_all_cpp_deps_to_folder(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                true,
+                true,
+                false,
+            )),
+            Err(crate::error::Error::NotFoundError { .. })
+        ));
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn diagnostics() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_java_dependency(java_dep("example-java"))
+            .add_jni_dependency(JniDependency {
+                skip_invalid_platforms: true,
+                valid_platforms: platforms(&["windowsx86-64"]),
+                ..jni_dep("example-jni")
+            })
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                binary_platforms: platforms(&["linuxx86-64"]),
+                ..cpp_dep("example-cpp")
+            })
+            .build();
+        // Only the shared build is published, and no headers.
+        mirror.publish(
+            &dep.cpp_dependencies[0].get_url(&mirror.maven_url, "linuxx86-64", false, false),
+            include_bytes!("../tests/fixtures/executable.zip"),
+        );
+        let runtime = runtime();
+        let coordinate = |artifact_id: &str, kind| Coordinate {
+            group_id: "com.example".to_string(),
+            artifact_id: artifact_id.to_string(),
+            version: "1.0.0".to_string(),
+            kind,
+        };
+
+        let config = crate::DownloadConfig {
+            linkage_fallback: true,
+            ..Default::default()
+        };
+        let out = tempdir().unwrap();
+        runtime
+            .block_on(dep.download_all_to_folder_with_config(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                true,
+                false,
+                true,
+                &config,
+            ))
+            .unwrap();
+        let diagnostics = config.diagnostics.take();
+        // Each repository that didn't have an artifact is reported too.
+        assert!(diagnostics.iter().any(|x| matches!(
+            x,
+            crate::Diagnostic::RepositoryFailed { artifact, maven_url, .. }
+                if *artifact == coordinate("example-java", ArtifactKind::Java)
+                    && *maven_url == format!("file://{}", mirror.maven_url)
+        )));
+        let mut rest = diagnostics
+            .into_iter()
+            .filter(|x| !matches!(x, crate::Diagnostic::RepositoryFailed { .. }))
+            .collect::<Vec<_>>();
+        rest.sort_by_key(|x| x.to_string());
+        assert_eq!(
+            rest,
+            vec![
+                crate::Diagnostic::HeadersMissing {
+                    artifact: coordinate("example-cpp", ArtifactKind::Cpp),
+                },
+                crate::Diagnostic::LinkageFallback {
+                    artifact: coordinate("example-cpp", ArtifactKind::Cpp),
+                    platform: BinaryPlatform::LinuxX86_64,
+                    is_static: false,
+                },
+                crate::Diagnostic::Skipped {
+                    artifact: coordinate("example-java", ArtifactKind::Java),
+                },
+                crate::Diagnostic::NotPublished {
+                    artifact: coordinate("example-jni", ArtifactKind::Jni),
+                    platform: BinaryPlatform::LinuxX86_64,
+                },
+            ]
+        );
+        assert!(config.diagnostics.list().is_empty());
+        assert_eq!(
+            rest[1].to_string(),
+            "com.example:example-cpp:1.0.0 has no static build for linuxx86-64, the shared one was downloaded instead"
+        );
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn cancelled_during_retry() {
+        let dep = VendorDep::builder("Example", UUID)
+            .add_maven_url("http://127.0.0.1:1/")
+            .add_java_dependency(java_dep("example-java"))
+            .build();
+        let config = crate::DownloadConfig {
+            retry: crate::RetryPolicy::new(2, std::time::Duration::from_millis(500)),
+            ..Default::default()
+        };
+        let cancel = config.cancel.clone();
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            cancel.cancel();
+        });
+
+        let out = tempdir().unwrap();
+        let res = runtime().block_on(dep.download_all_java_deps_to_folder_with_config(
+            out.path(),
+            true,
+            &config,
+        ));
+        canceller.join().unwrap();
+        assert!(matches!(res, Err(crate::error::Error::Cancelled)));
+        // Neither the repository nor the dependency failed.
+        assert!(config.diagnostics.list().is_empty());
+        assert_eq!(config.tally.summary().artifacts_skipped, 0);
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn headerless_cpp_dependency() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(CppDependency {
+                binary_platforms: platforms(&["linuxx86-64"]),
+                ..cpp_dep("example-cpp")
+            })
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                ..cpp_dep("missing-cpp")
+            })
+            .build();
+        assert!(!dep.cpp_dependencies[0].has_headers());
+        mirror.publish(
+            &dep.cpp_dependencies[0].get_url(&mirror.maven_url, "linuxx86-64", false, false),
+            include_bytes!("../tests/fixtures/executable.zip"),
+        );
+
+        let out = tempdir().unwrap();
+        let info = runtime()
+            .block_on(dep.download_all_cpp_deps_to_folder(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                true,
+            ))
+            .unwrap();
+        assert!(info.include_dirs.is_empty());
+        assert_eq!(info.libraries, vec!["example"]);
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn headers_inside_library_artifact() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                ..cpp_dep("example-cpp")
+            })
+            .build();
+        // Only the platform artifact is published, with the headers inside it.
+        mirror.publish(
+            &dep.cpp_dependencies[0].get_url(&mirror.maven_url, "linuxx86-64", false, false),
+            include_bytes!("../tests/fixtures/wrapped.zip"),
+        );
+
+        let out = tempdir().unwrap();
+        let info = runtime()
+            .block_on(dep.download_all_cpp_deps_to_folder(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                false,
+            ))
+            .unwrap();
+        assert_eq!(
+            info.include_dirs,
+            vec![out.path().join("example-cpp/libs/example-1.0/include")]
+        );
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn bundled_include_dirs() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                ..cpp_dep("example-cpp")
+            })
+            .build();
+        for (classifier, contents) in [
+            (
+                "headers",
+                &include_bytes!("../tests/fixtures/headers.zip")[..],
+            ),
+            (
+                "linuxx86-64",
+                include_bytes!("../tests/fixtures/bundled.zip"),
+            ),
+        ] {
+            mirror.publish(
+                &dep.cpp_dependencies[0].get_url(&mirror.maven_url, classifier, false, false),
+                contents,
+            );
+        }
+
+        let out = tempdir().unwrap();
+        let info = runtime()
+            .block_on(dep.download_all_cpp_deps_to_folder(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                false,
+            ))
+            .unwrap();
+        assert_eq!(
+            info.include_dirs,
+            vec![
+                out.path().join("example-cpp/include"),
+                out.path().join("example-cpp/libs/include")
+            ]
+        );
+        assert_eq!(info.libraries, vec!["example"]);
+        let existing = CppInfo::from_existing(out.path()).unwrap();
+        assert_eq!(existing.include_dirs, info.include_dirs);
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn java_jars_per_vendordep() {
+        let mirror = Mirror::new();
+        let vendordep = |name: &str| {
+            let dep = java_dep(&format!("{}-java", name));
+            mirror.publish(&dep.get_url(&mirror.maven_url), b"PK\x03\x04");
+            VendorDep::builder(name, UUID)
+                .add_maven_url(format!("file://{}", mirror.maven_url))
+                .add_java_dependency(dep)
+                .build()
+        };
+        let first = vendordep("first");
+        let second = vendordep("second");
+
+        let out = tempdir().unwrap();
+        let runtime = runtime();
+        let jars = runtime
+            .block_on(first.download_all_java_deps_to_folder(out.path(), false))
+            .unwrap();
+        assert_eq!(jars, vec![out.path().join("first-java-1.0.0.jar")]);
+        let jars = runtime
+            .block_on(second.download_all_java_deps_to_folder(out.path(), false))
+            .unwrap();
+        assert_eq!(jars, vec![out.path().join("second-java-1.0.0.jar")]);
+    }
+
+    #[cfg(feature = "download")]
+    #[test]
+    fn empty_vendordep_creates_output() {
+        let dep = VendorDep::builder("Empty", UUID).build();
+        let dir = tempdir().unwrap();
+        let out = dir.path().join("does/not/exist");
+        let runtime = runtime();
+        let jars = runtime
+            .block_on(dep.download_all_java_deps_to_folder(out.join("java"), false))
+            .unwrap();
+        assert!(jars.is_empty());
+        let jni = runtime
+            .block_on(dep.download_all_jni_deps_to_folder(
+                out.join("jni"),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+            ))
+            .unwrap();
+        assert!(jni.include_dirs.is_empty() && jni.libraries.is_empty());
+        let cpp = runtime
+            .block_on(dep.download_all_cpp_deps_to_folder(
+                out.join("cpp"),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                false,
+            ))
+            .unwrap();
+        assert!(cpp.include_dirs.is_empty() && cpp.libraries.is_empty());
+        for dir in ["java", "jni", "cpp"] {
+            assert!(out.join(dir).is_dir());
+        }
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn download_all_headers() {
+        let mirror = Mirror::new();
+        let cpp = |artifact_id: &str, header_classifier: &str| CppDependency {
+            header_classifier: header_classifier.to_string(),
+            binary_platforms: platforms(&["linuxx86-64"]),
+            ..cpp_dep(artifact_id)
+        };
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(cpp("example-cpp", "headers"))
+            .add_cpp_dependency(cpp("no-headers-cpp", ""))
+            .add_cpp_dependency(cpp("missing-cpp", "headers"))
+            .build();
+        mirror.publish(
+            &dep.cpp_dependencies[0].classifier_url(&mirror.maven_url, "headers"),
+            include_bytes!("../tests/fixtures/headers.zip"),
+        );
+
+        let out = tempdir().unwrap();
+        let runtime = runtime();
+        assert!(matches!(
+            runtime.block_on(dep.download_all_headers_to_folder(out.path(), false)),
+            Err(crate::error::Error::NotFoundError { artifact_id, .. }) if artifact_id == "missing-cpp"
+        ));
+        let include_dirs = runtime
+            .block_on(dep.download_all_headers_to_folder(out.path(), true))
+            .unwrap();
+        assert_eq!(include_dirs, vec![out.path().join("example-cpp/include")]);
+        assert!(out
+            .path()
+            .join("example-cpp/include/frc/Example.h")
+            .exists());
+        assert!(!out.path().join("example-cpp/libs").exists());
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn per_dependency_maven_urls() {
+        let mirrors = [tempdir().unwrap(), tempdir().unwrap()];
+        let mut dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        dep.maven_urls = vec![format!("file://{}/", mirrors[0].path().display())];
+        let elsewhere = format!("file://{}/", mirrors[1].path().display());
+        dep.java_dependencies[0].maven_urls = Some(vec![elsewhere.clone()]);
+        let jar = dep.java_dependencies[0].get_url(&format!("{}/", mirrors[1].path().display()));
+        std::fs::create_dir_all(std::path::Path::new(&jar).parent().unwrap()).unwrap();
+        std::fs::write(&jar, b"PK\x05\x06").unwrap();
+
+        let json = dep.to_json(false).unwrap();
+        assert_eq!(json.matches("\"mavenUrls\"").count(), 1);
+        let dep: VendorDep = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            dep.java_dependencies[0].effective_maven_urls(&dep.maven_urls),
+            [elsewhere]
+        );
+        assert_eq!(
+            dep.cpp_dependencies[0].effective_maven_urls(&dep.maven_urls),
+            dep.maven_urls
+        );
+
+        let out = tempdir().unwrap();
+        let jars = runtime()
+            .block_on(dep.download_all_java_deps_to_folder(out.path(), false))
+            .unwrap();
+        assert_eq!(jars, vec![out.path().join("wpiapi-java-24.3.0.jar")]);
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn not_found_error() {
+        let mirrors = [tempdir().unwrap(), tempdir().unwrap()];
+        let mut dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        dep.maven_urls = mirrors
+            .iter()
+            .map(|x| format!("file://{}/", x.path().display()))
+            .collect();
+        let out = tempdir().unwrap();
+        let err = runtime()
+            .block_on(dep.download_all_java_deps_to_folder(out.path(), false))
+            .unwrap_err();
+        let crate::error::Error::NotFoundError {
+            group_id,
+            artifact_id,
+            version,
+            tried_urls,
+        } = &err
+        else {
+            panic!("expected NotFoundError, got {:?}", err);
+        };
+        assert_eq!(
+            (group_id.as_str(), artifact_id.as_str(), version.as_str()),
+            ("com.ctre.phoenix6", "wpiapi-java", "24.3.0")
+        );
+        assert_eq!(
+            tried_urls,
+            &dep.maven_urls
+                .iter()
+                .map(|x| dep.java_dependencies[0].get_url(x))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            err.to_string(),
+            "Could not find Maven artifact com.ctre.phoenix6:wpiapi-java:24.3.0."
+        );
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn filtered_cpp_dependencies() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(cpp_dep("example-cpp"))
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                ..cpp_dep("missing-cpp")
+            })
+            .build();
+        mirror.publish(
+            &dep.cpp_dependencies[0].get_url(&mirror.maven_url, "linuxx86-64", false, false),
+            include_bytes!("../tests/fixtures/executable.zip"),
+        );
+
+        let runtime = runtime();
+        let out = tempdir().unwrap();
+        let info = runtime
+            .block_on(dep.download_cpp_deps_filtered(
+                out.path(),
+                |x| x.artifact_id == "example-cpp",
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                false,
+                &crate::DownloadConfig::default(),
+            ))
+            .unwrap();
+        assert_eq!(info.libraries, vec!["example"]);
+        assert!(!out.path().join("missing-cpp").exists());
+
+        let out = tempdir().unwrap();
+        let info = runtime
+            .block_on(dep.download_cpp_dep_by_id(
+                "example-cpp",
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+            ))
+            .unwrap();
+        assert_eq!(info.libraries, vec!["example"]);
+        assert!(!out.path().join("missing-cpp").exists());
+        assert!(matches!(
+            runtime.block_on(dep.download_cpp_dep_by_id(
+                "other-cpp",
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+            )),
+            Err(crate::error::Error::NoSuchDependency { vendordep, artifact_id })
+                if vendordep == "Example" && artifact_id == "other-cpp"
+        ));
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn cpp_dependencies_by_dependency() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(cpp_dep("example-cpp"))
+            .add_cpp_dependency(cpp_dep("missing-cpp"))
+            .build();
+        mirror.publish(
+            &dep.cpp_dependencies[0].get_url(&mirror.maven_url, "linuxx86-64", false, false),
+            include_bytes!("../tests/fixtures/executable.zip"),
+        );
+
+        let out = tempdir().unwrap();
+        let groups = runtime()
+            .block_on(dep.download_all_cpp_deps_by_dependency(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                true,
+            ))
+            .unwrap();
+        assert_eq!(
+            groups
+                .iter()
+                .map(|(artifact, info)| (artifact.artifact_id.as_str(), info.libraries.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("example-cpp", vec!["example".to_string()]),
+                ("missing-cpp", vec![]),
+            ]
+        );
+        assert!(groups[1].1.library_search_paths.is_empty());
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn debug_symbols() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                ..cpp_dep("example-cpp")
+            })
+            .build();
+        let maven_url = &mirror.maven_url;
+        let cpp = &dep.cpp_dependencies[0];
+        for (path, contents) in [
+            (
+                cpp.get_url(maven_url, "headers", false, false),
+                &include_bytes!("../tests/fixtures/headers.zip")[..],
+            ),
+            (
+                cpp.get_url(maven_url, "linuxx86-64", false, false),
+                include_bytes!("../tests/fixtures/executable.zip"),
+            ),
+            (
+                cpp.get_url(maven_url, "linuxx86-64", false, true),
+                include_bytes!("../tests/fixtures/executable.zip"),
+            ),
+        ] {
+            mirror.publish(&path, contents);
+        }
+
+        let out = tempdir().unwrap();
+        let (release, debug) = runtime()
+            .block_on(dep.download_all_cpp_deps_with_debug_symbols(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+            ))
+            .unwrap();
+        let debug_root = out.path().join("debug");
+        assert_eq!(
+            release.include_dirs,
+            vec![out.path().join("example-cpp/include")]
+        );
+        assert_eq!(release.libraries, vec!["example"]);
+        assert!(release
+            .library_search_paths
+            .iter()
+            .all(|x| !x.starts_with(&debug_root)));
+        assert!(debug.include_dirs.is_empty());
+        assert_eq!(debug.libraries, vec!["example"]);
+        assert!(debug
+            .library_search_paths
+            .iter()
+            .all(|x| x.starts_with(&debug_root)));
+        assert!(!debug_root.join("example-cpp/include").exists());
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn jni_headers() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_jni_dependency(jni_dep("example-jni"))
+            .add_jni_dependency(jni_dep("plain-jni"))
+            .build();
+        let maven_url = &mirror.maven_url;
+        mirror.publish(
+            &dep.jni_dependencies[0].headers_url(maven_url),
+            include_bytes!("../tests/fixtures/headers.zip"),
+        );
+        for jni in &dep.jni_dependencies {
+            mirror.publish(
+                &jni.get_url(maven_url, "linuxx86-64", false),
+                include_bytes!("../tests/fixtures/executable.zip"),
+            );
+        }
+
+        let out = tempdir().unwrap();
+        let info = runtime()
+            .block_on(dep.download_all_jni_deps_to_folder_with_config(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                &crate::DownloadConfig {
+                    jni_headers: true,
+                    ..Default::default()
+                },
+            ))
+            .unwrap();
+        assert_eq!(
+            info.include_dirs,
+            vec![out.path().join("example-jni/include")]
+        );
+        assert_eq!(info.libraries, vec!["example"]);
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn missing_artifacts() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_java_dependency(java_dep("example-java"))
+            .add_jni_dependency(JniDependency {
+                skip_invalid_platforms: true,
+                valid_platforms: platforms(&["linuxathena"]),
+                ..jni_dep("example-jni")
+            })
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                ..cpp_dep("example-cpp")
+            })
+            .build();
+        for path in [
+            dep.java_dependencies[0].get_url(&mirror.maven_url),
+            dep.cpp_dependencies[0].classifier_url(&mirror.maven_url, "headers"),
+        ] {
+            mirror.publish(&path, b"PK\x05\x06");
+        }
+
+        let runtime = runtime();
+        // The JNI dependency isn't published for the platform, so it isn't needed.
+        let missing = runtime
+            .block_on(dep.missing_artifacts(BinaryPlatform::LinuxX86_64, false, false))
+            .unwrap();
+        assert_eq!(
+            missing
+                .iter()
+                .map(|x| (x.artifact_id.as_str(), x.kind))
+                .collect::<Vec<_>>(),
+            vec![("example-cpp", ArtifactKind::Cpp)]
+        );
+        let missing = runtime
+            .block_on(dep.missing_artifacts(BinaryPlatform::LinuxAthena, false, false))
+            .unwrap();
+        assert_eq!(missing.len(), 2);
+
+        let mut unreachable: VendorDep =
+            serde_json::from_str(&dep.to_json(false).unwrap()).unwrap();
+        unreachable.maven_urls = vec!["http://127.0.0.1:1/".to_string()];
+        assert!(runtime
+            .block_on(unreachable.missing_artifacts(BinaryPlatform::LinuxX86_64, false, false))
+            .is_err());
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn jni_jar_with_nested_natives() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_jni_dependency(JniDependency {
+                is_jar: true,
+                ..jni_dep("example-jni")
+            })
+            .build();
+        let jar_path = dep.jni_dependencies[0].get_url(&mirror.maven_url, "linuxx86-64", false);
+        assert!(jar_path.ends_with(".jar"));
+        mirror.publish(
+            &jar_path,
+            include_bytes!("../tests/fixtures/nested-natives.jar"),
+        );
+
+        let out = tempdir().unwrap();
+        let info = runtime()
+            .block_on(dep.download_all_jni_deps_to_folder(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+            ))
+            .unwrap();
+        assert_eq!(info.libraries, vec!["example"]);
+        assert_eq!(
+            info.runtime_search_paths,
+            vec![out
+                .path()
+                .join("example-jni/com/example/natives/linux/x86-64/shared")]
+        );
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn detect_jni_packaging() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_jni_dependency(JniDependency {
+                is_jar: true,
+                ..jni_dep("example-jni")
             })
+            .build();
+        // Published as a `.zip`, though the vendordep says `.jar`.
+        let jni = &dep.jni_dependencies[0];
+        mirror.publish(
+            &jni.packaged_url(&mirror.maven_url, "linuxx86-64", false, false),
+            include_bytes!("../tests/fixtures/executable.zip"),
+        );
+
+        let runtime = runtime();
+        assert!(!runtime
+            .block_on(jni.detect_packaging(&dep.maven_urls[0], &BinaryPlatform::LinuxX86_64))
+            .unwrap());
+        assert!(matches!(
+            runtime.block_on(jni.detect_packaging(&dep.maven_urls[0], &BinaryPlatform::LinuxArm64)),
+            Err(crate::error::Error::NotFoundError { tried_urls, .. }) if tried_urls.len() == 2
+        ));
+
+        let download = |detect_jni_packaging| {
+            let out = tempdir().unwrap();
+            runtime.block_on(dep.download_all_jni_deps_to_folder_with_config(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                &crate::DownloadConfig {
+                    detect_jni_packaging,
+                    ..Default::default()
+                },
+            ))
+        };
+        assert!(matches!(
+            download(false),
+            Err(crate::error::Error::NotFoundError { .. })
+        ));
+        assert_eq!(download(true).unwrap().libraries, vec!["example"]);
+    }
+
+    #[cfg(feature = "download")]
+    #[test]
+    fn headers_is_not_a_library_platform() {
+        let phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let out = tempdir().unwrap();
+        let runtime = runtime();
+        let err = runtime
+            .block_on(phoenix.jni_dependencies[0].download_library_to_folder(
+                out.path(),
+                &phoenix.maven_urls[0],
+                BinaryPlatform::Headers,
+                false,
+            ))
+            .unwrap_err();
+        assert!(matches!(err, crate::error::Error::NotALibraryPlatform(x) if x == "headers"));
+        let err = runtime
+            .block_on(phoenix.download_all_cpp_deps_to_folder(
+                out.path(),
+                BinaryPlatform::Headers,
+                false,
+                false,
+                true,
+            ))
+            .unwrap_err();
+        assert!(matches!(err, crate::error::Error::NotALibraryPlatform(_)));
+    }
+
+    #[test]
+    fn supports_platform() {
+        let dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let jni = &dep.jni_dependencies[1];
+        assert!(jni.supports_platform(&BinaryPlatform::OsxUniversal));
+        assert!(!jni.supports_platform(&BinaryPlatform::LinuxAthena));
+        // Universal Mac artifacts stand in for either architecture.
+        let osxarm64 = BinaryPlatform::Other("osxarm64".to_string());
+        assert!(BinaryPlatform::OsxUniversal.covers(&osxarm64));
+        assert!(BinaryPlatform::OsxUniversal.covers(&BinaryPlatform::OsxUniversal));
+        assert!(!osxarm64.covers(&BinaryPlatform::OsxUniversal));
+        assert!(!BinaryPlatform::OsxUniversal.covers(&BinaryPlatform::LinuxX86_64));
+        assert!(jni.supports_platform(&osxarm64));
+        assert_eq!(
+            jni.published_platform(&osxarm64),
+            Some(BinaryPlatform::OsxUniversal)
+        );
+        assert_eq!(
+            jni.published_platform(&BinaryPlatform::LinuxX86_64),
+            Some(BinaryPlatform::LinuxX86_64)
+        );
+        assert_eq!(jni.published_platform(&BinaryPlatform::LinuxAthena), None);
+        let cpp = &dep.cpp_dependencies[0];
+        assert!(cpp.supports_platform(&BinaryPlatform::LinuxAthena));
+        assert!(!cpp.supports_platform(&BinaryPlatform::LinuxArm64));
+
+        let cpp: CppDependency = serde_json::from_str(
+            r#"{"groupId": "com.example", "artifactId": "example-cpp", "version": "1.0.0", "headerClassifier": "headers"}"#,
+        )
+        .unwrap();
+        assert!(cpp.binary_platforms.is_empty());
+        assert!(cpp.supports_platform(&BinaryPlatform::LinuxArm64));
+        assert!(cpp.supports_platform(&BinaryPlatform::Other("linuxraspbian".to_string())));
+        let mut jni = dep.jni_dependencies.into_iter().next().unwrap();
+        jni.valid_platforms.clear();
+        assert!(jni.supports_platform(&BinaryPlatform::OsxUniversal));
+
+        let mut java = dep.java_dependencies.into_iter().next().unwrap();
+        assert_eq!(java.platform(), None);
+        assert!(java.supports_platform(&BinaryPlatform::LinuxAthena));
+        java.classifier = Some("javadoc".to_string());
+        assert_eq!(java.platform(), None);
+        java.classifier = Some("osxuniversal".to_string());
+        assert_eq!(java.platform(), Some(BinaryPlatform::OsxUniversal));
+        assert!(!java.supports_platform(&BinaryPlatform::LinuxAthena));
+        assert!(java.supports_platform(&BinaryPlatform::Other("osxarm64".to_string())));
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn extract_local_archives() {
+        let dir = tempdir().unwrap();
+        let headers = dir.path().join("example-cpp-1.0.0-headers.zip");
+        std::fs::write(&headers, include_bytes!("../tests/fixtures/headers.zip")).unwrap();
+        let cpp = CppDependency {
+            header_classifier: "headers".to_string(),
+            ..cpp_dep("example-cpp")
+        };
+        let out = dir.path().join("include");
+        cpp.extract_archive_to_folder(&headers, &out).unwrap();
+        assert!(out.join("frc/Example.h").exists());
+        assert!(out.join("frc/detail/Impl.h").exists());
+
+        let jar = dir.path().join("example-jni-1.0.0-linuxx86-64.jar");
+        std::fs::write(&jar, include_bytes!("../tests/fixtures/nested-natives.jar")).unwrap();
+        let jni = JniDependency {
+            is_jar: true,
+            ..jni_dep("example-jni")
+        };
+        let out = dir.path().join("jni");
+        let config = crate::DownloadConfig {
+            keep_archives: true,
+            ..Default::default()
+        };
+        jni.extract_archive_to_folder_with_config(&jar, &out, &config)
+            .unwrap();
+        assert!(out
+            .join("com/example/natives/linux/x86-64/shared/libexample.so")
+            .exists());
+        // Nothing to keep, the archive being local already.
+        assert!(!out.join("example-jni-1.0.0-linuxx86-64.jar").exists());
+
+        assert!(matches!(
+            cpp.extract_archive_to_folder(dir.path().join("missing.zip"), &out),
+            Err(crate::error::Error::IoError(_))
+        ));
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn java_platform_filter() {
+        let mirror = Mirror::new();
+        let mut builder = mirror.vendordep();
+        for classifier in [None, Some("linuxathena"), Some("windowsx86-64")] {
+            let dep = JavaDependency {
+                classifier: classifier.map(str::to_string),
+                ..java_dep("example-java")
+            };
+            mirror.publish(&dep.get_url(&mirror.maven_url), b"PK\x03\x04");
+            builder = builder.add_java_dependency(dep);
+        }
+        let dep = builder.build();
+
+        let out = tempdir().unwrap();
+        let jars = runtime()
+            .block_on(dep.download_all_java_deps_to_folder_with_config(
+                out.path(),
+                false,
+                &crate::DownloadConfig {
+                    java_platform: Some(BinaryPlatform::LinuxAthena),
+                    ..Default::default()
+                },
+            ))
+            .unwrap();
+        assert_eq!(
+            jars,
+            vec![
+                out.path().join("example-java-1.0.0.jar"),
+                out.path().join("example-java-1.0.0-linuxathena.jar"),
+            ]
+        );
+        assert!(!out
+            .path()
+            .join("example-java-1.0.0-windowsx86-64.jar")
+            .exists());
     }
 }
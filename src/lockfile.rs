@@ -0,0 +1,405 @@
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{error, fetch, BinaryPlatform, DownloadConfig, Result, VendorDep};
+
+#[doc = "One artifact pinned by a [`Lockfile`]."]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedArtifact {
+    #[doc = "`group:artifact:version` of the dependency, for reference."]
+    pub coordinate: String,
+    #[doc = "The URL the artifact was resolved from, and the only one it is installed from."]
+    pub url: String,
+    #[doc = "The URL the artifact was actually served from, after [`DownloadConfig::url_rewriter`] and any redirects, if that differs from [`Self::url`]. "]
+    #[doc = "Only recorded for reference; to install from it, make it the [`Self::url`]."]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_url: Option<String>,
+    #[doc = "Lowercase hex SHA-256 digest of the artifact."]
+    pub sha256: String,
+    #[doc = "Where the artifact goes, relative to the install directory and `/`-separated: the jar itself for Java artifacts, the directory it is extracted to otherwise."]
+    pub path: String,
+    #[doc = "Whether the artifact is an archive to extract into [`Self::path`] rather than a file to store there."]
+    pub extract: bool,
+}
+
+#[doc = "Every artifact of a [`VendorDep`] for one platform and variant, pinned to an exact URL and SHA-256 digest so that installs are reproducible. "]
+#[doc = "Created with [`VendorDep::generate_lockfile`] and installed with [`install_from_lockfile`]. Laid out like [`VendorDep::download_all_to_folder`]."]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Lockfile {
+    #[doc = "Name of the locked vendordep."]
+    pub name: String,
+    #[doc = "Version of the locked vendordep."]
+    pub version: String,
+    #[doc = "Platform name, as in [`BinaryPlatform::to_str`]."]
+    pub platform: String,
+    #[doc = "Whether the C++ libraries are static."]
+    pub is_static: bool,
+    #[doc = "Whether the libraries are debug builds."]
+    pub is_debug: bool,
+    #[doc = "The pinned artifacts."]
+    pub artifacts: Vec<LockedArtifact>,
+}
+
+// An artifact to lock: its coordinate, candidate URLs (one per repository), destination and whether it is extracted.
+type Candidate = (String, Vec<String>, String, bool);
+
+impl VendorDep {
+    #[doc = "Download every artifact [`Self::download_all_to_folder`] would for `binary_platform` and record the URL it was found at and its SHA-256 digest. "]
+    #[doc = "JNI and C++ libraries that aren't published for the platform are left out. Fails with [`error::Error::NotFoundError`] if any other artifact can't be found."]
+    pub async fn generate_lockfile(
+        &self,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+    ) -> Result<Lockfile> {
+        self.generate_lockfile_with_config(
+            binary_platform,
+            is_static,
+            is_debug,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[doc = "Like [`Self::generate_lockfile`], with additional options."]
+    pub async fn generate_lockfile_with_config(
+        &self,
+        binary_platform: BinaryPlatform,
+        is_static: bool,
+        is_debug: bool,
+        config: &DownloadConfig,
+    ) -> Result<Lockfile> {
+        let platform = binary_platform.to_str();
+        let urls = |repositories: &[String], url: &dyn Fn(&str) -> String| {
+            repositories.iter().map(|x| url(x)).collect()
+        };
+        let mut candidates: Vec<Candidate> = Vec::new();
+        for dep in &self.java_dependencies {
+            candidates.push((
+                format!("{}:{}:{}", dep.group_id, dep.artifact_id, dep.version),
+                urls(dep.effective_maven_urls(&self.maven_urls), &|x| {
+                    dep.get_url(x)
+                }),
+                format!("java/{}", dep.file_name()),
+                false,
+            ));
+        }
+        for dep in &self.jni_dependencies {
+            if let Some(published) = dep.published_platform(&binary_platform) {
+                candidates.push((
+                    format!("{}:{}:{}", dep.group_id, dep.artifact_id, dep.version),
+                    urls(dep.effective_maven_urls(&self.maven_urls), &|x| {
+                        dep.get_url(x, published.to_str(), is_debug)
+                    }),
+                    format!("jni/{}", dep.artifact_id),
+                    true,
+                ));
+            }
+        }
+        for dep in &self.cpp_dependencies {
+            let coordinate = format!("{}:{}:{}", dep.group_id, dep.artifact_id, dep.version);
+            if dep.has_headers() {
+                candidates.push((
+                    coordinate.clone(),
+                    urls(dep.effective_maven_urls(&self.maven_urls), &|x| {
+                        dep.classifier_url(x, &dep.header_classifier)
+                    }),
+                    format!("cpp/{}/include", dep.artifact_id),
+                    true,
+                ));
+            }
+            if let Some(published) = dep.published_platform(&binary_platform) {
+                candidates.push((
+                    coordinate,
+                    urls(dep.effective_maven_urls(&self.maven_urls), &|x| {
+                        dep.get_url(x, published.to_str(), is_static, is_debug)
+                    }),
+                    format!("cpp/{}/libs", dep.artifact_id),
+                    true,
+                ));
+            }
+        }
+        let mut artifacts = Vec::new();
+        for (coordinate, urls, path, extract) in candidates {
+            artifacts.push(lock_artifact(coordinate, &urls, path, extract, config).await?);
+        }
+        Ok(Lockfile {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            platform: platform.to_string(),
+            is_static,
+            is_debug,
+            artifacts,
+        })
+    }
+}
+
+#[doc = "Fetch the first of `urls` that exists and record it."]
+async fn lock_artifact(
+    coordinate: String,
+    urls: &[String],
+    path: String,
+    extract: bool,
+    config: &DownloadConfig,
+) -> Result<LockedArtifact> {
+    config.cancel.check()?;
+    for url in urls {
+        match config
+            .retry
+            .run(&config.cancel, || {
+                fetch::fetch_resolved_if_exists(url, config)
+            })
+            .await
+        {
+            Ok(Some((bytes, resolved_url))) => {
+                fetch::ensure_archive(url, &bytes)?;
+                return Ok(LockedArtifact {
+                    coordinate,
+                    url: url.clone(),
+                    resolved_url: (resolved_url != *url).then_some(resolved_url),
+                    sha256: sha256_hex(&bytes),
+                    path,
+                    extract,
+                });
+            }
+            Ok(None) => {}
+            Err(error::Error::Cancelled) => return Err(error::Error::Cancelled),
+            Err(e) => log_warn!("Failed to fetch {}: {}", url, e),
+        }
+    }
+    let mut parts = coordinate.splitn(3, ':').map(str::to_string);
+    Err(error::Error::NotFoundError {
+        group_id: parts.next().unwrap_or_default(),
+        artifact_id: parts.next().unwrap_or_default(),
+        version: parts.next().unwrap_or_default(),
+        tried_urls: urls.to_vec(),
+    })
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[doc = "Install every artifact of `lock` into `out_dir`, downloading each only from its pinned URL. Fails with [`error::Error::DigestMismatch`] "]
+#[doc = "before writing an artifact whose digest differs from the pinned one. Returns the installed jars and extraction directories; "]
+#[doc = "read the C++ libraries back with [`CppInfo::from_existing`](crate::CppInfo::from_existing) on `<out_dir>/cpp`."]
+pub async fn install_from_lockfile<P: AsRef<Path>>(
+    lock: &Lockfile,
+    out_dir: P,
+) -> Result<Vec<PathBuf>> {
+    install_from_lockfile_with_config(lock, out_dir, &DownloadConfig::default()).await
+}
+
+#[doc = "Like [`install_from_lockfile`], with additional options."]
+pub async fn install_from_lockfile_with_config<P: AsRef<Path>>(
+    lock: &Lockfile,
+    out_dir: P,
+    config: &DownloadConfig,
+) -> Result<Vec<PathBuf>> {
+    let out_dir = out_dir.as_ref();
+    let mut installed = Vec::new();
+    for artifact in &lock.artifacts {
+        config.cancel.check()?;
+        let relative = Path::new(&artifact.path);
+        if relative.as_os_str().is_empty()
+            || relative
+                .components()
+                .any(|x| !matches!(x, Component::Normal(_)))
+        {
+            return Err(error::Error::UnsafeLockfilePath(artifact.path.clone()));
+        }
+        let bytes = config
+            .retry
+            .run(&config.cancel, || fetch::fetch_bytes(&artifact.url, config))
+            .await?;
+        let actual = sha256_hex(&bytes);
+        if actual != artifact.sha256.to_ascii_lowercase() {
+            return Err(error::Error::DigestMismatch {
+                url: artifact.url.clone(),
+                expected: artifact.sha256.clone(),
+                actual,
+            });
+        }
+        let path = out_dir.join(relative);
+        if artifact.extract {
+            // Told apart the way `fetch_and_extract` does, the lockfile not recording the format.
+            let mut archive = std::io::Cursor::new(&bytes);
+            let written = if bytes.starts_with(&fetch::GZIP_MAGIC) {
+                crate::extract::extract_tar_gz_with_progress(
+                    &mut archive,
+                    &path,
+                    &config.extract,
+                    &config.cancel,
+                    &config.sink,
+                    None,
+                )?
+            } else {
+                crate::extract::extract_zip(
+                    &mut zip::ZipArchive::new(archive)?,
+                    &path,
+                    &config.extract,
+                    &config.cancel,
+                    &config.sink,
+                )?
+            };
+            config.tally.downloaded(bytes.len() as u64, written.len());
+        } else {
+            config.sink.put(&path, &mut bytes.as_slice(), None)?;
+            config.tally.downloaded(bytes.len() as u64, 0);
+        }
+        installed.push(path);
+    }
+    Ok(installed)
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::{install_from_lockfile, sha256_hex, LockedArtifact};
+    use crate::{
+        error::Error,
+        test_util::{cpp_dep, java_dep, platforms, runtime, Mirror},
+        BinaryPlatform, CppDependency, CppInfo,
+    };
+
+    #[test]
+    fn digest() {
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lockfile() {
+        let mirror = Mirror::new();
+        let maven_url = &mirror.maven_url;
+        let dep = mirror
+            .vendordep()
+            .add_java_dependency(java_dep("example-java"))
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                binary_platforms: platforms(&["linuxx86-64"]),
+                ..cpp_dep("example-cpp")
+            })
+            .build();
+        let cpp = &dep.cpp_dependencies[0];
+        let libs = cpp.get_url(maven_url, "linuxx86-64", false, false);
+        for (path, contents) in [
+            (
+                dep.java_dependencies[0].get_url(maven_url),
+                &b"PK\x05\x06\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0"[..],
+            ),
+            (
+                cpp.classifier_url(maven_url, "headers"),
+                include_bytes!("../tests/fixtures/headers.zip"),
+            ),
+            (
+                libs.clone(),
+                include_bytes!("../tests/fixtures/executable.zip"),
+            ),
+        ] {
+            mirror.publish(&path, contents);
+        }
+
+        let runtime = runtime();
+        let lock = runtime
+            .block_on(dep.generate_lockfile(BinaryPlatform::LinuxX86_64, false, false))
+            .unwrap();
+        assert_eq!(
+            lock.artifacts
+                .iter()
+                .map(|x| x.path.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "java/example-java-1.0.0.jar",
+                "cpp/example-cpp/include",
+                "cpp/example-cpp/libs"
+            ]
+        );
+        assert_eq!(
+            lock.artifacts[2],
+            LockedArtifact {
+                coordinate: "com.example:example-cpp:1.0.0".to_string(),
+                url: format!("file://{}", libs),
+                resolved_url: None,
+                sha256: sha256_hex(include_bytes!("../tests/fixtures/executable.zip")),
+                path: "cpp/example-cpp/libs".to_string(),
+                extract: true,
+            }
+        );
+        // Not published for the platform, so not locked.
+        let athena = runtime
+            .block_on(dep.generate_lockfile(BinaryPlatform::LinuxAthena, false, false))
+            .unwrap();
+        assert_eq!(athena.artifacts.len(), 2);
+
+        let lock: super::Lockfile =
+            serde_json::from_str(&serde_json::to_string(&lock).unwrap()).unwrap();
+        let out = tempdir().unwrap();
+        let installed = runtime
+            .block_on(install_from_lockfile(&lock, out.path()))
+            .unwrap();
+        assert_eq!(installed[0], out.path().join("java/example-java-1.0.0.jar"));
+        let info = CppInfo::from_existing(out.path().join("cpp")).unwrap();
+        assert_eq!(info.libraries, vec!["example"]);
+        assert!(out
+            .path()
+            .join("cpp/example-cpp/include/frc/Example.h")
+            .exists());
+
+        // The artifact changed since it was locked.
+        std::fs::write(&libs, include_bytes!("../tests/fixtures/headers.zip")).unwrap();
+        let out = tempdir().unwrap();
+        let err = runtime
+            .block_on(install_from_lockfile(&lock, out.path()))
+            .unwrap_err();
+        assert!(matches!(err, Error::DigestMismatch { url, .. } if url == lock.artifacts[2].url));
+        assert!(!out.path().join("cpp/example-cpp/libs").exists());
+
+        let mut unsafe_lock = lock.clone();
+        unsafe_lock.artifacts[0].path = "../escape.jar".to_string();
+        assert!(matches!(
+            runtime.block_on(install_from_lockfile(&unsafe_lock, out.path())),
+            Err(Error::UnsafeLockfilePath(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lockfile_tar_gz() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(CppDependency {
+                binary_platforms: platforms(&["linuxx86-64"]),
+                packaging: Some("tar.gz".to_string()),
+                ..cpp_dep("example-cpp")
+            })
+            .build();
+        let libs = dep.cpp_dependencies[0].get_url(&mirror.maven_url, "linuxx86-64", false, false);
+        assert!(libs.ends_with(".tar.gz"));
+        mirror.publish(&libs, include_bytes!("../tests/fixtures/bundled.tar.gz"));
+
+        let runtime = runtime();
+        let lock = runtime
+            .block_on(dep.generate_lockfile(BinaryPlatform::LinuxX86_64, false, false))
+            .unwrap();
+        let lock: super::Lockfile =
+            serde_json::from_str(&serde_json::to_string(&lock).unwrap()).unwrap();
+        let out = tempdir().unwrap();
+        let installed = runtime
+            .block_on(install_from_lockfile(&lock, out.path()))
+            .unwrap();
+        let libs = out.path().join("cpp/example-cpp/libs");
+        assert_eq!(installed, vec![libs.clone()]);
+        assert!(libs.join("include/example/Example.h").exists());
+        assert!(libs.join("linux/x86-64/shared/libexample.so").exists());
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{
+    symbols::{ar_members, AR_MAGIC, ELF_MAGIC},
+    BinaryPlatform, CppInfo,
+};
+
+impl CppInfo {
+    #[doc = "Find which platforms the library files of [`Self::libraries`] are built for, from the machine in their headers rather than their names, "]
+    #[doc = "to catch libraries of different architectures mixed into one [`CppInfo`] before they fail to link. More than one platform means they are mixed. "]
+    #[doc = "Scans the same files as [`Self::find_providing_library`]: ELF shared objects and archives are `linux` platforms, PE DLLs and `.lib`s are `windows` platforms, "]
+    #[doc = "and Mach-O libraries are [`BinaryPlatform::OsxUniversal`] if they hold both Mac architectures, or `osxarm64` or `osxx86-64` (as [`BinaryPlatform::Other`]) if they hold one; "]
+    #[doc = "see [`BinaryPlatform::covers`] to check those against the platform being built for. 32-bit ARM ELF files are told apart by their float ABI: "]
+    #[doc = "[`BinaryPlatform::LinuxArm32`] if they pass floats in floating point registers, and otherwise [`BinaryPlatform::LinuxAthena`], as the roboRIO's toolchain does. "]
+    #[doc = "Best effort: files that can't be read, and other formats and machines, are skipped. Returns platforms in the order of [`Self::libraries`]."]
+    pub fn detect_platforms(&self) -> Vec<BinaryPlatform> {
+        let mut platforms = Vec::new();
+        for (_, path) in self.library_files() {
+            let bytes = match std::fs::read(&path) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    log_debug!("Skipping {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            for platform in file_platforms(&bytes) {
+                if !platforms.contains(&platform) {
+                    platforms.push(platform);
+                }
+            }
+        }
+        platforms
+    }
+}
+
+const EM_ARM: u16 = 40;
+const EM_X86_64: u16 = 62;
+const EM_AARCH64: u16 = 183;
+const EF_ARM_ABI_FLOAT_HARD: u32 = 0x400;
+const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+const IMAGE_FILE_MACHINE_ARM64: u16 = 0xaa64;
+const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+
+// The platforms the library file `bytes` is built for, of each object in it if it is an archive.
+fn file_platforms(bytes: &[u8]) -> Vec<BinaryPlatform> {
+    if !bytes.starts_with(AR_MAGIC) {
+        return object_platforms(bytes);
+    }
+    let mut platforms = Vec::new();
+    for member in ar_members(bytes).unwrap_or_default() {
+        for platform in object_platforms(member) {
+            if !platforms.contains(&platform) {
+                platforms.push(platform);
+            }
+        }
+    }
+    platforms
+}
+
+// The platforms a single object or library is built for. Several only for Mach-O universal binaries that hold architectures other than both Mac ones.
+fn object_platforms(bytes: &[u8]) -> Vec<BinaryPlatform> {
+    if bytes.starts_with(ELF_MAGIC) {
+        elf_platform(bytes).into_iter().collect()
+    } else if bytes.starts_with(b"MZ") {
+        pe_platform(bytes).into_iter().collect()
+    } else if let Some(cputypes) = macho_cputypes(bytes) {
+        macho_platforms(&cputypes)
+    } else {
+        // The objects and import members of Windows `.lib`s have no magic number.
+        coff_platform(bytes).into_iter().collect()
+    }
+}
+
+fn elf_platform(bytes: &[u8]) -> Option<BinaryPlatform> {
+    let flags = match bytes.get(4)? {
+        1 => 0x24,
+        2 => 0x30,
+        _ => return None,
+    };
+    let little_endian = match bytes.get(5)? {
+        1 => true,
+        2 => false,
+        _ => return None,
+    };
+    match read_u16(bytes, 0x12, little_endian)? {
+        EM_X86_64 => Some(BinaryPlatform::LinuxX86_64),
+        EM_AARCH64 => Some(BinaryPlatform::LinuxArm64),
+        EM_ARM if read_u32(bytes, flags, little_endian)? & EF_ARM_ABI_FLOAT_HARD != 0 => {
+            Some(BinaryPlatform::LinuxArm32)
+        }
+        EM_ARM => Some(BinaryPlatform::LinuxAthena),
+        _ => None,
+    }
+}
+
+fn pe_platform(bytes: &[u8]) -> Option<BinaryPlatform> {
+    let header = usize::try_from(read_u32(bytes, 0x3c, true)?).ok()?;
+    if bytes.get(header..header.checked_add(4)?)? != b"PE\0\0" {
+        return None;
+    }
+    coff_machine(read_u16(bytes, header.checked_add(4)?, true)?)
+}
+
+fn coff_platform(bytes: &[u8]) -> Option<BinaryPlatform> {
+    // Import members and anonymous objects, such as those of LTCG builds, start with 0x0000 0xFFFF, and have the machine further in.
+    let machine = if bytes.starts_with(&[0, 0, 0xff, 0xff]) {
+        6
+    } else {
+        0
+    };
+    coff_machine(read_u16(bytes, machine, true)?)
+}
+
+fn coff_machine(machine: u16) -> Option<BinaryPlatform> {
+    match machine {
+        IMAGE_FILE_MACHINE_AMD64 => Some(BinaryPlatform::WindowsX86_64),
+        IMAGE_FILE_MACHINE_ARM64 => Some(BinaryPlatform::WindowsArm64),
+        _ => None,
+    }
+}
+
+// The CPU type of a Mach-O file, or of each architecture of a universal one. `None` if it is neither.
+fn macho_cputypes(bytes: &[u8]) -> Option<Vec<u32>> {
+    match read_u32(bytes, 0, false)? {
+        magic @ (0xcafebabe | 0xcafebabf) => {
+            let entry = if magic == 0xcafebabf { 32 } else { 20 };
+            let count = usize::try_from(read_u32(bytes, 4, false)?).ok()?;
+            (0..count)
+                .map(|i| read_u32(bytes, i.checked_mul(entry)?.checked_add(8)?, false))
+                .collect()
+        }
+        0xfeedface | 0xfeedfacf => Some(vec![read_u32(bytes, 4, false)?]),
+        0xcefaedfe | 0xcffaedfe => Some(vec![read_u32(bytes, 4, true)?]),
+        _ => None,
+    }
+}
+
+fn macho_platforms(cputypes: &[u32]) -> Vec<BinaryPlatform> {
+    if cputypes.contains(&CPU_TYPE_X86_64) && cputypes.contains(&CPU_TYPE_ARM64) {
+        return vec![BinaryPlatform::OsxUniversal];
+    }
+    cputypes
+        .iter()
+        .filter_map(|x| match *x {
+            CPU_TYPE_X86_64 => Some(BinaryPlatform::Other("osxx86-64".to_string())),
+            CPU_TYPE_ARM64 => Some(BinaryPlatform::Other("osxarm64".to_string())),
+            _ => None,
+        })
+        .collect()
+}
+
+fn read_u16(bytes: &[u8], offset: usize, little_endian: bool) -> Option<u16> {
+    let field = bytes.get(offset..offset.checked_add(2)?)?.try_into().ok()?;
+    Some(if little_endian {
+        u16::from_le_bytes(field)
+    } else {
+        u16::from_be_bytes(field)
+    })
+}
+
+fn read_u32(bytes: &[u8], offset: usize, little_endian: bool) -> Option<u32> {
+    let field = bytes.get(offset..offset.checked_add(4)?)?.try_into().ok()?;
+    Some(if little_endian {
+        u32::from_le_bytes(field)
+    } else {
+        u32::from_be_bytes(field)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::file_platforms;
+    use crate::{BinaryPlatform, CppInfo};
+
+    fn elf(is_64: bool, machine: u16, flags: u32) -> Vec<u8> {
+        let mut bytes = vec![0; 64];
+        bytes[..4].copy_from_slice(b"\x7fELF");
+        bytes[4] = if is_64 { 2 } else { 1 };
+        bytes[5] = 1;
+        bytes[0x12..0x14].copy_from_slice(&machine.to_le_bytes());
+        let flags_offset = if is_64 { 0x30 } else { 0x24 };
+        bytes[flags_offset..flags_offset + 4].copy_from_slice(&flags.to_le_bytes());
+        bytes
+    }
+
+    fn pe(machine: u16) -> Vec<u8> {
+        let mut bytes = vec![0; 0x80];
+        bytes[..2].copy_from_slice(b"MZ");
+        bytes[0x3c..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+        bytes[0x40..0x44].copy_from_slice(b"PE\0\0");
+        bytes[0x44..0x46].copy_from_slice(&machine.to_le_bytes());
+        bytes
+    }
+
+    fn fat(cputypes: &[u32]) -> Vec<u8> {
+        let mut bytes = 0xcafebabeu32.to_be_bytes().to_vec();
+        bytes.extend((cputypes.len() as u32).to_be_bytes());
+        for cputype in cputypes {
+            bytes.extend(cputype.to_be_bytes());
+            bytes.extend([0; 16]);
+        }
+        bytes
+    }
+
+    fn ar(members: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut bytes = b"!<arch>\n".to_vec();
+        for (name, contents) in members {
+            bytes.extend(format!("{:<16}{:<32}{:<10}`\n", name, 0, contents.len()).as_bytes());
+            bytes.extend(contents);
+            if contents.len() % 2 == 1 {
+                bytes.push(b'\n');
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn headers() {
+        let mut import = vec![0, 0, 0xff, 0xff, 0, 0];
+        import.extend(0xaa64u16.to_le_bytes());
+        let mut macho = 0xfeedfacfu32.to_le_bytes().to_vec();
+        macho.extend(0x0100000cu32.to_le_bytes());
+        for (bytes, platforms) in [
+            (elf(true, 62, 0), vec![BinaryPlatform::LinuxX86_64]),
+            (elf(true, 183, 0), vec![BinaryPlatform::LinuxArm64]),
+            (elf(false, 40, 0x5000200), vec![BinaryPlatform::LinuxAthena]),
+            (elf(false, 40, 0x5000400), vec![BinaryPlatform::LinuxArm32]),
+            (elf(true, 243, 0), vec![]),
+            (pe(0x8664), vec![BinaryPlatform::WindowsX86_64]),
+            (pe(0x14c), vec![]),
+            (
+                ar(&[("/", vec![0; 4]), ("//", vec![]), ("/0", import)]),
+                vec![BinaryPlatform::WindowsArm64],
+            ),
+            (macho, vec!["osxarm64".parse().unwrap()]),
+            (
+                fat(&[0x01000007, 0x0100000c]),
+                vec![BinaryPlatform::OsxUniversal],
+            ),
+            (fat(&[0x01000007]), vec!["osxx86-64".parse().unwrap()]),
+            (
+                ar(&[
+                    ("a.o/", elf(true, 62, 0)),
+                    ("b.o/", elf(true, 183, 0)),
+                    ("c.o/", elf(true, 62, 0)),
+                ]),
+                vec![BinaryPlatform::LinuxX86_64, BinaryPlatform::LinuxArm64],
+            ),
+            (b"\x7fELF".to_vec(), vec![]),
+            (b"MZ".to_vec(), vec![]),
+            (fat(&[0x01000007])[..10].to_vec(), vec![]),
+        ] {
+            assert_eq!(file_platforms(&bytes), platforms, "{:?}", bytes);
+        }
+    }
+
+    #[test]
+    fn detect_platforms() {
+        let dir = tempdir().unwrap();
+        let libs = dir.path().join("libs");
+        std::fs::create_dir(&libs).unwrap();
+        std::fs::write(
+            libs.join("libprovider.so"),
+            include_bytes!("../tests/fixtures/libprovider.elf"),
+        )
+        .unwrap();
+        std::fs::write(
+            libs.join("libarchived.a"),
+            include_bytes!("../tests/fixtures/libarchived.ar"),
+        )
+        .unwrap();
+        std::fs::write(libs.join("libother.so"), elf(true, 183, 0)).unwrap();
+        std::fs::write(libs.join("README"), elf(false, 40, 0)).unwrap();
+
+        let info = CppInfo::new_empty()
+            .with_library_search_path(&libs)
+            .with_library("provider")
+            .with_library("archived");
+        assert_eq!(info.detect_platforms(), [BinaryPlatform::LinuxX86_64]);
+
+        // An arm64 library mixed in with x86-64 ones.
+        let info = info.with_library("other").with_library("absent");
+        assert_eq!(
+            info.detect_platforms(),
+            [BinaryPlatform::LinuxX86_64, BinaryPlatform::LinuxArm64]
+        );
+        assert!(CppInfo::new_empty().detect_platforms().is_empty());
+    }
+}
@@ -0,0 +1,505 @@
+#[cfg(feature = "download")]
+use std::collections::{HashSet, VecDeque};
+use std::{cmp::Ordering, collections::HashMap};
+
+#[cfg(feature = "download")]
+use crate::{fetch, DownloadConfig, VendorDep};
+use crate::{FrcYear, JavaDependency, Result};
+
+#[doc = "A `<dependency>` entry of a Maven POM."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PomDependency {
+    #[doc = "Maven group."]
+    pub group_id: String,
+    #[doc = "Maven artifact."]
+    pub artifact_id: String,
+    #[doc = "Maven version. Missing if the version is managed by a parent POM."]
+    pub version: Option<String>,
+    #[doc = "Maven classifier."]
+    pub classifier: Option<String>,
+    #[doc = "Maven type, such as `jar` or `aar`."]
+    pub packaging: Option<String>,
+    #[doc = "Maven scope. `None` is the same as `compile`."]
+    pub scope: Option<String>,
+    #[doc = "Whether this dependency is marked `<optional>`."]
+    pub optional: bool,
+}
+
+impl PomDependency {
+    #[doc = "Whether this dependency is needed on the classpath of a consumer. Test, provided, system, and optional dependencies are not."]
+    pub fn is_transitive(&self) -> bool {
+        !self.optional
+            && !matches!(
+                self.scope.as_deref(),
+                Some("test") | Some("provided") | Some("system")
+            )
+    }
+
+    #[doc = "Convert to a [`JavaDependency`]. Returns `None` if the version is missing, unresolved, or a range."]
+    pub fn to_java_dependency(&self) -> Option<JavaDependency> {
+        let version = self.version.as_ref()?;
+        if version.contains("${") || version.starts_with('[') || version.starts_with('(') {
+            return None;
+        }
+        Some(JavaDependency {
+            group_id: self.group_id.clone(),
+            artifact_id: self.artifact_id.clone(),
+            version: version.clone(),
+            classifier: self.classifier.clone(),
+            packaging: self.packaging.clone().unwrap_or_else(|| "jar".to_string()),
+            maven_urls: None,
+        })
+    }
+}
+
+fn child_text<'a, 'input: 'a>(node: roxmltree::Node<'a, 'input>, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|x| x.has_tag_name(name))
+        .and_then(|x| x.text())
+        .map(str::trim)
+}
+
+fn substitute_properties(value: &str, properties: &HashMap<String, String>) -> String {
+    let mut out = value.to_string();
+    for (key, replacement) in properties {
+        out = out.replace(&format!("${{{}}}", key), replacement);
+    }
+    out
+}
+
+#[doc = "Parse the `<dependencies>` section of a Maven POM. `${...}` references to `<properties>` and the project coordinates are substituted."]
+pub fn parse_pom_dependencies(pom: &str) -> Result<Vec<PomDependency>> {
+    let doc = roxmltree::Document::parse(pom)?;
+    let project = doc.root_element();
+    let parent = project.children().find(|x| x.has_tag_name("parent"));
+
+    let mut properties = HashMap::new();
+    if let Some(node) = project.children().find(|x| x.has_tag_name("properties")) {
+        for property in node.children().filter(|x| x.is_element()) {
+            properties.insert(
+                property.tag_name().name().to_string(),
+                property.text().unwrap_or_default().trim().to_string(),
+            );
+        }
+    }
+    for (key, name) in [
+        ("project.groupId", "groupId"),
+        ("project.artifactId", "artifactId"),
+        ("project.version", "version"),
+    ] {
+        let value = child_text(project, name).or_else(|| parent.and_then(|x| child_text(x, name)));
+        if let Some(value) = value {
+            properties.insert(key.to_string(), value.to_string());
+        }
+    }
+
+    let Some(dependencies) = project.children().find(|x| x.has_tag_name("dependencies")) else {
+        return Ok(vec![]);
+    };
+    let text =
+        |node, name: &str| child_text(node, name).map(|x| substitute_properties(x, &properties));
+    Ok(dependencies
+        .children()
+        .filter(|x| x.has_tag_name("dependency"))
+        .filter_map(|node| {
+            Some(PomDependency {
+                group_id: text(node, "groupId")?,
+                artifact_id: text(node, "artifactId")?,
+                version: text(node, "version"),
+                classifier: text(node, "classifier"),
+                packaging: text(node, "type"),
+                scope: text(node, "scope"),
+                optional: text(node, "optional").as_deref() == Some("true"),
+            })
+        })
+        .collect())
+}
+
+#[doc = "Contents of an artifact's `maven-metadata.xml`."]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MavenMetadata {
+    #[doc = "Maven group."]
+    pub group_id: Option<String>,
+    #[doc = "Maven artifact."]
+    pub artifact_id: Option<String>,
+    #[doc = "Most recently deployed version, including snapshots and pre-releases."]
+    pub latest: Option<String>,
+    #[doc = "Most recently deployed release version."]
+    pub release: Option<String>,
+    #[doc = "Every published version, in the order the repository lists them."]
+    pub versions: Vec<String>,
+}
+
+impl MavenMetadata {
+    #[doc = "Resolve the URL of `maven-metadata.xml` for an artifact."]
+    pub fn url(maven_url: &str, group_id: &str, artifact_id: &str) -> String {
+        format!(
+            "{0}{1}/{2}/maven-metadata.xml",
+            maven_url,
+            group_id.replace('.', "/"),
+            artifact_id
+        )
+    }
+
+    #[doc = "Parse the contents of a `maven-metadata.xml` file."]
+    pub fn parse(xml: &str) -> Result<Self> {
+        let doc = roxmltree::Document::parse(xml)?;
+        let metadata = doc.root_element();
+        let versioning = metadata.children().find(|x| x.has_tag_name("versioning"));
+        let versioning_text = |name: &str| {
+            versioning
+                .and_then(|x| child_text(x, name))
+                .map(str::to_string)
+        };
+        Ok(Self {
+            group_id: child_text(metadata, "groupId").map(str::to_string),
+            artifact_id: child_text(metadata, "artifactId").map(str::to_string),
+            latest: versioning_text("latest"),
+            release: versioning_text("release"),
+            versions: versioning
+                .and_then(|x| x.children().find(|x| x.has_tag_name("versions")))
+                .map(|x| {
+                    x.children()
+                        .filter(|x| x.has_tag_name("version"))
+                        .filter_map(|x| x.text())
+                        .map(|x| x.trim().to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+
+    #[doc = "[`Self::versions`] sorted oldest first with [`compare_versions`]."]
+    pub fn sorted_versions(&self) -> Vec<String> {
+        let mut versions = self.versions.clone();
+        versions.sort_by(|a, b| compare_versions(a, b));
+        versions
+    }
+
+    #[doc = "The newest version: [`Self::release`], else [`Self::latest`], else the last of [`Self::versions`]."]
+    pub fn newest(&self) -> Option<&str> {
+        self.release
+            .as_deref()
+            .or(self.latest.as_deref())
+            .or(self.versions.last().map(String::as_str))
+    }
+
+    #[doc = "The newest of [`Self::versions`] released for the same season as `wpilib_version`, as read by [`FrcYear::from_version`], which is the newest that is ABI compatible with it. "]
+    #[doc = "Pre-releases are only considered if `wpilib_version` is one itself, since beta vendor libraries go with beta WPILib. `None` if nothing was released for that season."]
+    pub fn newest_compatible(&self, wpilib_version: &str) -> Option<&str> {
+        let year = FrcYear::from_version(wpilib_version)?;
+        let prereleases = wpilib_version.contains('-');
+        self.versions
+            .iter()
+            .filter(|x| FrcYear::from_version(x) == Some(year))
+            .filter(|x| prereleases || !x.contains('-'))
+            .max_by(|a, b| compare_versions(a, b))
+            .map(String::as_str)
+    }
+
+    #[cfg(feature = "download")]
+    #[doc = "Download and parse `maven-metadata.xml` for an artifact."]
+    pub async fn fetch(maven_url: &str, group_id: &str, artifact_id: &str) -> Result<Self> {
+        let bytes = fetch::fetch_bytes(
+            &Self::url(maven_url, group_id, artifact_id),
+            &DownloadConfig::default(),
+        )
+        .await?;
+        Self::parse(&String::from_utf8_lossy(&bytes))
+    }
+}
+
+#[doc = "Order versions such as `24.3.0`, `2024.3.2` or `2025.1.1-beta-2` part by part, numerically where both parts are numbers. "]
+#[doc = "A pre-release (anything after the first `-`) comes before the release it leads up to, as in semver. "]
+#[doc = "A leading component from 20 to 99 is read as a year, so vendors that number releases `24.x` sort among `2024.x`."]
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_release, a_pre) = a.split_once('-').map_or((a, None), |(x, y)| (x, Some(y)));
+    let (b_release, b_pre) = b.split_once('-').map_or((b, None), |(x, y)| (x, Some(y)));
+    compare_parts(a_release.split('.'), b_release.split('.'), true).then_with(|| {
+        match (a_pre, b_pre) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(x), Some(y)) => compare_parts(x.split(['.', '-']), y.split(['.', '-']), false),
+        }
+    })
+}
+
+fn compare_parts<'a>(
+    mut a: impl Iterator<Item = &'a str>,
+    mut b: impl Iterator<Item = &'a str>,
+    years: bool,
+) -> Ordering {
+    let mut first = years;
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(mut x), Ok(mut y)) => {
+                    if first {
+                        for n in [&mut x, &mut y] {
+                            if (20..100).contains(&*n) {
+                                *n += 2000;
+                            }
+                        }
+                    }
+                    x.cmp(&y)
+                }
+                _ => x.cmp(y),
+            },
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (None, None) => return Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+        first = false;
+    }
+}
+
+#[cfg(feature = "download")]
+#[doc = "Every published version of an artifact in the repository at `maven_url`, from its `maven-metadata.xml`, sorted oldest first with [`compare_versions`]. "]
+#[doc = "See [`MavenMetadata::versions`] for the order the repository lists them in."]
+pub async fn list_versions(
+    group_id: &str,
+    artifact_id: &str,
+    maven_url: &str,
+) -> Result<Vec<String>> {
+    Ok(MavenMetadata::fetch(maven_url, group_id, artifact_id)
+        .await?
+        .sorted_versions())
+}
+
+#[cfg(feature = "download")]
+#[doc = "The newest version of an artifact in the repository at `maven_url` that works with `wpilib_version`; see [`MavenMetadata::newest_compatible`]. "]
+#[doc = "Answers questions such as which Phoenix 6 release goes with WPILib 2025.2.1."]
+pub async fn newest_compatible_version(
+    group_id: &str,
+    artifact_id: &str,
+    maven_url: &str,
+    wpilib_version: &str,
+) -> Result<Option<String>> {
+    Ok(MavenMetadata::fetch(maven_url, group_id, artifact_id)
+        .await?
+        .newest_compatible(wpilib_version)
+        .map(str::to_string))
+}
+
+#[cfg(feature = "download")]
+impl VendorDep {
+    #[doc = "Fetch the POM of a java dependency from the first of its repositories that has it."]
+    async fn fetch_pom(&self, dep: &JavaDependency) -> Option<String> {
+        for maven_url in dep.effective_maven_urls(&self.maven_urls) {
+            if let Ok(Some(bytes)) =
+                fetch::fetch_bytes_if_exists(&dep.pom_url(maven_url), &DownloadConfig::default())
+                    .await
+            {
+                return Some(String::from_utf8_lossy(&bytes).into_owned());
+            }
+        }
+        None
+    }
+
+    #[doc = "Resolve [`Self::java_dependencies`] and everything they transitively depend on according to their POMs. "]
+    #[doc = "Each `group:artifact` appears once; the first version encountered wins, as in Maven. Artifacts without a POM are treated as having no dependencies."]
+    pub async fn resolve_java_dependencies(&self) -> Result<Vec<JavaDependency>> {
+        let mut seen = HashSet::new();
+        let mut queue: VecDeque<JavaDependency> = self.java_dependencies.iter().cloned().collect();
+        let mut resolved = Vec::new();
+        while let Some(dep) = queue.pop_front() {
+            if !seen.insert(format!("{}:{}", dep.group_id, dep.artifact_id)) {
+                continue;
+            }
+            if let Some(pom) = self.fetch_pom(&dep).await {
+                for child in parse_pom_dependencies(&pom)? {
+                    if !child.is_transitive() {
+                        continue;
+                    }
+                    if let Some(child) = child.to_java_dependency() {
+                        queue.push_back(child);
+                    }
+                }
+            }
+            resolved.push(dep);
+        }
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+
+    use super::{compare_versions, parse_pom_dependencies, MavenMetadata};
+
+    #[test]
+    fn maven_metadata() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<metadata>
+  <groupId>edu.wpi.first.wpiutil</groupId>
+  <artifactId>wpiutil-cpp</artifactId>
+  <versioning>
+    <latest>2025.1.1-beta-1</latest>
+    <release>2025.1.1-beta-1</release>
+    <versions>
+      <version>2024.3.1</version>
+      <version>2024.3.2</version>
+      <version>2025.1.1-beta-1</version>
+    </versions>
+    <lastUpdated>20241010175034</lastUpdated>
+  </versioning>
+</metadata>"#;
+        let metadata = MavenMetadata::parse(xml).unwrap();
+        assert_eq!(metadata.artifact_id.as_deref(), Some("wpiutil-cpp"));
+        assert_eq!(metadata.release.as_deref(), Some("2025.1.1-beta-1"));
+        assert_eq!(metadata.newest(), Some("2025.1.1-beta-1"));
+        let unreleased = MavenMetadata {
+            release: None,
+            latest: None,
+            ..metadata.clone()
+        };
+        assert_eq!(unreleased.newest(), Some("2025.1.1-beta-1"));
+        assert_eq!(MavenMetadata::default().newest(), None);
+        assert_eq!(
+            metadata.versions,
+            vec!["2024.3.1", "2024.3.2", "2025.1.1-beta-1"]
+        );
+        assert_eq!(
+            MavenMetadata::url(
+                "https://frcmaven.wpi.edu/artifactory/release/",
+                "edu.wpi.first.wpiutil",
+                "wpiutil-cpp"
+            ),
+            "https://frcmaven.wpi.edu/artifactory/release/edu/wpi/first/wpiutil/wpiutil-cpp/maven-metadata.xml"
+        );
+    }
+
+    #[test]
+    fn newest_compatible() {
+        // Phoenix 6 numbers releases by the last two digits of the season.
+        let metadata = MavenMetadata {
+            versions: [
+                "24.3.0",
+                "25.0.0-beta-1",
+                "25.1.0",
+                "25.10.0",
+                "25.2.0",
+                "26.0.0-beta-1",
+            ]
+            .map(str::to_string)
+            .to_vec(),
+            ..Default::default()
+        };
+        assert_eq!(metadata.newest_compatible("2025.2.1"), Some("25.10.0"));
+        assert_eq!(metadata.newest_compatible("2024.3.2"), Some("24.3.0"));
+        assert_eq!(metadata.newest_compatible("2026.1.1"), None);
+        assert_eq!(
+            metadata.newest_compatible("2026.1.1-beta-2"),
+            Some("26.0.0-beta-1")
+        );
+        assert_eq!(metadata.newest_compatible("latest"), None);
+    }
+
+    #[test]
+    fn sorted_versions() {
+        assert_eq!(compare_versions("24.3.0", "24.3.0"), Ordering::Equal);
+        assert_eq!(compare_versions("24.10.0", "24.3.0"), Ordering::Greater);
+        assert_eq!(compare_versions("24.3", "24.3.1"), Ordering::Less);
+        assert_eq!(
+            compare_versions("2025.1.1-beta-1", "2025.1.1"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_versions("2025.1.1-beta-2", "2025.1.1-beta-10"),
+            Ordering::Less
+        );
+        assert_eq!(compare_versions("24.3.0", "2024.3.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.2.0", "1.10.0"), Ordering::Less);
+        let metadata = MavenMetadata {
+            versions: vec![
+                "2025.1.1".to_string(),
+                "24.3.0".to_string(),
+                "2025.1.1-beta-1".to_string(),
+                "2024.10.0".to_string(),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            metadata.sorted_versions(),
+            vec!["24.3.0", "2024.10.0", "2025.1.1-beta-1", "2025.1.1"]
+        );
+    }
+
+    #[cfg(all(feature = "download", unix))]
+    #[test]
+    fn list_versions() {
+        let mirror = tempfile::tempdir().unwrap();
+        let url = MavenMetadata::url(
+            &format!("{}/", mirror.path().display()),
+            "com.example",
+            "example-java",
+        );
+        std::fs::create_dir_all(std::path::Path::new(&url).parent().unwrap()).unwrap();
+        std::fs::write(
+            &url,
+            "<metadata><versioning><versions><version>1.10.0</version><version>1.2.0</version></versions></versioning></metadata>",
+        )
+        .unwrap();
+        let versions = crate::test_util::runtime()
+            .block_on(super::list_versions(
+                "com.example",
+                "example-java",
+                &format!("file://{}/", mirror.path().display()),
+            ))
+            .unwrap();
+        assert_eq!(versions, vec!["1.2.0", "1.10.0"]);
+    }
+
+    #[test]
+    fn pom_dependencies() {
+        let pom = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <modelVersion>4.0.0</modelVersion>
+  <groupId>com.ctre.phoenix6</groupId>
+  <artifactId>wpiapi-java</artifactId>
+  <version>24.3.0</version>
+  <properties>
+    <wpilib.version>2024.3.2</wpilib.version>
+  </properties>
+  <dependencies>
+    <dependency>
+      <groupId>edu.wpi.first.wpilibj</groupId>
+      <artifactId>wpilibj-java</artifactId>
+      <version>${wpilib.version}</version>
+    </dependency>
+    <dependency>
+      <groupId>com.ctre.phoenix6</groupId>
+      <artifactId>api-java</artifactId>
+      <version>${project.version}</version>
+      <scope>runtime</scope>
+    </dependency>
+    <dependency>
+      <groupId>org.junit.jupiter</groupId>
+      <artifactId>junit-jupiter</artifactId>
+      <version>5.10.1</version>
+      <scope>test</scope>
+    </dependency>
+  </dependencies>
+</project>"#;
+        let deps = parse_pom_dependencies(pom).unwrap();
+        assert_eq!(deps.len(), 3);
+        let transitive = deps
+            .iter()
+            .filter(|x| x.is_transitive())
+            .filter_map(|x| x.to_java_dependency())
+            .map(|x| format!("{}:{}:{}", x.group_id, x.artifact_id, x.version))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            transitive,
+            vec![
+                "edu.wpi.first.wpilibj:wpilibj-java:2024.3.2",
+                "com.ctre.phoenix6:api-java:24.3.0",
+            ]
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use std::path::Path;
+
+#[doc = "What sort of native library a file is, as decided by [`classify_native`]."]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NativeKind {
+    #[doc = "A library loaded at runtime: `.so`, `.dylib` or `.dll`."]
+    Shared,
+    #[doc = "A static archive linked into the program: `.a`."]
+    Static,
+    #[doc = "A Windows `.lib`, linked against in place of its `.dll`. Static Windows builds also use `.lib`, and are linked the same way."]
+    Import,
+}
+
+#[doc = "Classify a native library by its extension, returning its kind and the library name as passed to the linker or `System.loadLibrary`. "]
+#[doc = "A leading `lib` is stripped whatever the extension, so `libwpiutil.so`, `libwpiutil.a` and `wpiutil.dll` are all named `wpiutil`. Anything else, such as `.pdb` or `.so.debug` files, is `None`."]
+pub fn classify_native(path: &Path) -> Option<(NativeKind, String)> {
+    let kind = match path.extension()?.to_str()? {
+        "so" | "dylib" | "dll" => NativeKind::Shared,
+        "a" => NativeKind::Static,
+        "lib" => NativeKind::Import,
+        _ => return None,
+    };
+    let stem = path.file_stem()?.to_str()?;
+    let name = match stem.strip_prefix("lib") {
+        Some(name) if !name.is_empty() => name,
+        _ => stem,
+    };
+    Some((kind, name.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::{classify_native, NativeKind};
+
+    #[test]
+    fn classify() {
+        for (file, kind, name) in [
+            ("libwpiutil.so", NativeKind::Shared, "wpiutil"),
+            ("libwpiutil.dylib", NativeKind::Shared, "wpiutil"),
+            ("wpiutil.dll", NativeKind::Shared, "wpiutil"),
+            ("libwpiutil.dll", NativeKind::Shared, "wpiutil"),
+            ("libwpiutil.a", NativeKind::Static, "wpiutil"),
+            ("wpiutil.lib", NativeKind::Import, "wpiutil"),
+            ("lib.so", NativeKind::Shared, "lib"),
+        ] {
+            assert_eq!(
+                classify_native(Path::new(file)),
+                Some((kind, name.to_string())),
+                "{}",
+                file
+            );
+        }
+        for file in [
+            "wpiutil.pdb",
+            "libwpiutil.so.debug",
+            "libwpiutil.so.2024",
+            "README",
+        ] {
+            assert_eq!(classify_native(Path::new(file)), None, "{}", file);
+        }
+    }
+}
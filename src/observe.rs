@@ -0,0 +1,132 @@
+use std::path::{Path, PathBuf};
+
+use crate::{Result, VendorDep};
+
+#[doc = "A C++ dependency found on disk by [`observe_cpp_downloads`]."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObservedArtifact {
+    #[doc = "Maven group, if exactly one of the vendordeps given to [`observe_cpp_downloads`] has a C++ dependency with this artifact id. "]
+    #[doc = "The download tree doesn't record groups, so this is a guess."]
+    pub group_id: Option<String>,
+    #[doc = "Maven artifact, from the directory name."]
+    pub artifact_id: String,
+    #[doc = "The `<artifact_id>` directory."]
+    pub path: PathBuf,
+    #[doc = "Whether `include` holds anything."]
+    pub has_headers: bool,
+    #[doc = "Whether `libs` holds anything."]
+    pub has_libraries: bool,
+}
+
+impl ObservedArtifact {
+    #[doc = "`group:artifact`, with `?` for an unknown group."]
+    pub fn coordinate(&self) -> String {
+        format!(
+            "{}:{}",
+            self.group_id.as_deref().unwrap_or("?"),
+            self.artifact_id
+        )
+    }
+}
+
+fn non_empty_dir(path: &Path) -> Result<bool> {
+    if !path.is_dir() {
+        return Ok(false);
+    }
+    Ok(std::fs::read_dir(path)?.next().is_some())
+}
+
+#[doc = "Scan `root`, laid out like the output of [`VendorDep::download_all_cpp_deps_to_folder`] (`<artifact_id>/include` and `<artifact_id>/libs`), "]
+#[doc = "and list the C++ dependencies that are present, sorted by artifact id. Directories with neither headers nor libraries are left out. "]
+#[doc = "Groups are guessed from the C++ dependencies of `known`, usually the declared vendordeps. "]
+#[doc = "This is the inverse of downloading, for spotting drift between what is declared and what is on disk; see [`VendorDep::undeclared_cpp_artifacts`]."]
+pub fn observe_cpp_downloads(root: &Path, known: &[VendorDep]) -> Result<Vec<ObservedArtifact>> {
+    let mut observed = Vec::new();
+    for entry in std::fs::read_dir(root)? {
+        let path = entry?.path();
+        let Some(artifact_id) = path.file_name().and_then(|x| x.to_str()) else {
+            continue;
+        };
+        let has_headers = non_empty_dir(&path.join("include"))?;
+        let has_libraries = non_empty_dir(&path.join("libs"))?;
+        if !has_headers && !has_libraries {
+            continue;
+        }
+        let mut groups = known
+            .iter()
+            .flat_map(|x| &x.cpp_dependencies)
+            .filter(|x| x.artifact_id == artifact_id)
+            .map(|x| x.group_id.as_str())
+            .collect::<Vec<_>>();
+        groups.sort();
+        groups.dedup();
+        observed.push(ObservedArtifact {
+            group_id: match groups[..] {
+                [group_id] => Some(group_id.to_string()),
+                _ => None,
+            },
+            artifact_id: artifact_id.to_string(),
+            path,
+            has_headers,
+            has_libraries,
+        });
+    }
+    observed.sort_by(|a, b| a.artifact_id.cmp(&b.artifact_id));
+    Ok(observed)
+}
+
+impl VendorDep {
+    #[doc = "The entries of `observed` that none of this vendordep's C++ dependencies account for. "]
+    #[doc = "An entry is accounted for if a dependency has its artifact id and, when its group is known, its group. "]
+    #[doc = "Declared dependencies missing from disk are found by [`Self::verify_downloaded`]."]
+    pub fn undeclared_cpp_artifacts<'a>(
+        &self,
+        observed: &'a [ObservedArtifact],
+    ) -> Vec<&'a ObservedArtifact> {
+        observed
+            .iter()
+            .filter(|x| {
+                !self.cpp_dependencies.iter().any(|dep| {
+                    dep.artifact_id == x.artifact_id
+                        && !x.group_id.as_ref().is_some_and(|g| *g != dep.group_id)
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::observe_cpp_downloads;
+    use crate::VendorDep;
+
+    #[test]
+    fn observe_cpp_downloads_and_drift() {
+        let phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for path in [
+            "wpiapi-cpp/include/ctre/phoenix6/CANcoder.hpp",
+            "wpiapi-cpp/libs/linux/athena/shared/libCTRE_Phoenix6_WPI.so",
+            "REVLib-cpp/libs/linux/athena/shared/libREVLib.so",
+        ] {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+        std::fs::create_dir_all(root.join("empty/include")).unwrap();
+
+        let observed = observe_cpp_downloads(root, std::slice::from_ref(&phoenix)).unwrap();
+        assert_eq!(
+            observed.iter().map(|x| x.coordinate()).collect::<Vec<_>>(),
+            vec!["?:REVLib-cpp", "com.ctre.phoenix6:wpiapi-cpp"]
+        );
+        assert!(!observed[0].has_headers && observed[0].has_libraries);
+        assert!(observed[1].has_headers && observed[1].has_libraries);
+
+        let undeclared = phoenix.undeclared_cpp_artifacts(&observed);
+        assert_eq!(undeclared, vec![&observed[0]]);
+    }
+}
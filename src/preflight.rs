@@ -0,0 +1,284 @@
+use crate::{fetch, ArtifactKind, BinaryPlatform, Coordinate, DownloadConfig, Result, VendorDep};
+
+#[doc = "Whether an artifact can be downloaded, as found by [`VendorDep::validate_downloadable`]."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArtifactStatus {
+    #[doc = "At least one repository has the artifact."]
+    Ok,
+    #[doc = "Every repository answered that the artifact doesn't exist."]
+    Missing,
+    #[doc = "No repository has the artifact, but at least one couldn't be asked, so it may well be there. Holds the last error."]
+    Unreachable(String),
+}
+
+#[doc = "One cell of a [`ValidationReport`]: an artifact of a dependency, for one platform or for all of them."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactCheck {
+    #[doc = "The dependency the artifact belongs to."]
+    pub coordinate: Coordinate,
+    #[doc = "Classifier of the artifact, such as `headers` or `linuxx86-64`. `None` for Java dependencies without one."]
+    pub classifier: Option<String>,
+    #[doc = "The requested platform the artifact is for, or `None` for Java dependencies and C++ headers, which every platform shares. "]
+    #[doc = "The artifact itself may be published for one of its [`BinaryPlatform::fallbacks`]."]
+    pub platform: Option<BinaryPlatform>,
+    #[doc = "Whether it can be downloaded."]
+    pub status: ArtifactStatus,
+}
+
+#[doc = "Result of [`VendorDep::validate_downloadable`]: every artifact the vendordep needs on the requested platforms, and whether it can be downloaded."]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    #[doc = "Java dependencies first, then C++ headers, then the JNI and C++ libraries of each platform in the order requested."]
+    pub checks: Vec<ArtifactCheck>,
+}
+
+impl ValidationReport {
+    #[doc = "Whether every artifact can be downloaded."]
+    pub fn is_ok(&self) -> bool {
+        self.checks.iter().all(|x| x.status == ArtifactStatus::Ok)
+    }
+
+    #[doc = "The artifacts that are missing or unreachable."]
+    pub fn failures(&self) -> impl Iterator<Item = &ArtifactCheck> {
+        self.checks
+            .iter()
+            .filter(|x| x.status != ArtifactStatus::Ok)
+    }
+}
+
+impl VendorDep {
+    #[doc = "Check that every artifact [`Self::download_all_to_folder`] would need on each of `platforms` exists in at least one of its Maven repositories, "]
+    #[doc = "by sending a `HEAD` request for each instead of downloading it. Checks the shared release builds, as well as Java dependencies and C++ headers once for all platforms. "]
+    #[doc = "JNI dependencies that [`skip_invalid_platforms`](crate::JniDependency::skip_invalid_platforms) are left out on platforms they don't list, as downloads leave them out; "]
+    #[doc = "see [`Self::platform_coverage`] for which platforms each dependency lists. "]
+    #[doc = "Problems with the artifacts are reported in the [`ValidationReport`] rather than as errors; fails with [`crate::error::Error::NotALibraryPlatform`] for [`BinaryPlatform::Headers`]."]
+    pub async fn validate_downloadable(
+        &self,
+        platforms: &[BinaryPlatform],
+    ) -> Result<ValidationReport> {
+        self.validate_downloadable_with_config(platforms, &DownloadConfig::default())
+            .await
+    }
+
+    #[doc = "Like [`Self::validate_downloadable`], with additional options."]
+    pub async fn validate_downloadable_with_config(
+        &self,
+        platforms: &[BinaryPlatform],
+        config: &DownloadConfig,
+    ) -> Result<ValidationReport> {
+        for platform in platforms {
+            platform.ensure_library_platform()?;
+        }
+        let coordinate = |group_id: &str, artifact_id: &str, version: &str, kind| Coordinate {
+            group_id: group_id.to_string(),
+            artifact_id: artifact_id.to_string(),
+            version: version.to_string(),
+            kind,
+        };
+        // Each artifact to check, with the URL of each repository that may have it.
+        let mut artifacts = Vec::new();
+        for dep in &self.java_dependencies {
+            artifacts.push((
+                coordinate(
+                    &dep.group_id,
+                    &dep.artifact_id,
+                    &dep.version,
+                    ArtifactKind::Java,
+                ),
+                dep.classifier.clone(),
+                None,
+                dep.effective_maven_urls(&self.maven_urls)
+                    .iter()
+                    .map(|x| dep.get_url(x))
+                    .collect::<Vec<_>>(),
+            ));
+        }
+        for dep in self.cpp_dependencies.iter().filter(|x| x.has_headers()) {
+            artifacts.push((
+                coordinate(
+                    &dep.group_id,
+                    &dep.artifact_id,
+                    &dep.version,
+                    ArtifactKind::Cpp,
+                ),
+                Some(dep.header_classifier.clone()),
+                None,
+                dep.effective_maven_urls(&self.maven_urls)
+                    .iter()
+                    .map(|x| dep.classifier_url(x, &dep.header_classifier))
+                    .collect(),
+            ));
+        }
+        for platform in platforms {
+            for dep in &self.jni_dependencies {
+                if dep.skip_invalid_platforms && !dep.supports_platform(platform) {
+                    continue;
+                }
+                let published = dep
+                    .published_platform(platform)
+                    .unwrap_or_else(|| platform.clone());
+                artifacts.push((
+                    coordinate(
+                        &dep.group_id,
+                        &dep.artifact_id,
+                        &dep.version,
+                        ArtifactKind::Jni,
+                    ),
+                    Some(dep.classifier(published.to_str(), false)),
+                    Some(platform.clone()),
+                    dep.effective_maven_urls(&self.maven_urls)
+                        .iter()
+                        .map(|x| dep.get_url(x, published.to_str(), false))
+                        .collect(),
+                ));
+            }
+            for dep in &self.cpp_dependencies {
+                let published = dep
+                    .published_platform(platform)
+                    .unwrap_or_else(|| platform.clone());
+                artifacts.push((
+                    coordinate(
+                        &dep.group_id,
+                        &dep.artifact_id,
+                        &dep.version,
+                        ArtifactKind::Cpp,
+                    ),
+                    Some(dep.library_classifier(published.to_str(), false, false)),
+                    Some(platform.clone()),
+                    dep.effective_maven_urls(&self.maven_urls)
+                        .iter()
+                        .map(|x| dep.get_url(x, published.to_str(), false, false))
+                        .collect(),
+                ));
+            }
+        }
+        let checks = futures::future::join_all(artifacts.into_iter().map(
+            |(coordinate, classifier, platform, urls)| async move {
+                ArtifactCheck {
+                    coordinate,
+                    classifier,
+                    platform,
+                    status: status(&urls, config).await,
+                }
+            },
+        ))
+        .await;
+        Ok(ValidationReport { checks })
+    }
+}
+
+// Ask each of `urls` in turn whether it has the artifact, stopping at the first that does.
+async fn status(urls: &[String], config: &DownloadConfig) -> ArtifactStatus {
+    let mut unreachable = None;
+    for url in urls {
+        match fetch::head(url, config).await {
+            Ok(fetch::Head::Found(_)) => return ArtifactStatus::Ok,
+            Ok(fetch::Head::Missing) => {}
+            Err(e) => unreachable = Some(e.to_string()),
+        }
+    }
+    match unreachable {
+        Some(e) => ArtifactStatus::Unreachable(e),
+        None => ArtifactStatus::Missing,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ArtifactStatus;
+    use crate::{
+        test_util::{cpp_dep, java_dep, jni_dep, platforms, runtime, Mirror},
+        BinaryPlatform, CppDependency, JniDependency,
+    };
+
+    #[cfg(unix)]
+    #[test]
+    fn validate_downloadable() {
+        let mirror = Mirror::new();
+        let mut dep = mirror
+            .vendordep()
+            .add_java_dependency(java_dep("example-java"))
+            .add_jni_dependency(JniDependency {
+                skip_invalid_platforms: true,
+                valid_platforms: platforms(&["linuxx86-64"]),
+                ..jni_dep("example-jni")
+            })
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                binary_platforms: platforms(&["linuxx86-64", "windowsx86-64"]),
+                ..cpp_dep("example-cpp")
+            })
+            .build();
+        let maven_url = &mirror.maven_url;
+        for url in [
+            dep.java_dependencies[0].get_url(maven_url),
+            dep.jni_dependencies[0].get_url(maven_url, "linuxx86-64", false),
+            dep.cpp_dependencies[0].classifier_url(maven_url, "headers"),
+            dep.cpp_dependencies[0].get_url(maven_url, "linuxx86-64", false, false),
+        ] {
+            mirror.publish(&url, b"PK\x05\x06");
+        }
+        let runtime = runtime();
+
+        let report = runtime
+            .block_on(dep.validate_downloadable(&[
+                BinaryPlatform::LinuxX86_64,
+                BinaryPlatform::WindowsX86_64,
+            ]))
+            .unwrap();
+        let cells = report
+            .checks
+            .iter()
+            .map(|x| {
+                (
+                    x.coordinate.artifact_id.as_str(),
+                    x.classifier.as_deref(),
+                    x.platform.clone(),
+                    x.status.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cells,
+            vec![
+                ("example-java", None, None, ArtifactStatus::Ok),
+                ("example-cpp", Some("headers"), None, ArtifactStatus::Ok),
+                (
+                    "example-jni",
+                    Some("linuxx86-64"),
+                    Some(BinaryPlatform::LinuxX86_64),
+                    ArtifactStatus::Ok
+                ),
+                (
+                    "example-cpp",
+                    Some("linuxx86-64"),
+                    Some(BinaryPlatform::LinuxX86_64),
+                    ArtifactStatus::Ok
+                ),
+                // The JNI dependency skips Windows, which it doesn't list.
+                (
+                    "example-cpp",
+                    Some("windowsx86-64"),
+                    Some(BinaryPlatform::WindowsX86_64),
+                    ArtifactStatus::Missing
+                ),
+            ]
+        );
+        assert!(!report.is_ok());
+        assert_eq!(report.failures().count(), 1);
+
+        dep.maven_urls = vec!["http://127.0.0.1:1/".to_string()];
+        let report = runtime
+            .block_on(dep.validate_downloadable(&[BinaryPlatform::LinuxX86_64]))
+            .unwrap();
+        assert_eq!(report.checks.len(), 4);
+        assert!(report
+            .checks
+            .iter()
+            .all(|x| matches!(x.status, ArtifactStatus::Unreachable(_))));
+
+        assert!(runtime
+            .block_on(dep.validate_downloadable(&[BinaryPlatform::Headers]))
+            .is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use std::{future::Future, time::Duration};
+
+use crate::{error, CancellationToken, Result};
+
+#[doc = "How often a download is retried against the same repository before moving on to the next of [`VendorDep::maven_urls`](crate::VendorDep::maven_urls). "]
+#[doc = "Only transient failures are retried: timeouts, connection errors and HTTP 408, 429 and 5xx responses. Anything else, such as a 404, moves on to the next repository straight away. "]
+#[doc = "The default retries twice, waiting 500ms and then 1s."]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    #[doc = "Attempts after the first one."]
+    pub retries: u32,
+    #[doc = "Wait before the first retry, doubled for each one after it."]
+    pub initial_backoff: Duration,
+}
+
+impl RetryPolicy {
+    #[doc = "Never retry."]
+    pub const NONE: Self = Self {
+        retries: 0,
+        initial_backoff: Duration::ZERO,
+    };
+
+    #[doc = "Retry up to `retries` times, waiting `initial_backoff` before the first retry and twice as long before each one after it."]
+    pub fn new(retries: u32, initial_backoff: Duration) -> Self {
+        Self {
+            retries,
+            initial_backoff,
+        }
+    }
+
+    #[doc = "Run `attempt` until it succeeds, fails with an error that isn't transient, or the retries run out. `cancel` is checked after each wait."]
+    pub(crate) async fn run<T, F: Future<Output = Result<T>>>(
+        &self,
+        cancel: &CancellationToken,
+        mut attempt: impl FnMut() -> F,
+    ) -> Result<T> {
+        let mut backoff = self.initial_backoff;
+        let mut retries = self.retries;
+        loop {
+            match attempt().await {
+                Err(e) if retries > 0 && is_transient(&e) => {
+                    log_debug!("Retrying in {:?} after {}", backoff, e);
+                    retries -= 1;
+                    tokio::time::sleep(backoff).await;
+                    cancel.check()?;
+                    backoff *= 2;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(2, Duration::from_millis(500))
+    }
+}
+
+#[doc = "Whether `err` may go away by trying the same URL again."]
+fn is_transient(err: &error::Error) -> bool {
+    match err {
+        error::Error::HttpStatus { code, .. } => matches!(code, 408 | 429 | 500..=599),
+        error::Error::ReqwestError(e) => e.is_timeout() || e.is_connect(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, time::Duration};
+
+    use super::RetryPolicy;
+    use crate::{error::Error, test_util::runtime, CancellationToken};
+
+    fn status(code: u16) -> Error {
+        Error::HttpStatus {
+            code,
+            url: "https://maven.example.com/a.jar".to_string(),
+        }
+    }
+
+    #[test]
+    fn retries_transient_failures() {
+        let runtime = runtime();
+        let policy = RetryPolicy::new(2, Duration::from_millis(1));
+        let cancel = CancellationToken::new();
+
+        // A 503 is retried until it succeeds.
+        let attempts = Cell::new(0);
+        let res = runtime.block_on(policy.run(&cancel, || {
+            attempts.set(attempts.get() + 1);
+            let attempt = attempts.get();
+            async move {
+                if attempt < 3 {
+                    Err(status(503))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        }));
+        assert_eq!(res.unwrap(), 3);
+
+        // Retries run out.
+        attempts.set(0);
+        let res = runtime.block_on(policy.run(&cancel, || {
+            attempts.set(attempts.get() + 1);
+            async { Err::<(), _>(status(429)) }
+        }));
+        assert!(matches!(res, Err(Error::HttpStatus { code: 429, .. })));
+        assert_eq!(attempts.get(), 3);
+
+        // A 404 goes to the next mirror right away.
+        attempts.set(0);
+        let res = runtime.block_on(policy.run(&cancel, || {
+            attempts.set(attempts.get() + 1);
+            async { Err::<(), _>(status(404)) }
+        }));
+        assert!(matches!(res, Err(Error::HttpStatus { code: 404, .. })));
+        assert_eq!(attempts.get(), 1);
+
+        // Cancelling stops retrying.
+        attempts.set(0);
+        cancel.cancel();
+        let res = runtime.block_on(policy.run(&cancel, || {
+            attempts.set(attempts.get() + 1);
+            async { Err::<(), _>(status(503)) }
+        }));
+        assert!(matches!(res, Err(Error::Cancelled)));
+        assert_eq!(attempts.get(), 1);
+    }
+}
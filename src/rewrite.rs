@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+#[doc = "Rewrites every URL right before it is requested, as stored in [`DownloadConfig::url_rewriter`](crate::DownloadConfig::url_rewriter). "]
+#[doc = "Sees the URLs of headers, libraries, JNI and Java artifacts alike, after the repository and file name have been resolved, "]
+#[doc = "so it can route them through a caching proxy or CDN whatever its path scheme or query parameters."]
+#[derive(Clone)]
+pub struct UrlRewriter(Arc<dyn Fn(&str) -> String + Send + Sync>);
+
+impl UrlRewriter {
+    #[doc = "Rewrite URLs with `f`, which is given the resolved URL and returns the one to request."]
+    pub fn new(f: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    pub(crate) fn rewrite(&self, url: &str) -> String {
+        (self.0)(url)
+    }
+}
+
+impl std::fmt::Debug for UrlRewriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UrlRewriter").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,273 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    classify_native, ArtifactKind, BinaryPlatform, Coordinate, DownloadConfig, JniDependency,
+    JniInfo, NativeKind, Result, VendorDep,
+};
+
+#[doc = "A native library found in a JNI download by [`VendorDep::discover_jni_libraries`], with the dependency it came from."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredLibrary {
+    #[doc = "The library name, as passed to `System.loadLibrary`."]
+    pub name: String,
+    #[doc = "The library file."]
+    pub path: PathBuf,
+    #[doc = "What sort of library it is."]
+    pub kind: NativeKind,
+    #[doc = "The dependency whose artifact held it."]
+    pub source_artifact: Coordinate,
+    #[doc = "Whether that dependency has a [`crate::JniDependency::sim_mode`], that is whether this is a simulation library rather than one for real hardware."]
+    pub is_sim: bool,
+}
+
+#[doc = "The simulation libraries of one JNI dependency, from [`VendorDep::download_all_jni_deps_for_sim`]."]
+#[derive(Debug, Clone)]
+pub struct SimJniLibrary {
+    #[doc = "The dependency."]
+    pub artifact: Coordinate,
+    #[doc = "Its [`crate::JniDependency::sim_mode`], such as `hwsim` or `swsim`."]
+    pub sim_mode: String,
+    #[doc = "Its native libraries."]
+    pub info: JniInfo,
+}
+
+#[doc = "Native libraries for a HAL simulation run, grouped by the dependency and simulation mode they belong to. "]
+#[doc = "Vendordeps may publish a library for each mode, such as hardware-attached (`hwsim`) and software-only (`swsim`) simulation, and a run only loads one of them; see [`Self::for_mode`]."]
+#[derive(Debug, Clone, Default)]
+pub struct SimJniInfo {
+    #[doc = "The downloaded simulation libraries, in the order of [`VendorDep::jni_dependencies`]."]
+    pub libraries: Vec<SimJniLibrary>,
+}
+
+impl SimJniInfo {
+    #[doc = "The simulation modes libraries were downloaded for, without repeats."]
+    pub fn sim_modes(&self) -> Vec<&str> {
+        let mut modes = Vec::new();
+        for library in &self.libraries {
+            if !modes.contains(&library.sim_mode.as_str()) {
+                modes.push(library.sim_mode.as_str());
+            }
+        }
+        modes
+    }
+
+    #[doc = "The libraries to load for a simulation run in `sim_mode`, ready for [`JniInfo::jvm_arg`]."]
+    pub fn for_mode(&self, sim_mode: &str) -> JniInfo {
+        let mut info = JniInfo {
+            library_dirs: vec![],
+            library_names: vec![],
+        };
+        for library in self.libraries.iter().filter(|x| x.sim_mode == sim_mode) {
+            for dir in &library.info.library_dirs {
+                if !info.library_dirs.contains(dir) {
+                    info.library_dirs.push(dir.clone());
+                }
+            }
+            for name in &library.info.library_names {
+                if !info.library_names.contains(name) {
+                    info.library_names.push(name.clone());
+                }
+            }
+        }
+        info
+    }
+}
+
+impl VendorDep {
+    #[doc = "List the native libraries that [`Self::download_all_jni_deps_to_folder`] and [`Self::download_all_jni_deps_for_sim`] extracted into `root`, "]
+    #[doc = "tagged with the JNI dependency each came from, unlike the flat [`crate::CppInfo::libraries`]. This tells the simulation HAL apart from the real one "]
+    #[doc = "when both were downloaded into the same folder. Dependencies that weren't downloaded are left out. Libraries are listed in the order of "]
+    #[doc = "[`Self::jni_dependencies`], and by path within each dependency."]
+    pub fn discover_jni_libraries<P: AsRef<Path>>(
+        &self,
+        root: P,
+    ) -> Result<Vec<DiscoveredLibrary>> {
+        let mut libraries = Vec::new();
+        for dep in &self.jni_dependencies {
+            let dir = root.as_ref().join(&dep.artifact_id);
+            if !dir.exists() {
+                continue;
+            }
+            for item in jwalk::WalkDir::new(&dir).sort(true) {
+                let path = item?.path();
+                let Some((kind, name)) = classify_native(&path) else {
+                    continue;
+                };
+                libraries.push(DiscoveredLibrary {
+                    name,
+                    path,
+                    kind,
+                    source_artifact: jni_coordinate(dep),
+                    is_sim: dep.sim_mode.is_some(),
+                });
+            }
+        }
+        Ok(libraries)
+    }
+
+    #[doc = "Download the JNI dependencies with a [`crate::JniDependency::sim_mode`] for simulating robot code on `platform`, into `<output_folder>/<jni_dependency_name>/` "]
+    #[doc = "like [`Self::download_all_jni_deps_to_folder`]. Dependencies without a simulation mode or not published for `platform` are left out, "]
+    #[doc = "and nothing is downloaded for a platform that isn't [`BinaryPlatform::is_simulation_capable`]."]
+    pub async fn download_all_jni_deps_for_sim<P: AsRef<Path>>(
+        &self,
+        p: P,
+        platform: BinaryPlatform,
+        skip_failed_packages: bool,
+    ) -> Result<SimJniInfo> {
+        self.download_all_jni_deps_for_sim_with_config(
+            p,
+            platform,
+            skip_failed_packages,
+            &DownloadConfig::default(),
+        )
+        .await
+    }
+
+    #[doc = "Like [`Self::download_all_jni_deps_for_sim`], with additional options."]
+    pub async fn download_all_jni_deps_for_sim_with_config<P: AsRef<Path>>(
+        &self,
+        p: P,
+        platform: BinaryPlatform,
+        skip_failed_packages: bool,
+        config: &DownloadConfig,
+    ) -> Result<SimJniInfo> {
+        let path = p.as_ref();
+        std::fs::create_dir_all(path)?;
+        if !platform.is_simulation_capable() {
+            log_info!(
+                "Not downloading simulation libraries of {}, {} can't run simulations",
+                self.name,
+                platform.to_str()
+            );
+            return Ok(SimJniInfo::default());
+        }
+        let platforms = if config.platform_fallbacks {
+            platform.fallbacks()
+        } else {
+            vec![platform]
+        };
+        let deps = self
+            .jni_dependencies
+            .iter()
+            .filter_map(|dep| Some((dep, dep.sim_mode.as_ref()?)))
+            .filter(|(dep, _)| platforms.iter().any(|x| dep.supports_platform(x)))
+            .collect::<Vec<_>>();
+        let parts = futures::future::try_join_all(deps.iter().map(|(dep, _)| {
+            self.download_jni_dep(dep, path, &platforms, false, skip_failed_packages, config)
+        }))
+        .await?;
+        Ok(SimJniInfo {
+            libraries: deps
+                .into_iter()
+                .zip(parts)
+                .filter(|(_, info)| !info.libraries.is_empty())
+                .map(|((dep, sim_mode), info)| SimJniLibrary {
+                    artifact: jni_coordinate(dep),
+                    sim_mode: sim_mode.clone(),
+                    info: info.into(),
+                })
+                .collect(),
+        })
+    }
+}
+
+fn jni_coordinate(dep: &JniDependency) -> Coordinate {
+    Coordinate {
+        group_id: dep.group_id.clone(),
+        artifact_id: dep.artifact_id.clone(),
+        version: dep.version.clone(),
+        kind: ArtifactKind::Jni,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use crate::{
+        test_util::{jni_dep, platforms, runtime, Mirror},
+        BinaryPlatform, JniDependency,
+    };
+
+    #[cfg(unix)]
+    #[test]
+    fn download_all_jni_deps_for_sim() {
+        let mirror = Mirror::new();
+        let jni = |artifact_id, valid_platforms: &[&str], sim_mode: Option<&str>| JniDependency {
+            skip_invalid_platforms: true,
+            valid_platforms: platforms(valid_platforms),
+            sim_mode: sim_mode.map(str::to_string),
+            ..jni_dep(artifact_id)
+        };
+        let dep = mirror
+            .vendordep()
+            .add_jni_dependency(jni(
+                "hw-jni",
+                &["linuxx86-64", "linuxathena"],
+                Some("hwsim"),
+            ))
+            .add_jni_dependency(jni("sw-jni", &["linuxx86-64"], Some("swsim")))
+            .add_jni_dependency(jni("mac-jni", &["osxuniversal"], Some("swsim")))
+            .add_jni_dependency(jni("robot-jni", &["linuxx86-64"], None))
+            .build();
+        for jni in &dep.jni_dependencies {
+            mirror.publish(
+                &jni.get_url(&mirror.maven_url, "linuxx86-64", false),
+                include_bytes!("../tests/fixtures/executable.zip"),
+            );
+        }
+
+        let out = tempdir().unwrap();
+        let runtime = runtime();
+        let info = runtime
+            .block_on(dep.download_all_jni_deps_for_sim(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+            ))
+            .unwrap();
+        assert_eq!(
+            info.libraries
+                .iter()
+                .map(|x| x.artifact.artifact_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["hw-jni", "sw-jni"]
+        );
+        assert_eq!(info.sim_modes(), vec!["hwsim", "swsim"]);
+        let swsim = info.for_mode("swsim");
+        assert_eq!(swsim.library_dirs, vec![out.path().join("sw-jni/lib")]);
+        assert_eq!(swsim.library_names, vec!["example"]);
+        assert!(!out.path().join("robot-jni").exists());
+
+        // The real hardware library lands next to the simulation ones, and only provenance tells them apart.
+        runtime
+            .block_on(dep.download_all_jni_deps_to_folder(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+            ))
+            .unwrap();
+        let discovered = dep.discover_jni_libraries(out.path()).unwrap();
+        assert_eq!(
+            discovered
+                .iter()
+                .map(|x| (x.source_artifact.artifact_id.as_str(), x.is_sim))
+                .collect::<Vec<_>>(),
+            vec![("hw-jni", true), ("sw-jni", true), ("robot-jni", false)]
+        );
+        let real = discovered.iter().find(|x| !x.is_sim).unwrap();
+        assert_eq!(real.name, "example");
+        assert_eq!(real.kind, crate::NativeKind::Shared);
+        assert_eq!(real.path, out.path().join("robot-jni/lib/libexample.so"));
+
+        let athena = runtime
+            .block_on(dep.download_all_jni_deps_for_sim(
+                out.path(),
+                BinaryPlatform::LinuxAthena,
+                false,
+            ))
+            .unwrap();
+        assert!(athena.libraries.is_empty());
+    }
+}
@@ -0,0 +1,392 @@
+use std::{
+    collections::HashMap,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use crate::{error, Result};
+
+#[doc = "Where downloaded jars, extracted files and `.meta` files are written, so they can go to object storage or a cache instead of the local filesystem. "]
+#[doc = "Set through [`DownloadConfig::sink`](crate::DownloadConfig::sink). Paths are the ones the download methods would otherwise write to, that is the output folder joined with the file's place in it; "]
+#[doc = "a sink backed by something other than the filesystem decides how to map them to keys. Methods that read the output folder back, such as the [`CppInfo`](crate::CppInfo) returned by the C++ and JNI downloads, "]
+#[doc = "still look at the local filesystem."]
+pub trait ArtifactSink: Send + Sync {
+    #[doc = "Whether a file is already stored at `path`. Consulted for [`ExtractPolicy`](crate::ExtractPolicy) and conditional downloads."]
+    fn exists(&self, path: &Path) -> bool;
+
+    #[doc = "Store `contents` at `path`, replacing anything already there. `unix_mode` holds the permission bits recorded in the archive, if any. "]
+    #[doc = "Reading `contents` fails if the archive is corrupt; when this fails partway, nothing should be left at `path`."]
+    fn put(&self, path: &Path, contents: &mut dyn Read, unix_mode: Option<u32>) -> Result<()>;
+
+    #[doc = "Store a symlink at `path` pointing to `target`, which is relative to the symlink and stays within the output folder. "]
+    #[doc = "Sinks that can't represent symlinks can keep the default, which leaves them out."]
+    fn put_symlink(&self, path: &Path, target: &Path) -> Result<()> {
+        let _ = (path, target);
+        Ok(())
+    }
+
+    #[doc = "The CRC-32 of the file stored at `path`, for [`ExtractPolicy::SkipIdentical`](crate::ExtractPolicy::SkipIdentical). "]
+    #[doc = "The default is `None`, meaning unknown, in which case the file is always replaced."]
+    fn crc32(&self, path: &Path) -> Result<Option<u32>> {
+        let _ = path;
+        Ok(None)
+    }
+
+    #[doc = "Remove the file stored at `path` even if it is read-only, before [`ExtractPolicy::OverwriteReadOnly`](crate::ExtractPolicy::OverwriteReadOnly) replaces it. "]
+    #[doc = "Sinks without read-only files can keep the default, which does nothing."]
+    fn remove(&self, path: &Path) -> Result<()> {
+        let _ = path;
+        Ok(())
+    }
+
+    #[doc = "Whether the sink supports [`Self::replace_dir`], letting [`DownloadConfig::atomic`](crate::DownloadConfig::atomic) extract into a staging directory. "]
+    #[doc = "The default is false, in which case archives are always extracted in place."]
+    fn can_replace_dirs(&self) -> bool {
+        false
+    }
+
+    #[doc = "Replace the directory at `target`, if there is one, with `staged`, which holds a completely extracted archive. Only called if [`Self::can_replace_dirs`]."]
+    fn replace_dir(&self, staged: &Path, target: &Path) -> Result<()> {
+        let _ = (staged, target);
+        Ok(())
+    }
+}
+
+#[doc = "The default [`ArtifactSink`], writing to the local filesystem and creating directories as needed. "]
+#[doc = "On unix, the permission bits passed to [`ArtifactSink::put`] (such as the executable bit) are applied to the file."]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileSystemSink;
+
+impl ArtifactSink for FileSystemSink {
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn put(&self, path: &Path, contents: &mut dyn Read, unix_mode: Option<u32>) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::File::create(path)?;
+        // A truncated library would only fail later, at link time.
+        if let Err(e) = std::io::copy(contents, &mut file) {
+            drop(file);
+            _ = std::fs::remove_file(path);
+            return Err(e.into());
+        }
+        #[cfg(unix)]
+        if let Some(mode) = unix_mode {
+            use std::os::unix::fs::PermissionsExt;
+            // Only keep the permission bits; setuid/setgid from an archive shouldn't be honored.
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode & 0o777))?;
+        }
+        #[cfg(not(unix))]
+        let _ = unix_mode;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn put_symlink(&self, path: &Path, target: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if std::fs::symlink_metadata(path).is_ok() {
+            std::fs::remove_file(path)?;
+        }
+        std::os::unix::fs::symlink(target, path)?;
+        Ok(())
+    }
+
+    fn crc32(&self, path: &Path) -> Result<Option<u32>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let mut file = std::fs::File::open(path)?;
+        let mut hasher = crc32fast::Hasher::new();
+        let mut buf = [0; 8192];
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        Ok(Some(hasher.finalize()))
+    }
+
+    // Unix only needs the directory to be writable to remove a read-only file, Windows needs the attribute cleared.
+    fn remove(&self, path: &Path) -> Result<()> {
+        #[cfg(windows)]
+        {
+            let mut permissions = std::fs::symlink_metadata(path)?.permissions();
+            #[allow(clippy::permissions_set_readonly_false)]
+            permissions.set_readonly(false);
+            std::fs::set_permissions(path, permissions)?;
+        }
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn can_replace_dirs(&self) -> bool {
+        true
+    }
+
+    // A directory can't be renamed over a non-empty one, so move the old one aside first. `target` is briefly absent, but never partially extracted.
+    fn replace_dir(&self, staged: &Path, target: &Path) -> Result<()> {
+        let mut old = staged.as_os_str().to_owned();
+        old.push(".old");
+        let old = PathBuf::from(old);
+        let had_target = match std::fs::rename(target, &old) {
+            Ok(()) => true,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+            Err(e) => return Err(e.into()),
+        };
+        if let Err(e) = std::fs::rename(staged, target) {
+            if had_target {
+                _ = std::fs::rename(&old, target);
+            }
+            return Err(e.into());
+        }
+        if had_target {
+            _ = std::fs::remove_dir_all(&old);
+        }
+        Ok(())
+    }
+}
+
+#[doc = "A shared [`ArtifactSink`], as stored in [`DownloadConfig::sink`](crate::DownloadConfig::sink). Defaults to [`FileSystemSink`]."]
+#[derive(Clone)]
+pub struct ArtifactStore(Arc<dyn ArtifactSink>);
+
+impl ArtifactStore {
+    #[doc = "Write downloads through `sink`."]
+    pub fn new(sink: impl ArtifactSink + 'static) -> Self {
+        Self(Arc::new(sink))
+    }
+
+    pub(crate) fn exists(&self, path: &Path) -> bool {
+        self.0.exists(path)
+    }
+
+    pub(crate) fn put(
+        &self,
+        path: &Path,
+        contents: &mut dyn Read,
+        unix_mode: Option<u32>,
+    ) -> Result<()> {
+        self.0.put(path, contents, unix_mode)
+    }
+
+    pub(crate) fn put_symlink(&self, path: &Path, target: &Path) -> Result<()> {
+        self.0.put_symlink(path, target)
+    }
+
+    pub(crate) fn crc32(&self, path: &Path) -> Result<Option<u32>> {
+        self.0.crc32(path)
+    }
+
+    pub(crate) fn remove(&self, path: &Path) -> Result<()> {
+        self.0.remove(path)
+    }
+
+    pub(crate) fn can_replace_dirs(&self) -> bool {
+        self.0.can_replace_dirs()
+    }
+
+    pub(crate) fn replace_dir(&self, staged: &Path, target: &Path) -> Result<()> {
+        self.0.replace_dir(staged, target)
+    }
+}
+
+#[doc = "Keeps files in memory, for the `*_to_memory` downloads. Symlinks are left out."]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MemorySink(Arc<Mutex<HashMap<PathBuf, Vec<u8>>>>);
+
+impl MemorySink {
+    #[doc = "Take the files stored so far."]
+    pub(crate) fn take(&self) -> HashMap<PathBuf, Vec<u8>> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+impl ArtifactSink for MemorySink {
+    fn exists(&self, path: &Path) -> bool {
+        self.0.lock().unwrap().contains_key(path)
+    }
+
+    fn put(&self, path: &Path, contents: &mut dyn Read, _unix_mode: Option<u32>) -> Result<()> {
+        let mut bytes = Vec::new();
+        contents.read_to_end(&mut bytes)?;
+        self.0.lock().unwrap().insert(path.to_path_buf(), bytes);
+        Ok(())
+    }
+}
+
+#[doc = "Which artifact wrote each file during one download, so that artifacts sharing an output directory can't silently overwrite each other's files."]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FileClaims(Arc<Mutex<HashMap<PathBuf, String>>>);
+
+impl FileClaims {
+    #[doc = "A store writing through `sink` on behalf of `owner`, which fails with [`error::Error::FileCollision`] when a path was already written by another owner."]
+    pub(crate) fn store_for(&self, sink: &ArtifactStore, owner: String) -> ArtifactStore {
+        ArtifactStore::new(ClaimingSink {
+            inner: sink.clone(),
+            claims: self.clone(),
+            owner,
+        })
+    }
+}
+
+struct ClaimingSink {
+    inner: ArtifactStore,
+    claims: FileClaims,
+    owner: String,
+}
+
+impl ClaimingSink {
+    fn claim(&self, path: &Path) -> Result<()> {
+        let mut claims = self.claims.0.lock().unwrap();
+        match claims.get(path) {
+            Some(first) if *first != self.owner => Err(error::Error::FileCollision {
+                path: path.to_path_buf(),
+                first: first.clone(),
+                second: self.owner.clone(),
+            }),
+            Some(_) => Ok(()),
+            None => {
+                claims.insert(path.to_path_buf(), self.owner.clone());
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ArtifactSink for ClaimingSink {
+    fn exists(&self, path: &Path) -> bool {
+        self.inner.exists(path)
+    }
+
+    fn put(&self, path: &Path, contents: &mut dyn Read, unix_mode: Option<u32>) -> Result<()> {
+        self.claim(path)?;
+        self.inner.put(path, contents, unix_mode)
+    }
+
+    fn put_symlink(&self, path: &Path, target: &Path) -> Result<()> {
+        self.claim(path)?;
+        self.inner.put_symlink(path, target)
+    }
+
+    fn crc32(&self, path: &Path) -> Result<Option<u32>> {
+        self.inner.crc32(path)
+    }
+
+    fn remove(&self, path: &Path) -> Result<()> {
+        self.claim(path)?;
+        self.inner.remove(path)
+    }
+
+    fn can_replace_dirs(&self) -> bool {
+        self.inner.can_replace_dirs()
+    }
+
+    fn replace_dir(&self, staged: &Path, target: &Path) -> Result<()> {
+        self.inner.replace_dir(staged, target)
+    }
+}
+
+impl Default for ArtifactStore {
+    fn default() -> Self {
+        Self::new(FileSystemSink)
+    }
+}
+
+impl std::fmt::Debug for ArtifactStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArtifactStore").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::{ArtifactStore, MemorySink};
+    use crate::{
+        test_util::{cpp_dep, java_dep, runtime, Mirror},
+        BinaryPlatform, CppDependency, DownloadConfig,
+    };
+
+    #[cfg(unix)]
+    #[test]
+    fn custom_sink() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_java_dependency(java_dep("example-java"))
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                ..cpp_dep("example-cpp")
+            })
+            .build();
+        let maven_url = &mirror.maven_url;
+        let cpp = &dep.cpp_dependencies[0];
+        for (path, contents) in [
+            (
+                dep.java_dependencies[0].get_url(maven_url),
+                &b"PK\x03\x04"[..],
+            ),
+            (
+                cpp.get_url(maven_url, "headers", false, false),
+                include_bytes!("../tests/fixtures/headers.zip"),
+            ),
+            (
+                cpp.get_url(maven_url, "linuxx86-64", false, false),
+                include_bytes!("../tests/fixtures/executable.zip"),
+            ),
+        ] {
+            mirror.publish(&path, contents);
+        }
+
+        let sink = MemorySink::default();
+        let config = DownloadConfig {
+            sink: ArtifactStore::new(sink.clone()),
+            ..Default::default()
+        };
+        let out = tempdir().unwrap();
+        let runtime = runtime();
+        runtime
+            .block_on(dep.download_all_java_deps_to_folder_with_config(out.path(), false, &config))
+            .unwrap();
+        runtime
+            .block_on(dep.download_all_cpp_deps_to_folder_with_config(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                false,
+                &config,
+            ))
+            .unwrap();
+
+        let stored = sink.take();
+        let mut paths = stored.keys().cloned().collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                out.path().join("example-cpp/include/frc/Example.h"),
+                out.path().join("example-cpp/include/frc/detail/Impl.h"),
+                out.path().join("example-cpp/libs/bin/halsim-tool"),
+                out.path().join("example-cpp/libs/lib/libexample.so"),
+                out.path().join("example-java-1.0.0.jar"),
+            ]
+        );
+        assert_eq!(
+            stored[&out.path().join("example-java-1.0.0.jar")],
+            b"PK\x03\x04"
+        );
+        assert_eq!(std::fs::read_dir(out.path()).unwrap().count(), 0);
+    }
+}
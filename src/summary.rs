@@ -0,0 +1,173 @@
+use std::sync::{
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+    Arc,
+};
+
+#[doc = "Totals of what downloads did, read from a [`DownloadTally`]."]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DownloadSummary {
+    #[doc = "Artifacts (jars, header archives and library archives) that were downloaded."]
+    pub artifacts_downloaded: usize,
+    #[doc = "Dependencies skipped because they weren't found in any repository."]
+    pub artifacts_skipped: usize,
+    #[doc = "Files extracted from downloaded archives."]
+    pub files_extracted: usize,
+    #[doc = "Size of everything downloaded, in bytes."]
+    pub total_bytes: u64,
+}
+
+#[doc = "How much a download would fetch, from [`VendorDep::estimated_download_size`](crate::VendorDep::estimated_download_size). "]
+#[doc = "Servers behind CDNs often leave out `Content-Length`, so sizes may be unknown; a progress bar should be indeterminate unless [`Self::is_exact`]."]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SizeEstimate {
+    #[doc = "Size of each artifact, in download order. `None` if the server didn't report it or the artifact is missing."]
+    pub sizes: Vec<Option<u64>>,
+    #[doc = "Sum of the known sizes, in bytes."]
+    pub known_bytes: u64,
+    #[doc = "Artifacts that exist but whose size wasn't reported."]
+    pub unknown: usize,
+    #[doc = "Artifacts that weren't found in any repository."]
+    pub missing: usize,
+}
+
+impl SizeEstimate {
+    #[doc = "Whether the size of every artifact that will be downloaded is known, so [`Self::known_bytes`] is the total."]
+    pub fn is_exact(&self) -> bool {
+        self.unknown == 0
+    }
+
+    pub(crate) fn add(&mut self, head: Option<crate::fetch::Head>) {
+        let size = match head {
+            Some(crate::fetch::Head::Found(Some(len))) => {
+                self.known_bytes += len;
+                Some(len)
+            }
+            Some(crate::fetch::Head::Found(None)) => {
+                self.unknown += 1;
+                None
+            }
+            Some(crate::fetch::Head::Missing) | None => {
+                self.missing += 1;
+                None
+            }
+        };
+        self.sizes.push(size);
+    }
+}
+
+#[doc = "Running totals of the downloads using it, set as [`DownloadConfig::tally`](crate::DownloadConfig::tally). Clones share the same totals, "]
+#[doc = "so keep a clone to read the [`DownloadSummary`] once the download finishes."]
+#[derive(Debug, Clone, Default)]
+pub struct DownloadTally(Arc<Inner>);
+
+#[derive(Debug, Default)]
+struct Inner {
+    artifacts_downloaded: AtomicUsize,
+    artifacts_skipped: AtomicUsize,
+    files_extracted: AtomicUsize,
+    total_bytes: AtomicU64,
+}
+
+impl DownloadTally {
+    #[doc = "Create a tally with all totals at zero."]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[doc = "Read the current totals."]
+    pub fn summary(&self) -> DownloadSummary {
+        DownloadSummary {
+            artifacts_downloaded: self.0.artifacts_downloaded.load(Ordering::Relaxed),
+            artifacts_skipped: self.0.artifacts_skipped.load(Ordering::Relaxed),
+            files_extracted: self.0.files_extracted.load(Ordering::Relaxed),
+            total_bytes: self.0.total_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn downloaded(&self, bytes: u64, files_extracted: usize) {
+        self.0.artifacts_downloaded.fetch_add(1, Ordering::Relaxed);
+        self.0.total_bytes.fetch_add(bytes, Ordering::Relaxed);
+        self.0
+            .files_extracted
+            .fetch_add(files_extracted, Ordering::Relaxed);
+    }
+
+    pub(crate) fn skipped(&self) {
+        self.0.artifacts_skipped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DownloadSummary, DownloadTally, SizeEstimate};
+    use crate::{
+        fetch::Head,
+        test_util::{cpp_dep, runtime, Mirror},
+        BinaryPlatform, CppDependency, DownloadConfig,
+    };
+
+    #[test]
+    fn size_estimate() {
+        let mut estimate = SizeEstimate::default();
+        estimate.add(Some(Head::Found(Some(4))));
+        estimate.add(Some(Head::Found(Some(6))));
+        assert!(estimate.is_exact());
+        estimate.add(Some(Head::Found(None)));
+        estimate.add(None);
+        assert_eq!(
+            estimate,
+            SizeEstimate {
+                sizes: vec![Some(4), Some(6), None, None],
+                known_bytes: 10,
+                unknown: 1,
+                missing: 1,
+            }
+        );
+        assert!(!estimate.is_exact());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tally() {
+        let mirror = Mirror::new();
+        let dep = mirror
+            .vendordep()
+            .add_cpp_dependency(cpp_dep("example-cpp"))
+            .add_cpp_dependency(CppDependency {
+                header_classifier: "headers".to_string(),
+                ..cpp_dep("missing-cpp")
+            })
+            .build();
+        let fixture = include_bytes!("../tests/fixtures/executable.zip");
+        mirror.publish(
+            &dep.cpp_dependencies[0].get_url(&mirror.maven_url, "linuxx86-64", false, false),
+            fixture,
+        );
+
+        let tally = DownloadTally::new();
+        let config = DownloadConfig {
+            tally: tally.clone(),
+            ..Default::default()
+        };
+        let out = tempfile::tempdir().unwrap();
+        runtime()
+            .block_on(dep.download_all_cpp_deps_to_folder_with_config(
+                out.path(),
+                BinaryPlatform::LinuxX86_64,
+                false,
+                false,
+                true,
+                &config,
+            ))
+            .unwrap();
+        assert_eq!(
+            tally.summary(),
+            DownloadSummary {
+                artifacts_downloaded: 1,
+                artifacts_skipped: 1,
+                files_extracted: 2,
+                total_bytes: fixture.len() as u64,
+            }
+        );
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{classify_native, CppInfo};
+
+impl CppInfo {
+    #[doc = "Find which of [`Self::libraries`] define `symbol`, to track an `undefined reference` link error down to the library that provides it, "]
+    #[doc = "or to check whether a library on the link line is needed at all. `symbol` is the name the linker sees, so C++ symbols are given mangled, as `nm` prints them without `-C`. "]
+    #[doc = "Scans the library files in [`Self::library_search_paths`] and [`Self::runtime_search_paths`]: the dynamic symbol tables of ELF shared objects "]
+    #[doc = "and the symbol tables of the ELF objects in static archives are read directly, and other formats, such as Mach-O and PE, are handed to `nm` if it is installed. "]
+    #[doc = "Best effort: files that can't be read are skipped. Returns library names in the order of [`Self::libraries`]."]
+    pub fn find_providing_library(&self, symbol: &str) -> Vec<String> {
+        let mut providers = HashSet::new();
+        for (name, path) in self.library_files() {
+            if !providers.contains(&name) && defines(&path, symbol) {
+                providers.insert(name);
+            }
+        }
+        let mut seen = HashSet::new();
+        self.libraries
+            .iter()
+            .filter(|x| providers.contains(*x) && seen.insert(*x))
+            .cloned()
+            .collect()
+    }
+
+    // The files of `Self::libraries` in the library and runtime search paths, with the library each belongs to, in the order of `Self::libraries`.
+    pub(crate) fn library_files(&self) -> Vec<(String, PathBuf)> {
+        let mut files = Vec::new();
+        let mut seen = HashSet::new();
+        for dir in self
+            .library_search_paths
+            .iter()
+            .chain(&self.runtime_search_paths)
+        {
+            if !seen.insert(dir) {
+                continue;
+            }
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Some((_, name)) = classify_native(&path) else {
+                    continue;
+                };
+                if let Some(index) = self.libraries.iter().position(|x| *x == name) {
+                    files.push((index, name, path));
+                }
+            }
+        }
+        files.sort();
+        files
+            .into_iter()
+            .map(|(_, name, path)| (name, path))
+            .collect()
+    }
+}
+
+pub(crate) const ELF_MAGIC: &[u8] = b"\x7fELF";
+pub(crate) const AR_MAGIC: &[u8] = b"!<arch>\n";
+
+// Whether the library at `path` defines `symbol`.
+fn defines(path: &Path, symbol: &str) -> bool {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log_debug!("Skipping {}: {}", path.display(), e);
+            return false;
+        }
+    };
+    let found = if bytes.starts_with(ELF_MAGIC) {
+        elf_defines(&bytes, symbol)
+    } else if bytes.starts_with(AR_MAGIC) {
+        ar_defines(&bytes, symbol)
+    } else {
+        None
+    };
+    match found {
+        Some(found) => found,
+        None => nm_defines(path, symbol),
+    }
+}
+
+// Whether any ELF object in the `ar` archive `bytes` defines `symbol`. `None` if the archive holds objects in another format, as Mach-O archives and Windows `.lib`s do.
+fn ar_defines(bytes: &[u8], symbol: &str) -> Option<bool> {
+    for member in ar_members(bytes)? {
+        if !member.starts_with(ELF_MAGIC) {
+            return None;
+        }
+        if elf_defines(member, symbol)? {
+            return Some(true);
+        }
+    }
+    Some(false)
+}
+
+// The contents of the members of the `ar` archive `bytes`, leaving out symbol indexes and name tables. `None` if it is malformed.
+pub(crate) fn ar_members(bytes: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut members = Vec::new();
+    let mut offset = AR_MAGIC.len();
+    while offset + 60 <= bytes.len() {
+        let header = &bytes[offset..offset + 60];
+        let name = std::str::from_utf8(&header[..16]).ok()?.trim_end();
+        let size: usize = std::str::from_utf8(&header[48..58])
+            .ok()?
+            .trim()
+            .parse()
+            .ok()?;
+        let start = offset + 60;
+        let member = bytes.get(start..start.checked_add(size)?)?;
+        // Members are aligned to two bytes.
+        offset = start + size + size % 2;
+        // BSD archives store long names at the start of the member.
+        let (name, member) = match name.strip_prefix("#1/") {
+            Some(len) => {
+                let len = len.parse::<usize>().ok()?;
+                let name = std::str::from_utf8(member.get(..len)?).unwrap_or_default();
+                (name.trim_end_matches('\0'), member.get(len..)?)
+            }
+            None => (name, member),
+        };
+        // Symbol indexes and the long name table. Other names starting with `/` are members with long names.
+        if matches!(name, "/" | "//" | "/SYM64/") || name.starts_with("__.SYMDEF") {
+            continue;
+        }
+        members.push(member);
+    }
+    Some(members)
+}
+
+// Whether the ELF file `bytes` defines `symbol` as a global or weak symbol, in its dynamic symbol table, or in its symbol table if it has none, as objects don't. `None` if it is malformed.
+fn elf_defines(bytes: &[u8], symbol: &str) -> Option<bool> {
+    let is_64 = match bytes.get(4)? {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+    let little_endian = match bytes.get(5)? {
+        1 => true,
+        2 => false,
+        _ => return None,
+    };
+    let read = |offset: u64, size: usize| -> Option<u64> {
+        let offset = usize::try_from(offset).ok()?;
+        let field = bytes.get(offset..offset.checked_add(size)?)?;
+        Some(field.iter().enumerate().fold(0, |value, (i, byte)| {
+            let shift = if little_endian { i } else { size - 1 - i } * 8;
+            value | ((*byte as u64) << shift)
+        }))
+    };
+    let word = if is_64 { 8 } else { 4 };
+    let (shoff, shentsize, shnum) = if is_64 {
+        (read(0x28, 8)?, read(0x3a, 2)?, read(0x3c, 2)?)
+    } else {
+        (read(0x20, 4)?, read(0x2e, 2)?, read(0x30, 2)?)
+    };
+    // (type, offset, size, link, entry size) of each section.
+    let mut sections = Vec::new();
+    for i in 0..shnum {
+        // Checked up front, so that the fields of the header can't overflow.
+        let base = shoff.checked_add(i.checked_mul(shentsize)?)?;
+        base.checked_add(0x40)?;
+        let (offset, size, link, entsize) = if is_64 {
+            (0x18, 0x20, 0x28, 0x38)
+        } else {
+            (0x10, 0x14, 0x18, 0x24)
+        };
+        sections.push((
+            read(base + 4, 4)?,
+            read(base + offset, word)?,
+            read(base + size, word)?,
+            read(base + link, 4)?,
+            read(base + entsize, word)?,
+        ));
+    }
+    const SHT_SYMTAB: u64 = 2;
+    const SHT_DYNSYM: u64 = 11;
+    let Some(&(_, offset, size, link, entsize)) = sections
+        .iter()
+        .find(|x| x.0 == SHT_DYNSYM)
+        .or_else(|| sections.iter().find(|x| x.0 == SHT_SYMTAB))
+    else {
+        return Some(false);
+    };
+    let &(_, strtab, _, _, _) = sections.get(usize::try_from(link).ok()?)?;
+    if entsize == 0 {
+        return None;
+    }
+    for i in 0..size / entsize {
+        let base = offset.checked_add(i.checked_mul(entsize)?)?;
+        base.checked_add(16)?;
+        let (info, shndx) = if is_64 {
+            (read(base + 4, 1)?, read(base + 6, 2)?)
+        } else {
+            (read(base + 12, 1)?, read(base + 14, 2)?)
+        };
+        // Global, weak and GNU unique symbols that aren't undefined.
+        if shndx == 0 || !matches!(info >> 4, 1 | 2 | 10) {
+            continue;
+        }
+        let name = usize::try_from(strtab.checked_add(read(base, 4)?)?).ok()?;
+        let name = bytes.get(name..)?;
+        let name = &name[..name.iter().position(|x| *x == 0)?];
+        if name == symbol.as_bytes() {
+            return Some(true);
+        }
+    }
+    Some(false)
+}
+
+// Ask `nm` whether the library at `path` defines `symbol`, for formats that aren't read here. Mach-O prefixes symbols with an underscore, which is ignored.
+fn nm_defines(path: &Path, symbol: &str) -> bool {
+    let output = match std::process::Command::new("nm")
+        .arg("-g")
+        .arg(path)
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            log_debug!(
+                "nm could not read {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return false;
+        }
+        Err(e) => {
+            log_debug!("Could not run nm on {}: {}", path.display(), e);
+            return false;
+        }
+    };
+    String::from_utf8_lossy(&output.stdout).lines().any(|line| {
+        // Undefined symbols have no address, so they only have two fields.
+        match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [_, kind, name] => {
+                !matches!(*kind, "U" | "w" | "v")
+                    && (*name == symbol || name.strip_prefix('_') == Some(symbol))
+            }
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+
+    use super::{ar_defines, elf_defines};
+    use crate::CppInfo;
+
+    #[test]
+    fn find_providing_library() {
+        let dir = tempdir().unwrap();
+        let libs = dir.path().join("libs");
+        std::fs::create_dir(&libs).unwrap();
+        std::fs::write(
+            libs.join("libprovider.so"),
+            include_bytes!("../tests/fixtures/libprovider.elf"),
+        )
+        .unwrap();
+        std::fs::write(
+            libs.join("libarchived.a"),
+            include_bytes!("../tests/fixtures/libarchived.ar"),
+        )
+        .unwrap();
+        std::fs::write(libs.join("libbroken.so"), b"\x7fELF").unwrap();
+        let info = CppInfo::new_empty()
+            .with_library_search_path(&libs)
+            .with_library("archived")
+            .with_library("provider")
+            .with_library("broken")
+            .with_library("absent");
+
+        assert_eq!(
+            info.find_providing_library("provider_visible"),
+            ["provider"]
+        );
+        assert_eq!(info.find_providing_library("provider_weak"), ["provider"]);
+        assert_eq!(
+            info.find_providing_library("archived_function"),
+            ["archived"]
+        );
+        // Static functions aren't exported, and undefined symbols aren't provided.
+        for symbol in ["provider_hidden", "consumer_missing", "archived_missing"] {
+            assert!(info.find_providing_library(symbol).is_empty(), "{}", symbol);
+        }
+
+        // Only the libraries being linked are scanned.
+        let info = CppInfo::new_empty()
+            .with_library_search_path(&libs)
+            .with_library("provider");
+        assert!(info.find_providing_library("archived_function").is_empty());
+    }
+
+    #[test]
+    fn malformed_files() {
+        assert_eq!(elf_defines(b"\x7fELF", "x"), None);
+        assert_eq!(elf_defines(b"\x7fELF\x02\x01", "x"), None);
+        assert_eq!(ar_defines(b"!<arch>\n", "x"), Some(false));
+        assert_eq!(ar_defines(b"!<arch>\nnot a header", "x"), Some(false));
+        let mut truncated = include_bytes!("../tests/fixtures/libarchived.ar").to_vec();
+        truncated.truncate(100);
+        assert_eq!(ar_defines(&truncated, "archived_function"), None);
+    }
+}
@@ -0,0 +1,89 @@
+// Fixtures shared by the download tests: a local Maven repository, and dependencies with only the fields a test cares about.
+
+use std::path::Path;
+
+use tempfile::TempDir;
+
+use crate::{CppDependency, JavaDependency, JniDependency, VendorDep, VendorDepBuilder};
+
+pub(crate) const UUID: &str = "9a2f4a3b-8d0e-4f7c-9a51-2f6c0d1e7b44";
+
+// A temporary directory standing in for a Maven repository, removed when dropped.
+pub(crate) struct Mirror {
+    _dir: TempDir,
+    // The repository as a plain path ending in `/`, so the artifact URLs built from it are the paths to publish them at.
+    pub(crate) maven_url: String,
+}
+
+impl Mirror {
+    pub(crate) fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let maven_url = format!("{}/", dir.path().display());
+        Self {
+            _dir: dir,
+            maven_url,
+        }
+    }
+
+    // Publish `contents` at `url`, an artifact URL built from `Self::maven_url`.
+    pub(crate) fn publish(&self, url: &str, contents: &[u8]) {
+        std::fs::create_dir_all(Path::new(url).parent().unwrap()).unwrap();
+        std::fs::write(url, contents).unwrap();
+    }
+
+    // A vendordep named `Example` that downloads from this repository.
+    pub(crate) fn vendordep(&self) -> VendorDepBuilder {
+        VendorDep::builder("Example", UUID).add_maven_url(format!("file://{}", self.maven_url))
+    }
+}
+
+pub(crate) fn java_dep(artifact_id: &str) -> JavaDependency {
+    JavaDependency {
+        group_id: "com.example".to_string(),
+        artifact_id: artifact_id.to_string(),
+        version: "1.0.0".to_string(),
+        classifier: None,
+        packaging: "jar".to_string(),
+        maven_urls: None,
+    }
+}
+
+// A `.zip` packaged JNI dependency for every platform.
+pub(crate) fn jni_dep(artifact_id: &str) -> JniDependency {
+    JniDependency {
+        group_id: "com.example".to_string(),
+        artifact_id: artifact_id.to_string(),
+        version: "1.0.0".to_string(),
+        is_jar: false,
+        skip_invalid_platforms: false,
+        valid_platforms: vec![],
+        sim_mode: None,
+        classifier_template: None,
+        maven_urls: None,
+    }
+}
+
+// A C++ dependency for every platform, without headers.
+pub(crate) fn cpp_dep(artifact_id: &str) -> CppDependency {
+    CppDependency {
+        group_id: "com.example".to_string(),
+        artifact_id: artifact_id.to_string(),
+        version: "1.0.0".to_string(),
+        header_classifier: String::new(),
+        binary_platforms: vec![],
+        classifier_template: None,
+        maven_urls: None,
+        packaging: None,
+    }
+}
+
+pub(crate) fn platforms(platforms: &[&str]) -> Vec<String> {
+    platforms.iter().map(|x| x.to_string()).collect()
+}
+
+pub(crate) fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+}
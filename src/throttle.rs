@@ -0,0 +1,87 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::{
+    sync::{Semaphore, SemaphorePermit},
+    time::Instant,
+};
+
+#[doc = "Limits how hard downloads hit Maven repositories, to be polite to shared community mirrors. Clones share the same limits, so one [`Throttle`] can cover several concurrent downloads. "]
+#[doc = "The default is unlimited."]
+#[derive(Debug, Clone, Default)]
+pub struct Throttle(Arc<Inner>);
+
+#[derive(Debug, Default)]
+struct Inner {
+    semaphore: Option<Semaphore>,
+    min_interval: Option<Duration>,
+    next_request: Mutex<HashMap<String, Instant>>,
+}
+
+impl Throttle {
+    #[doc = "Allow at most `max_concurrency` requests in flight at once (unlimited if 0), and start requests to the same host at least `min_interval` apart."]
+    pub fn new(max_concurrency: usize, min_interval: Option<Duration>) -> Self {
+        Self(Arc::new(Inner {
+            semaphore: (max_concurrency > 0).then(|| Semaphore::new(max_concurrency)),
+            min_interval,
+            next_request: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    #[doc = "Wait until a request to `url` may start. The request counts against the concurrency limit until the returned permit is dropped."]
+    pub(crate) async fn acquire(&self, url: &str) -> Option<SemaphorePermit<'_>> {
+        let permit = match &self.0.semaphore {
+            // The semaphore is never closed, so this only fails if it is.
+            Some(semaphore) => semaphore.acquire().await.ok(),
+            None => None,
+        };
+        if let Some(interval) = self.0.min_interval {
+            let host = reqwest::Url::parse(url)
+                .ok()
+                .and_then(|x| x.host_str().map(str::to_string))
+                .unwrap_or_default();
+            let start = {
+                let mut next_request = self.0.next_request.lock().unwrap();
+                let now = Instant::now();
+                let start = next_request.get(&host).map_or(now, |x| (*x).max(now));
+                next_request.insert(host, start + interval);
+                start
+            };
+            tokio::time::sleep_until(start).await;
+        }
+        permit
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::Throttle;
+    use crate::test_util::runtime;
+
+    #[test]
+    fn throttle() {
+        runtime().block_on(async {
+            let throttle = Throttle::new(1, None);
+            let permit = throttle.acquire("https://frcmaven.wpi.edu/a.jar").await;
+            assert!(tokio::time::timeout(
+                Duration::from_millis(20),
+                throttle.acquire("https://frcmaven.wpi.edu/b.jar")
+            )
+            .await
+            .is_err());
+            drop(permit);
+            throttle.acquire("https://frcmaven.wpi.edu/b.jar").await;
+
+            let throttle = Throttle::new(0, Some(Duration::from_millis(50)));
+            let start = tokio::time::Instant::now();
+            throttle.acquire("https://frcmaven.wpi.edu/a.jar").await;
+            throttle.acquire("https://frcmaven.wpi.edu/b.jar").await;
+            assert!(start.elapsed() >= Duration::from_millis(50));
+        });
+    }
+}
@@ -0,0 +1,508 @@
+use thiserror::Error;
+
+use crate::{
+    ArtifactKind, BinaryPlatform, Coordinate, FrcYear, JavaDependency, PackageSpec, VendorDep,
+};
+
+#[doc = "A problem found by [`VendorDep::validate`]."]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    #[error("Vendordep is for FRC {found}, expected {expected}.")]
+    YearMismatch { expected: FrcYear, found: FrcYear },
+    #[error("{0} is not a valid UUID.")]
+    InvalidUuid(String),
+    #[error("Vendordep does not list any Maven repositories.")]
+    NoMavenUrls,
+}
+
+#[doc = "A reason two vendordeps can't be installed together, found by [`VendorDep::is_compatible_with`]. Displays as a human-readable reason."]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Incompatibility {
+    #[error("{name} is included twice, as versions {first} and {second}.")]
+    VersionConflict {
+        #[doc = "`uuid` of both vendordeps."]
+        uuid: String,
+        #[doc = "`name` of the first vendordep."]
+        name: String,
+        #[doc = "Version of the first vendordep."]
+        first: String,
+        #[doc = "Version of the second vendordep."]
+        second: String,
+    },
+    #[error("{message}")]
+    Conflict {
+        #[doc = "`file_name` of the vendordep declaring the conflict."]
+        declared_by: String,
+        #[doc = "The `error_message` of the conflict, or a generic message if it is empty."]
+        message: String,
+    },
+    #[error("{first_name} is for FRC {first}, but {second_name} is for FRC {second}.")]
+    YearMismatch {
+        #[doc = "`name` of the first vendordep."]
+        first_name: String,
+        #[doc = "Season of the first vendordep."]
+        first: FrcYear,
+        #[doc = "`name` of the second vendordep."]
+        second_name: String,
+        #[doc = "Season of the second vendordep."]
+        second: FrcYear,
+    },
+}
+
+#[doc = "Whether two vendordeps can be installed together, from [`VendorDep::is_compatible_with`]."]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Compatibility {
+    #[doc = "Every reason they can't; empty if they can."]
+    pub problems: Vec<Incompatibility>,
+}
+
+impl Compatibility {
+    #[doc = "Whether there are no [`Self::problems`]."]
+    pub fn is_compatible(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+#[doc = "A `requires` entry whose vendordep is not installed, found by [`VendorDep::check_requirements`]."]
+#[derive(Debug, Clone)]
+pub struct MissingRequirement {
+    #[doc = "`file_name` of the vendordep declaring the requirement."]
+    pub file_name: String,
+    #[doc = "The requirement that is not met."]
+    pub requirement: PackageSpec,
+}
+
+#[doc = "A Java artifact that vendordeps put on the classpath at different versions, found by [`VendorDep::check_java_conflicts`]. "]
+#[doc = "Only one version can be loaded, so code built against the others may fail at runtime."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JavaVersionConflict {
+    #[doc = "Maven group."]
+    pub group_id: String,
+    #[doc = "Maven artifact."]
+    pub artifact_id: String,
+    #[doc = "Maven classifier. Jars with different classifiers are different files, so they don't conflict."]
+    pub classifier: Option<String>,
+    #[doc = "Each version declared, in the order they are first declared, with the `file_name`s of the vendordeps declaring it."]
+    pub versions: Vec<(String, Vec<String>)>,
+}
+
+// Java artifacts keyed by their first declaration, each with every version declared and the vendordeps declaring it.
+type JavaVersions<'a> = Vec<(
+    &'a JavaDependency,
+    Vec<(&'a JavaDependency, Vec<&'a VendorDep>)>,
+)>;
+
+// Java dependencies of `deps` grouped by `group:artifact[:classifier]`, then by version, in the order they are declared.
+fn java_versions(deps: &[VendorDep]) -> JavaVersions<'_> {
+    let mut artifacts: JavaVersions = Vec::new();
+    for dep in deps {
+        for java in &dep.java_dependencies {
+            let same_artifact = |x: &&JavaDependency| {
+                x.group_id == java.group_id
+                    && x.artifact_id == java.artifact_id
+                    && x.classifier == java.classifier
+            };
+            let versions = match artifacts.iter().position(|(x, _)| same_artifact(x)) {
+                Some(i) => &mut artifacts[i].1,
+                None => {
+                    artifacts.push((java, Vec::new()));
+                    &mut artifacts.last_mut().unwrap().1
+                }
+            };
+            match versions.iter_mut().find(|(x, _)| x.version == java.version) {
+                Some((_, declared_by)) => {
+                    if !declared_by.iter().any(|x| std::ptr::eq(*x, dep)) {
+                        declared_by.push(dep);
+                    }
+                }
+                None => versions.push((java, vec![dep])),
+            }
+        }
+    }
+    artifacts
+}
+
+#[doc = "A platform name that isn't one of the [`BinaryPlatform`] variants, found by [`VendorDep::validate_platforms`]."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownPlatform {
+    #[doc = "The JNI or C++ dependency listing the platform."]
+    pub artifact: Coordinate,
+    #[doc = "The platform as written."]
+    pub platform: String,
+}
+
+impl VendorDep {
+    #[doc = "Check that this vendordep targets a season compatible with `expected_year`, has a well-formed `uuid` and lists at least one Maven repository. "]
+    #[doc = "Meant as a pre-flight check when importing third-party vendordep JSON."]
+    pub fn validate(&self, expected_year: impl Into<FrcYear>) -> Result<(), ValidationError> {
+        let expected_year = expected_year.into();
+        if !self.frc_year.is_compatible_with(expected_year) {
+            return Err(ValidationError::YearMismatch {
+                expected: expected_year,
+                found: self.frc_year,
+            });
+        }
+        if uuid::Uuid::parse_str(&self.uuid).is_err() {
+            return Err(ValidationError::InvalidUuid(self.uuid.clone()));
+        }
+        if self.maven_urls.is_empty() {
+            return Err(ValidationError::NoMavenUrls);
+        }
+        Ok(())
+    }
+
+    #[doc = "Check whether this vendordep and `other` can be installed together: they must not be different versions of the same vendordep (by `uuid`), "]
+    #[doc = "neither may list the other in `conflicts_with`, and they must be for compatible seasons. The same version of the same vendordep is compatible with itself."]
+    pub fn is_compatible_with(&self, other: &VendorDep) -> Compatibility {
+        let mut problems = Vec::new();
+        if self.uuid == other.uuid && self.version != other.version {
+            problems.push(Incompatibility::VersionConflict {
+                uuid: self.uuid.clone(),
+                name: self.name.clone(),
+                first: self.version.clone(),
+                second: other.version.clone(),
+            });
+        }
+        for (dep, conflicting) in [(self, other), (other, self)] {
+            for conflict in dep
+                .conflicts_with
+                .iter()
+                .filter(|x| x.uuid == conflicting.uuid)
+            {
+                problems.push(Incompatibility::Conflict {
+                    declared_by: dep.file_name.clone(),
+                    message: if conflict.error_message.is_empty() {
+                        format!("{} conflicts with {}.", dep.name, conflicting.name)
+                    } else {
+                        conflict.error_message.clone()
+                    },
+                });
+            }
+        }
+        if !self.frc_year.is_compatible_with(other.frc_year) {
+            problems.push(Incompatibility::YearMismatch {
+                first_name: self.name.clone(),
+                first: self.frc_year,
+                second_name: other.name.clone(),
+                second: other.frc_year,
+            });
+        }
+        Compatibility { problems }
+    }
+
+    #[doc = "Find the platforms listed in [`crate::JniDependency::valid_platforms`] and [`crate::CppDependency::binary_platforms`] that parse as [`BinaryPlatform::Other`] "]
+    #[doc = "(or as [`BinaryPlatform::Headers`], which isn't a platform), usually typos such as `linux-x86-64`. Parsing tolerates them, but no artifact will be found for them. "]
+    #[doc = "Returns them in the order they are listed; an empty list means every platform is known."]
+    pub fn validate_platforms(&self) -> Vec<UnknownPlatform> {
+        let jni = self.jni_dependencies.iter().map(|x| {
+            (
+                &x.valid_platforms,
+                (&x.group_id, &x.artifact_id, &x.version, ArtifactKind::Jni),
+            )
+        });
+        let cpp = self.cpp_dependencies.iter().map(|x| {
+            (
+                &x.binary_platforms,
+                (&x.group_id, &x.artifact_id, &x.version, ArtifactKind::Cpp),
+            )
+        });
+        let mut unknown = Vec::new();
+        for (platforms, (group_id, artifact_id, version, kind)) in jni.chain(cpp) {
+            for platform in platforms {
+                let parsed = match platform.parse::<BinaryPlatform>() {
+                    Ok(x) => x,
+                    Err(e) => match e {},
+                };
+                if matches!(parsed, BinaryPlatform::Other(_) | BinaryPlatform::Headers) {
+                    unknown.push(UnknownPlatform {
+                        artifact: Coordinate {
+                            group_id: group_id.clone(),
+                            artifact_id: artifact_id.clone(),
+                            version: version.clone(),
+                            kind,
+                        },
+                        platform: platform.clone(),
+                    });
+                }
+            }
+        }
+        unknown
+    }
+
+    #[doc = "The conventional [`Self::file_name`] for a vendordep called `name`: `<name>.json`, with each run of characters other than ASCII letters, digits, `-` and `_` "]
+    #[doc = "replaced by a `-`, so `CTRE-Phoenix (v6)` becomes `CTRE-Phoenix-v6.json`. For generators, so the format isn't hardcoded in each."]
+    pub fn canonical_file_name(name: &str) -> String {
+        let mut stem = String::new();
+        for c in name.chars() {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                stem.push(c);
+            } else if !stem.is_empty() && !stem.ends_with('-') {
+                stem.push('-');
+            }
+        }
+        format!("{}.json", stem.trim_end_matches('-'))
+    }
+
+    #[doc = "Whether [`Self::file_name`] is a `.json` file named after [`Self::name`], ignoring case and punctuation and allowing a prefix or suffix, "]
+    #[doc = "as in `WPILibNewCommands.json` for `WPILib-New-Commands` or `wpilib-hal.json` for `hal`. A mismatch is a common authoring error, "]
+    #[doc = "such as a file name left over from the template the vendordep was copied from, though some vendors name their files differently on purpose."]
+    pub fn file_name_matches_name(&self) -> bool {
+        let normalize = |x: &str| {
+            x.chars()
+                .filter(char::is_ascii_alphanumeric)
+                .map(|x| x.to_ascii_lowercase())
+                .collect::<String>()
+        };
+        let Some(stem) = self
+            .file_name
+            .len()
+            .checked_sub(".json".len())
+            .filter(|&i| self.file_name[i..].eq_ignore_ascii_case(".json"))
+            .map(|i| &self.file_name[..i])
+        else {
+            return false;
+        };
+        let name = normalize(&self.name);
+        !name.is_empty() && normalize(stem).contains(&name)
+    }
+
+    #[doc = "Find every Java artifact that `deps` declare at more than one version, which would put conflicting versions of it on one classpath. "]
+    #[doc = "The Java counterpart of [`Self::is_compatible_with`]; an empty list means the vendordeps agree."]
+    pub fn check_java_conflicts(deps: &[VendorDep]) -> Vec<JavaVersionConflict> {
+        java_versions(deps)
+            .into_iter()
+            .filter(|(_, versions)| versions.len() > 1)
+            .map(|(java, versions)| JavaVersionConflict {
+                group_id: java.group_id.clone(),
+                artifact_id: java.artifact_id.clone(),
+                classifier: java.classifier.clone(),
+                versions: versions
+                    .into_iter()
+                    .map(|(x, declared_by)| {
+                        (
+                            x.version.clone(),
+                            declared_by.iter().map(|x| x.file_name.clone()).collect(),
+                        )
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    #[doc = "Combine the [`Self::java_dependencies`] of `deps` into one classpath, listing artifacts that several vendordeps declare once. "]
+    #[doc = "Each keeps the repositories of the vendordep that first declared it in [`JavaDependency::maven_urls`], so it can be downloaded on its own. "]
+    #[doc = "Fails with every conflict [`Self::check_java_conflicts`] finds."]
+    pub fn merge_java_dependencies(
+        deps: &[VendorDep],
+    ) -> Result<Vec<JavaDependency>, Vec<JavaVersionConflict>> {
+        let conflicts = Self::check_java_conflicts(deps);
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+        Ok(java_versions(deps)
+            .into_iter()
+            .flat_map(|(_, versions)| versions)
+            .map(|(java, declared_by)| JavaDependency {
+                maven_urls: Some(
+                    java.effective_maven_urls(&declared_by[0].maven_urls)
+                        .to_vec(),
+                ),
+                ..java.clone()
+            })
+            .collect())
+    }
+
+    #[doc = "Find every `requires` entry in `deps` whose `uuid` doesn't belong to any vendordep in `deps`."]
+    pub fn check_requirements(deps: &[VendorDep]) -> Vec<MissingRequirement> {
+        deps.iter()
+            .flat_map(|dep| {
+                dep.requires
+                    .iter()
+                    .filter(|x| !deps.iter().any(|other| other.uuid == x.uuid))
+                    .map(|x| MissingRequirement {
+                        file_name: dep.file_name.clone(),
+                        requirement: x.clone(),
+                    })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Incompatibility, ValidationError};
+    use crate::{FrcYear, PackageSpec, VendorDep};
+
+    #[test]
+    fn file_names() {
+        assert_eq!(VendorDep::canonical_file_name("REVLib"), "REVLib.json");
+        assert_eq!(
+            VendorDep::canonical_file_name("CTRE-Phoenix (v6)"),
+            "CTRE-Phoenix-v6.json"
+        );
+        assert_eq!(
+            VendorDep::canonical_file_name("PathplannerLib"),
+            "PathplannerLib.json"
+        );
+
+        let mut dep = VendorDep::builder(
+            "WPILib-New-Commands",
+            "111e20f7-815e-48f8-9dd6-e675ce75b266",
+        )
+        .file_name("WPILibNewCommands.json")
+        .build();
+        assert!(dep.file_name_matches_name());
+        dep.file_name = "wpilib-WPILib-New-Commands.JSON".to_string();
+        assert!(dep.file_name_matches_name());
+        dep.file_name = "ExampleVendordep.json".to_string();
+        assert!(!dep.file_name_matches_name());
+        dep.file_name = "WPILibNewCommands".to_string();
+        assert!(!dep.file_name_matches_name());
+    }
+
+    #[test]
+    fn validate_platforms() {
+        let mut phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        assert!(phoenix.validate_platforms().is_empty());
+
+        phoenix.cpp_dependencies[0]
+            .binary_platforms
+            .push("linux-x86-64".to_string());
+        let unknown = phoenix.validate_platforms();
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].artifact.artifact_id, "wpiapi-cpp");
+        assert_eq!(unknown[0].platform, "linux-x86-64");
+    }
+
+    #[test]
+    fn is_compatible_with() {
+        let phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        assert!(phoenix.is_compatible_with(&phoenix).is_compatible());
+
+        let mut older: VendorDep = serde_json::from_str(&phoenix.to_json(false).unwrap()).unwrap();
+        older.version = "24.1.0".to_string();
+        older.frc_year = FrcYear(2023);
+        let problems = phoenix.is_compatible_with(&older).problems;
+        assert_eq!(problems.len(), 2);
+        assert!(matches!(
+            &problems[0],
+            Incompatibility::VersionConflict { first, second, .. } if first == "24.3.0" && second == "24.1.0"
+        ));
+        assert_eq!(
+            problems[1].to_string(),
+            format!(
+                "{0} is for FRC 2024, but {0} is for FRC 2023.",
+                phoenix.name
+            )
+        );
+
+        let other = VendorDep::builder("Other", "3f7a0c6e-5d2b-4e8a-9c1f-6b0d2e4a8c73")
+            .frc_year(2024)
+            .add_conflict(PackageSpec {
+                uuid: phoenix.uuid.clone(),
+                error_message: "Other replaces Phoenix 6.".to_string(),
+                offline_file_name: phoenix.file_name.clone(),
+            })
+            .build();
+        let compatibility = phoenix.is_compatible_with(&other);
+        assert!(!compatibility.is_compatible());
+        assert_eq!(
+            compatibility.problems,
+            vec![Incompatibility::Conflict {
+                declared_by: other.file_name.clone(),
+                message: "Other replaces Phoenix 6.".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let mut dep: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        assert_eq!(dep.validate(2024), Ok(()));
+        assert_eq!(
+            dep.validate(2025),
+            Err(ValidationError::YearMismatch {
+                expected: FrcYear(2025),
+                found: FrcYear(2024)
+            })
+        );
+        dep.maven_urls.clear();
+        assert_eq!(dep.validate(2024), Err(ValidationError::NoMavenUrls));
+        dep.uuid = "not-a-uuid".to_string();
+        assert_eq!(
+            dep.validate(2024),
+            Err(ValidationError::InvalidUuid("not-a-uuid".to_string()))
+        );
+    }
+
+    #[test]
+    fn java_conflicts() {
+        let phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let mut other: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        other.file_name = "Other.json".to_string();
+        other.maven_urls = vec!["https://example.com/maven/".to_string()];
+
+        // The same artifacts at the same versions are listed once.
+        let deps = [phoenix, other];
+        assert!(VendorDep::check_java_conflicts(&deps).is_empty());
+        let merged = VendorDep::merge_java_dependencies(&deps).unwrap();
+        assert_eq!(merged.len(), deps[0].java_dependencies.len());
+        assert_eq!(merged[0].maven_urls.as_ref().unwrap(), &deps[0].maven_urls);
+
+        let [phoenix, mut other] = deps;
+        other.java_dependencies[0].version = "99.0.0".to_string();
+        let deps = [phoenix, other];
+        let conflicts = VendorDep::check_java_conflicts(&deps);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            conflicts[0].artifact_id,
+            deps[0].java_dependencies[0].artifact_id
+        );
+        assert_eq!(
+            conflicts[0].versions,
+            vec![
+                (
+                    deps[0].java_dependencies[0].version.clone(),
+                    vec![deps[0].file_name.clone()]
+                ),
+                ("99.0.0".to_string(), vec!["Other.json".to_string()]),
+            ]
+        );
+        assert_eq!(
+            VendorDep::merge_java_dependencies(&deps).unwrap_err(),
+            conflicts
+        );
+    }
+
+    #[test]
+    fn check_requirements() {
+        let phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let mut dependent: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        dependent.file_name = "PathplannerLib.json".to_string();
+        dependent.uuid = "1b42324f-17c6-4875-8e77-1c312bc8c786".to_string();
+        dependent.requires = serde_json::from_str(
+            r#"[{"uuid": "e995de00-2c64-4df5-8831-c1441420ff19", "errorMessage": "Phoenix 6 is required.", "offlineFileName": "Phoenix6.json"}]"#,
+        )
+        .unwrap();
+
+        let missing = VendorDep::check_requirements(std::slice::from_ref(&dependent));
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].file_name, "PathplannerLib.json");
+        assert_eq!(missing[0].requirement.offline_file_name, "Phoenix6.json");
+        assert!(VendorDep::check_requirements(&[phoenix, dependent]).is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+use std::path::{Path, PathBuf};
+
+use crate::{ArtifactKind, BinaryPlatform, Coordinate, JavaDependency, Result, VendorDep};
+
+#[doc = "Why an artifact failed [`VendorDep::verify_downloaded`]."]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingKind {
+    #[doc = "The expected file or directory does not exist."]
+    Absent,
+    #[doc = "The expected file or directory exists but is empty."]
+    Empty,
+}
+
+#[doc = "An artifact that is not present in a download directory, found by [`VendorDep::verify_downloaded`]."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingArtifact {
+    #[doc = "The dependency the artifact belongs to."]
+    pub artifact: Coordinate,
+    #[doc = "Where the artifact was expected."]
+    pub path: PathBuf,
+    #[doc = "What is wrong with it."]
+    pub kind: MissingKind,
+}
+
+#[doc = "A jar of some other version of a Java dependency, found by [`VendorDep::find_version_mismatches`]."]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionMismatch {
+    #[doc = "The dependency, at its declared version."]
+    pub artifact: Coordinate,
+    #[doc = "The jar."]
+    pub path: PathBuf,
+    #[doc = "The version in the jar's file name."]
+    pub found_version: String,
+}
+
+// The version in `file_name` if it names a build of `dep`, such as `24.1.0` for `wpiapi-java-24.1.0.jar`.
+fn jar_version<'a>(dep: &JavaDependency, file_name: &'a str) -> Option<&'a str> {
+    let rest = file_name
+        .strip_prefix(dep.artifact_id.as_str())?
+        .strip_prefix('-')?
+        .strip_suffix(dep.packaging.as_str())?
+        .strip_suffix('.')?;
+    let version = match &dep.classifier {
+        Some(classifier) => rest.strip_suffix(classifier.as_str())?.strip_suffix('-')?,
+        // Another classifier of the declared version, such as `-sources`.
+        None if rest
+            .strip_prefix(dep.version.as_str())
+            .is_some_and(|x| x.starts_with('-')) =>
+        {
+            return None
+        }
+        None => rest,
+    };
+    // Anything else is a different artifact whose id starts with this one, such as `wpiapi-java-sim`.
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then_some(version)
+}
+
+fn check(path: PathBuf, artifact: impl FnOnce() -> Coordinate) -> Result<Option<MissingArtifact>> {
+    let kind = match std::fs::metadata(&path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => MissingKind::Absent,
+        Err(e) => return Err(e.into()),
+        Ok(metadata) if metadata.is_dir() => {
+            if std::fs::read_dir(&path)?.next().is_some() {
+                return Ok(None);
+            }
+            MissingKind::Empty
+        }
+        Ok(metadata) if metadata.len() == 0 => MissingKind::Empty,
+        Ok(_) => return Ok(None),
+    };
+    Ok(Some(MissingArtifact {
+        artifact: artifact(),
+        path,
+        kind,
+    }))
+}
+
+impl VendorDep {
+    #[doc = "Check that `root`, laid out by [`VendorDep::download_all_to_folder`], holds every artifact this vendordep needs on `platform`: "]
+    #[doc = "each jar in `java/`, each JNI dependency published for `platform` in `jni/`, and the headers and libraries of each C++ dependency in `cpp/`. "]
+    #[doc = "Nothing is downloaded. Returns what is absent or empty; an empty list means the copy is complete."]
+    pub fn verify_downloaded(
+        &self,
+        root: &Path,
+        platform: &BinaryPlatform,
+    ) -> Result<Vec<MissingArtifact>> {
+        let coordinate = |group_id: &str, artifact_id: &str, version: &str, kind| Coordinate {
+            group_id: group_id.to_string(),
+            artifact_id: artifact_id.to_string(),
+            version: version.to_string(),
+            kind,
+        };
+        let mut missing = Vec::new();
+        for dep in &self.java_dependencies {
+            missing.extend(check(root.join("java").join(dep.file_name()), || {
+                coordinate(
+                    &dep.group_id,
+                    &dep.artifact_id,
+                    &dep.version,
+                    ArtifactKind::Java,
+                )
+            })?);
+        }
+        for dep in &self.jni_dependencies {
+            if dep.skip_invalid_platforms && !dep.supports_platform(platform) {
+                continue;
+            }
+            missing.extend(check(root.join("jni").join(&dep.artifact_id), || {
+                coordinate(
+                    &dep.group_id,
+                    &dep.artifact_id,
+                    &dep.version,
+                    ArtifactKind::Jni,
+                )
+            })?);
+        }
+        for dep in &self.cpp_dependencies {
+            let dep_path = root.join("cpp").join(&dep.artifact_id);
+            if dep.has_headers() {
+                missing.extend(check(dep_path.join("include"), || {
+                    coordinate(
+                        &dep.group_id,
+                        &dep.artifact_id,
+                        &dep.version,
+                        ArtifactKind::Cpp,
+                    )
+                })?);
+            }
+            if dep.supports_platform(platform) {
+                missing.extend(check(dep_path.join("libs"), || {
+                    coordinate(
+                        &dep.group_id,
+                        &dep.artifact_id,
+                        &dep.version,
+                        ArtifactKind::Cpp,
+                    )
+                })?);
+            }
+        }
+        Ok(missing)
+    }
+
+    #[doc = "Find jars in `root/java/`, laid out by [`VendorDep::download_all_to_folder`], that are another version of one of this vendordep's Java dependencies, "]
+    #[doc = "such as one left behind by an older vendordep sharing the folder. Such a jar may be loaded instead of the declared one. "]
+    #[doc = "Versions are read from file names; nothing is opened or downloaded. A missing folder has no mismatches."]
+    pub fn find_version_mismatches(&self, root: &Path) -> Result<Vec<VersionMismatch>> {
+        let entries = match std::fs::read_dir(root.join("java")) {
+            Ok(x) => x,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let mut files = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if let Ok(file_name) = entry.file_name().into_string() {
+                files.push((file_name, entry.path()));
+            }
+        }
+        files.sort();
+        let mut mismatches = Vec::new();
+        for dep in &self.java_dependencies {
+            for (file_name, path) in &files {
+                let Some(version) = jar_version(dep, file_name) else {
+                    continue;
+                };
+                if version != dep.version {
+                    mismatches.push(VersionMismatch {
+                        artifact: Coordinate {
+                            group_id: dep.group_id.clone(),
+                            artifact_id: dep.artifact_id.clone(),
+                            version: dep.version.clone(),
+                            kind: ArtifactKind::Java,
+                        },
+                        path: path.clone(),
+                        found_version: version.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(mismatches)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{jar_version, MissingKind};
+    use crate::{BinaryPlatform, VendorDep};
+
+    #[test]
+    fn verify_downloaded() {
+        let phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for (path, contents) in [
+            ("java/wpiapi-java-24.3.0.jar", &b"PK\x05\x06"[..]),
+            (
+                "jni/tools/linux/athena/shared/libCTRE_PhoenixTools.so",
+                b"\x7fELF",
+            ),
+            (
+                "cpp/wpiapi-cpp/include/ctre/phoenix6/CANcoder.hpp",
+                b"#pragma once",
+            ),
+            (
+                "cpp/wpiapi-cpp/libs/linux/athena/shared/libCTRE_Phoenix6_WPI.so",
+                b"\x7fELF",
+            ),
+            (
+                "cpp/wpiapi-cpp-sim/include/ctre/phoenix6/CANcoder.hpp",
+                b"#pragma once",
+            ),
+        ] {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+        // The sim dependencies aren't published for the roboRIO.
+        assert!(phoenix
+            .verify_downloaded(root, &BinaryPlatform::LinuxAthena)
+            .unwrap()
+            .is_empty());
+
+        std::fs::write(root.join("java/wpiapi-java-24.3.0.jar"), b"").unwrap();
+        std::fs::create_dir_all(root.join("jni/tools-sim")).unwrap();
+        let missing = phoenix
+            .verify_downloaded(root, &BinaryPlatform::LinuxX86_64)
+            .unwrap()
+            .into_iter()
+            .map(|x| (x.path.strip_prefix(root).unwrap().to_path_buf(), x.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            missing,
+            vec![
+                ("java/wpiapi-java-24.3.0.jar".into(), MissingKind::Empty),
+                ("jni/tools-sim".into(), MissingKind::Empty),
+                ("cpp/wpiapi-cpp-sim/libs".into(), MissingKind::Absent),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_version_mismatches() {
+        let phoenix: VendorDep =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/Phoenix6-frc2024.json"))
+                .unwrap();
+        let dep = &phoenix.java_dependencies[0];
+        assert_eq!(jar_version(dep, "wpiapi-java-24.1.0.jar"), Some("24.1.0"));
+        assert_eq!(jar_version(dep, "wpiapi-java-24.3.0-sources.jar"), None);
+        assert_eq!(jar_version(dep, "wpiapi-java-sim-24.1.0.jar"), None);
+        assert_eq!(jar_version(dep, "wpiapi-java-24.1.0.pom"), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        assert!(phoenix.find_version_mismatches(root).unwrap().is_empty());
+        std::fs::create_dir_all(root.join("java")).unwrap();
+        for file_name in [
+            "wpiapi-java-24.3.0.jar",
+            "wpiapi-java-24.1.0.jar",
+            "Other-1.0.0.jar",
+        ] {
+            std::fs::write(root.join("java").join(file_name), b"PK\x05\x06").unwrap();
+        }
+        let mismatches = phoenix.find_version_mismatches(root).unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].artifact.version, "24.3.0");
+        assert_eq!(mismatches[0].found_version, "24.1.0");
+        assert_eq!(mismatches[0].path, root.join("java/wpiapi-java-24.1.0.jar"));
+    }
+}
@@ -0,0 +1,236 @@
+#[cfg(feature = "download")]
+use crate::{maven::MavenMetadata, Result};
+use crate::{
+    ArtifactKind, Coordinate, CppDependency, FrcYear, JavaDependency, JniDependency, VendorDep,
+};
+
+#[doc = "Maven repository for WPILib releases. The same as [`crate::WPILIB_RELEASE_MAVEN_REPO`]."]
+pub const RELEASE_MAVEN_REPO: &str = crate::WPILIB_RELEASE_MAVEN_REPO;
+
+#[doc = "Prefix of the Maven group of every WPILib library; see [`group_id`]."]
+pub const GROUP_PREFIX: &str = "edu.wpi.first";
+
+#[doc = "Classifier of the header artifacts of WPILib's C++ libraries."]
+pub const HEADER_CLASSIFIER: &str = "headers";
+
+#[doc = "Platforms WPILib publishes native artifacts for."]
+pub const PLATFORMS: &[&str] = &[
+    "linuxarm32",
+    "linuxarm64",
+    "linuxathena",
+    "linuxx86-64",
+    "osxuniversal",
+    "windowsarm64",
+    "windowsx86-64",
+];
+
+#[doc = "Maven group of the WPILib library `name`, such as `edu.wpi.first.wpimath` for `wpimath`."]
+pub fn group_id(name: &str) -> String {
+    format!("{}.{}", GROUP_PREFIX, name)
+}
+
+#[doc = "Suffix of the artifact ids of a library's artifacts of `kind`: `-java`, `-jni` or `-cpp`."]
+pub fn artifact_suffix(kind: ArtifactKind) -> &'static str {
+    match kind {
+        ArtifactKind::Java => "-java",
+        ArtifactKind::Jni => "-jni",
+        ArtifactKind::Cpp => "-cpp",
+    }
+}
+
+#[doc = "Maven artifact of the WPILib library `name` of `kind`, such as `wpimath-cpp`."]
+pub fn artifact_id(name: &str, kind: ArtifactKind) -> String {
+    format!("{}{}", name, artifact_suffix(kind))
+}
+
+#[doc = "Which kind of artifact `artifact_id` is within the library `name`, the inverse of [`artifact_id`]. Maven paths are case-sensitive, so this must match exactly: "]
+#[doc = "`wpilibNewCommands-cpp` is an artifact of `wpilibNewCommands`, while `wpimathjni` or `WPIMath-cpp` are not artifacts of `wpimath`."]
+pub fn artifact_kind(name: &str, artifact_id: &str) -> Option<ArtifactKind> {
+    [ArtifactKind::Java, ArtifactKind::Jni, ArtifactKind::Cpp]
+        .into_iter()
+        .find(|&kind| artifact_id.strip_prefix(name) == Some(artifact_suffix(kind)))
+}
+
+#[doc = "File name of the vendordep `wpilib-index` generates for the WPILib library `name`, such as `wpilib-wpimath.json`; see [`VendorDep::canonical_file_name`]."]
+pub fn file_name(name: &str) -> String {
+    format!("wpilib-{}", VendorDep::canonical_file_name(name))
+}
+
+#[doc = "Maven coordinates of the WPILib library `name` of `kind` at `version`."]
+pub fn coordinate(name: &str, kind: ArtifactKind, version: &str) -> Coordinate {
+    Coordinate {
+        group_id: group_id(name),
+        artifact_id: artifact_id(name, kind),
+        version: version.to_string(),
+        kind,
+    }
+}
+
+// Libraries making up WPILib itself, as `(name, has Java, has JNI, has C++)`. Each is published as
+// `edu.wpi.first.<name>:<name>-(java|jni|cpp)`, the same layout `wpilib-index` crawls.
+const CORE_LIBRARIES: &[(&str, bool, bool, bool)] = &[
+    ("wpiutil", true, true, true),
+    ("wpinet", true, true, true),
+    ("ntcore", true, true, true),
+    ("hal", true, true, true),
+    ("wpimath", true, true, true),
+    ("wpiunits", true, false, false),
+    ("cscore", true, true, true),
+    ("cameraserver", true, false, true),
+    ("apriltag", true, true, true),
+    ("wpilibc", false, false, true),
+    ("wpilibj", true, false, false),
+];
+
+#[cfg(feature = "download")]
+#[doc = "Look up the newest WPILib version published to [`RELEASE_MAVEN_REPO`], from the `maven-metadata.xml` of `wpilibj-java`. "]
+#[doc = "Unlike [`WPILIB_LATEST_VERSION`](crate::WPILIB_LATEST_VERSION), this doesn't go stale, but it needs the network; fall back to the constant if it fails."]
+pub async fn latest_wpilib_version() -> Result<String> {
+    const GROUP_ID: &str = "edu.wpi.first.wpilibj";
+    const ARTIFACT_ID: &str = "wpilibj-java";
+    let metadata = MavenMetadata::fetch(RELEASE_MAVEN_REPO, GROUP_ID, ARTIFACT_ID).await?;
+    match metadata.newest() {
+        Some(version) => Ok(version.to_string()),
+        None => Err(crate::error::Error::NotFoundError {
+            group_id: GROUP_ID.to_string(),
+            artifact_id: ARTIFACT_ID.to_string(),
+            version: "latest".to_string(),
+            tried_urls: vec![MavenMetadata::url(
+                RELEASE_MAVEN_REPO,
+                GROUP_ID,
+                ARTIFACT_ID,
+            )],
+        }),
+    }
+}
+
+#[doc = "UUID of the [`VendorDep`] returned by [`VendorDep::wpilib_core`]."]
+pub const WPILIB_CORE_UUID: &str = "0d2a6c3e-5b1f-4c8e-9a47-3e6f2d8b1c05";
+
+impl VendorDep {
+    #[doc = "The base WPILib libraries (wpiutil, wpinet, ntcore, hal, wpimath, wpiunits, cscore, cameraserver, apriltag, wpilibc and wpilibj) "]
+    #[doc = "of `version` as a [`VendorDep`], downloading from [`RELEASE_MAVEN_REPO`]. WPILib doesn't publish vendordep JSON for these, "]
+    #[doc = "so the coordinates are built in. Command-based libraries are left out, since GradleRIO treats them as vendordeps of their own."]
+    pub fn wpilib_core(year: impl Into<FrcYear>, version: &str) -> VendorDep {
+        let mut builder = VendorDep::builder("WPILib", WPILIB_CORE_UUID)
+            .version(version)
+            .frc_year(year)
+            .add_maven_url(RELEASE_MAVEN_REPO);
+        for &(name, java, jni, cpp) in CORE_LIBRARIES {
+            if java {
+                builder = builder.add_java_dependency(JavaDependency {
+                    group_id: group_id(name),
+                    artifact_id: artifact_id(name, ArtifactKind::Java),
+                    version: version.to_string(),
+                    classifier: None,
+                    packaging: "jar".to_string(),
+                    maven_urls: None,
+                });
+            }
+            if jni {
+                builder = builder.add_jni_dependency(JniDependency {
+                    group_id: group_id(name),
+                    artifact_id: artifact_id(name, ArtifactKind::Jni),
+                    version: version.to_string(),
+                    is_jar: true,
+                    skip_invalid_platforms: true,
+                    valid_platforms: PLATFORMS.iter().map(|x| x.to_string()).collect(),
+                    sim_mode: None,
+                    classifier_template: None,
+                    maven_urls: None,
+                });
+            }
+            if cpp {
+                builder = builder.add_cpp_dependency(CppDependency {
+                    group_id: group_id(name),
+                    artifact_id: artifact_id(name, ArtifactKind::Cpp),
+                    version: version.to_string(),
+                    header_classifier: HEADER_CLASSIFIER.to_string(),
+                    binary_platforms: PLATFORMS.iter().map(|x| x.to_string()).collect(),
+                    classifier_template: None,
+                    maven_urls: None,
+                    packaging: None,
+                });
+            }
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{artifact_kind, coordinate, file_name};
+    use crate::{ArtifactKind, VendorDep, WPILIB_LATEST_VERSION, WPILIB_LATEST_YEAR};
+
+    #[test]
+    fn artifact_kinds() {
+        // Artifact folders as listed under https://frcmaven.wpi.edu/artifactory/release/edu/wpi/first/
+        assert_eq!(
+            artifact_kind("wpimath", "wpimath-cpp"),
+            Some(ArtifactKind::Cpp)
+        );
+        assert_eq!(
+            artifact_kind("wpimath", "wpimath-java"),
+            Some(ArtifactKind::Java)
+        );
+        assert_eq!(artifact_kind("hal", "hal-jni"), Some(ArtifactKind::Jni));
+        assert_eq!(artifact_kind("wpimath", "wpimathjni"), None);
+        assert_eq!(artifact_kind("wpimath", "WPIMath-cpp"), None);
+        assert_eq!(artifact_kind("wpiutil", "wpiutil-cpp-sources"), None);
+        assert_eq!(artifact_kind("wpi", "wpiutil-cpp"), None);
+    }
+
+    #[test]
+    fn coordinates() {
+        let coordinate = coordinate("wpimath", ArtifactKind::Jni, "2024.3.2");
+        assert_eq!(
+            coordinate.to_string(),
+            "edu.wpi.first.wpimath:wpimath-jni:2024.3.2"
+        );
+        assert_eq!(
+            artifact_kind("wpimath", &coordinate.artifact_id),
+            Some(coordinate.kind)
+        );
+    }
+
+    #[test]
+    fn wpilib_core() {
+        let dep = VendorDep::wpilib_core(WPILIB_LATEST_YEAR, WPILIB_LATEST_VERSION);
+        assert_eq!(dep.validate(WPILIB_LATEST_YEAR), Ok(()));
+        assert_eq!(dep.java_dependencies.len(), 10);
+        assert_eq!(dep.jni_dependencies.len(), 7);
+        assert_eq!(dep.cpp_dependencies.len(), 9);
+        let coordinates = dep
+            .coordinates()
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>();
+        assert!(coordinates.contains(&"edu.wpi.first.wpimath:wpimath-cpp:2024.3.2".to_string()));
+        assert!(coordinates.contains(&"edu.wpi.first.hal:hal-jni:2024.3.2".to_string()));
+        assert!(coordinates.contains(&"edu.wpi.first.wpilibj:wpilibj-java:2024.3.2".to_string()));
+        // Matches what wpilib-index generates for the same library.
+        let hal: VendorDep =
+            serde_json::from_slice(include_bytes!("../wpilib/wpilib-hal.json")).unwrap();
+        assert_eq!(file_name(&hal.name), hal.file_name);
+        assert!(hal.file_name_matches_name());
+        let core = VendorDep::wpilib_core(2025, &hal.version);
+        let cpp = core
+            .cpp_dependencies
+            .iter()
+            .find(|x| x.artifact_id == "hal-cpp")
+            .unwrap();
+        assert_eq!(cpp.group_id, hal.cpp_dependencies[0].group_id);
+        assert_eq!(
+            cpp.binary_platforms,
+            hal.cpp_dependencies[0].binary_platforms
+        );
+        assert_eq!(
+            core.jni_dependencies
+                .iter()
+                .find(|x| x.artifact_id == "hal-jni")
+                .unwrap()
+                .is_jar,
+            hal.jni_dependencies[0].is_jar
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::WPILIB_LATEST_YEAR;
+
+#[doc = "An FRC season, such as 2024. Vendordeps are built against one season's WPILib and don't work with another's. "]
+#[doc = "Deserializes from either a number or a string, since vendordep JSON in the wild uses both."]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(transparent)]
+pub struct FrcYear(pub u32);
+
+impl FrcYear {
+    #[doc = "The season of [`WPILIB_LATEST_YEAR`]."]
+    pub const fn current() -> Self {
+        Self(WPILIB_LATEST_YEAR)
+    }
+
+    #[doc = "The season a version such as `2025.1.1`, `25.2.0` or `v2024.3.2-beta-1` was released for, read from its first component: "]
+    #[doc = "a year, or the last two digits of one as vendors such as CTRE use. `None` if the version doesn't start with either."]
+    pub fn from_version(version: &str) -> Option<Self> {
+        let year = version
+            .trim_start_matches('v')
+            .split(['.', '-'])
+            .next()?
+            .parse::<u32>()
+            .ok()?;
+        match year {
+            20..=99 => Some(Self(2000 + year)),
+            2000..=2099 => Some(Self(year)),
+            _ => None,
+        }
+    }
+
+    #[doc = "Whether something built for this season can be used alongside something built for `other`, that is whether they are the same season."]
+    pub fn is_compatible_with(&self, other: FrcYear) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl From<u32> for FrcYear {
+    fn from(year: u32) -> Self {
+        Self(year)
+    }
+}
+
+impl PartialEq<u32> for FrcYear {
+    fn eq(&self, other: &u32) -> bool {
+        self.0 == *other
+    }
+}
+
+impl std::fmt::Display for FrcYear {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for FrcYear {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::__private::deserialize_string_or_u32_for_u32(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FrcYear;
+    use crate::WPILIB_LATEST_YEAR;
+
+    #[test]
+    fn frc_year() {
+        assert_eq!(
+            serde_json::from_str::<FrcYear>("2024").unwrap(),
+            FrcYear(2024)
+        );
+        assert_eq!(
+            serde_json::from_str::<FrcYear>("\"2024\"").unwrap(),
+            FrcYear(2024)
+        );
+        assert!(serde_json::from_str::<FrcYear>("\"twenty\"").is_err());
+        assert_eq!(serde_json::to_string(&FrcYear(2024)).unwrap(), "2024");
+        assert_eq!(FrcYear::current(), WPILIB_LATEST_YEAR);
+        assert!(FrcYear(2024).is_compatible_with(FrcYear(2024)));
+        assert!(!FrcYear(2024).is_compatible_with(FrcYear(2025)));
+        assert_eq!(FrcYear(2025).to_string(), "2025");
+        assert_eq!(FrcYear::from_version("2025.1.1"), Some(FrcYear(2025)));
+        assert_eq!(FrcYear::from_version("25.2.0"), Some(FrcYear(2025)));
+        assert_eq!(
+            FrcYear::from_version("v2024.3.2-beta-1"),
+            Some(FrcYear(2024))
+        );
+        assert_eq!(FrcYear::from_version("1.0.0"), None);
+        assert_eq!(FrcYear::from_version("latest"), None);
+    }
+}
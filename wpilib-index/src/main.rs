@@ -1,11 +1,37 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use clap::Parser;
 use reqwest::Client;
 use serde::Deserialize;
-use vendordeps::{CppDependency, JavaDependency, JniDependency};
+use vendordeps::{
+    maven::MavenMetadata,
+    wpilib::{self, artifact_kind},
+    ArtifactKind, BinaryPlatform, CppDependency, FrcYear, JavaDependency, JniDependency, VendorDep,
+};
 
-const LATEST_VERSION: &'static str = "2025.1.1-beta-1";
-const YEAR: u32 = 2025;
+#[derive(Parser, Debug)]
+#[command(about = "Generate vendordep JSON files for WPILib's own Maven artifacts.")]
+struct Args {
+    #[arg(
+        long = "version",
+        help = "WPILib version to index. May be repeated. Defaults to the latest release of each artifact."
+    )]
+    versions: Vec<String>,
+    #[arg(long, help = "FRC year. Defaults to the year prefix of the version.")]
+    year: Option<u32>,
+    #[arg(
+        long,
+        default_value = "wpilib",
+        help = "Directory to write vendordeps to. With several versions, each is written to a subdirectory named after the version."
+    )]
+    out_dir: PathBuf,
+    #[arg(
+        long,
+        default_value = "https://raw.githubusercontent.com/wilsonwatson/vendordeps/main/wpilib",
+        help = "URL the written vendordeps will be published under, which their jsonUrl points into."
+    )]
+    json_url_base: String,
+}
 
 #[derive(Deserialize, Debug)]
 struct FolderItem {
@@ -18,192 +44,385 @@ struct Folder {
     data: Vec<FolderItem>,
 }
 
-async fn index_artifactory(client: &Client, base: &str, link: &str) {
-    let wpilib_dir = Path::new("wpilib");
-    _ = std::fs::create_dir_all(wpilib_dir);
+async fn list_folder(client: &Client, url: &str) -> Option<Folder> {
+    let res = client.get(url).send().await.unwrap();
+    if !res.status().is_success() {
+        return None;
+    }
+    Some(res.json().await.unwrap())
+}
+
+async fn release_version(
+    client: &Client,
+    link: &str,
+    name: &str,
+    artifact_id: &str,
+) -> Option<String> {
+    let group_id = format!("{}.{}", link.replace('/', "."), name);
+    let url = MavenMetadata::url(
+        vendordeps::WPILIB_RELEASE_MAVEN_REPO,
+        &group_id,
+        artifact_id,
+    );
+    let res = client.get(&url).send().await.unwrap();
+    if !res.status().is_success() {
+        return None;
+    }
+    MavenMetadata::parse(&res.text().await.unwrap())
+        .unwrap()
+        .release
+}
+
+// Returns the platforms `artifact_id` is published for at `version`, and whether the artifacts are jars.
+async fn list_platforms(
+    client: &Client,
+    base: &str,
+    link: &str,
+    name: &str,
+    artifact_id: &str,
+    version: &str,
+) -> (Vec<String>, bool) {
+    let mut support = Vec::new();
+    let mut is_jar = false;
+    let Some(folder) = list_folder(
+        client,
+        &format!(
+            "{}/{}/{}/{}/{}/?recordNum=0",
+            base, link, name, artifact_id, version
+        ),
+    )
+    .await
+    else {
+        return (support, is_jar);
+    };
+    for item in folder.data {
+        if let Some((platform, jar)) = artifact_platform(&item.name, artifact_id, version) {
+            support.push(platform.to_str().to_string());
+            is_jar |= jar;
+        }
+    }
+    (support, is_jar)
+}
+
+// The platform of a release build file named `<artifact_id>-<version>-<platform>.(zip|jar)`, and whether it is a jar.
+// Debug and static builds (`linuxathenadebug`, `linuxathenastatic`, ...), headers, sources, checksums and anything
+// else whose classifier isn't a known platform are `None`.
+fn artifact_platform(
+    file_name: &str,
+    artifact_id: &str,
+    version: &str,
+) -> Option<(BinaryPlatform, bool)> {
+    let rest = file_name
+        .strip_prefix(artifact_id)?
+        .strip_prefix('-')?
+        .strip_prefix(version)?
+        .strip_prefix('-')?;
+    let (classifier, is_jar) = match rest.strip_suffix(".zip") {
+        Some(classifier) => (classifier, false),
+        None => (rest.strip_suffix(".jar")?, true),
+    };
+    match classifier.parse() {
+        Ok(BinaryPlatform::Other(_) | BinaryPlatform::Headers) => None,
+        Ok(platform) => Some((platform, is_jar)),
+        Err(e) => match e {},
+    }
+}
+
+async fn version_exists(client: &Client, base: &str, link: &str, version: &str) -> bool {
     let folder: Folder = client
-        .get(&format!("{}/{}/?recordNum=0", base, link))
+        .get(&format!(
+            "{}/{}/wpiutil/wpiutil-cpp/?recordNum=0",
+            base, link
+        ))
         .send()
         .await
         .unwrap()
         .json()
         .await
         .unwrap();
+    folder.data.iter().any(|x| x.folder && x.name == version)
+}
+
+async fn index_artifactory(
+    client: &Client,
+    base: &str,
+    link: &str,
+    version: Option<&str>,
+    year: Option<u32>,
+    wpilib_dir: &Path,
+    json_url_base: &str,
+) {
+    _ = std::fs::create_dir_all(wpilib_dir);
+    let folder = list_folder(client, &format!("{}/{}/?recordNum=0", base, link))
+        .await
+        .unwrap();
     for item in folder.data {
         if !item.folder {
             continue;
         }
         let name = item.name;
-        let folder: Folder = client
-            .get(&format!("{}/{}/{}/?recordNum=0", base, link, &name))
-            .send()
-            .await
-            .unwrap()
-            .json()
-            .await
-            .unwrap();
-        let mut jni: Vec<(String, Vec<String>)> = Vec::new();
-        let mut java: Vec<String> = Vec::new();
-        let mut cpp: Vec<(String, Vec<String>)> = Vec::new();
+        let Some(folder) =
+            list_folder(client, &format!("{}/{}/{}/?recordNum=0", base, link, &name)).await
+        else {
+            continue;
+        };
+        let mut jni: Vec<(String, String, Vec<String>, bool)> = Vec::new();
+        let mut java: Vec<(String, String)> = Vec::new();
+        let mut cpp: Vec<(String, String, Vec<String>)> = Vec::new();
         for item in folder.data {
             let artifact_id = item.name.as_str();
-            if artifact_id == format!("{}-cpp", &name) {
-                let mut support = Vec::new();
-                let folder: Folder = client
-                    .get(&format!(
-                        "{}/{}/{}/{}/?recordNum=0",
-                        base, link, &name, artifact_id
-                    ))
-                    .send()
-                    .await
-                    .unwrap()
-                    .json()
-                    .await
-                    .unwrap();
-                for item in folder.data {
-                    let version = item.name.as_str();
-                    if item.name == LATEST_VERSION {
-                        let folder: Folder = client
-                            .get(&format!(
-                                "{}/{}/{}/{}/{}/?recordNum=0",
-                                base, link, &name, artifact_id, version
-                            ))
-                            .send()
-                            .await
-                            .unwrap()
-                            .json()
-                            .await
-                            .unwrap();
-                        let expected_start = format!("{}-{}-", artifact_id, version);
-                        for item in folder.data {
-                            let zipname = item.name.as_str();
-                            if zipname.ends_with("debug.zip")
-                                || zipname.ends_with("debug.jar")
-                                || zipname.ends_with("static.zip")
-                                || zipname.ends_with("static.jar")
-                                || zipname.ends_with("staticdebug.zip")
-                                || zipname.ends_with("staticdebug.jar")
-                                || zipname.ends_with("sources.zip")
-                                || zipname.ends_with("sources.jar")
-                                || zipname.ends_with("headers.zip")
-                                || zipname.ends_with("headers.jar")
-                            {
-                                continue;
-                            }
-                            if zipname.starts_with(&expected_start) {
-                                let ending = &zipname[expected_start.len()..zipname.len() - 4];
-                                support.push(ending.to_string());
-                            }
-                        }
+            let Some(kind) = artifact_kind(&name, artifact_id) else {
+                continue;
+            };
+            let version = match version {
+                Some(version) => version.to_string(),
+                None => match release_version(client, link, &name, artifact_id).await {
+                    Some(version) => version,
+                    None => continue,
+                },
+            };
+            match kind {
+                ArtifactKind::Cpp => {
+                    let (support, _) =
+                        list_platforms(client, base, link, &name, artifact_id, &version).await;
+                    if !support.is_empty() {
+                        cpp.push((artifact_id.to_string(), version, support));
                     }
                 }
-                if !support.is_empty() {
-                    cpp.push((artifact_id.to_string(), support));
-                }
-            } else if item.name == format!("{}-java", &name) {
-                java.push(artifact_id.to_string());
-            } else if item.name == format!("{}-jni", &name) {
-                let mut support = Vec::new();
-                let folder: Folder = client
-                    .get(&format!(
-                        "{}/{}/{}/{}/?recordNum=0",
-                        base, link, &name, artifact_id
-                    ))
-                    .send()
-                    .await
-                    .unwrap()
-                    .json()
-                    .await
-                    .unwrap();
-                for item in folder.data {
-                    let version = item.name.as_str();
-                    if item.name == LATEST_VERSION {
-                        let folder: Folder = client
-                            .get(&format!(
-                                "{}/{}/{}/{}/{}/?recordNum=0",
-                                base, link, &name, artifact_id, version
-                            ))
-                            .send()
-                            .await
-                            .unwrap()
-                            .json()
-                            .await
-                            .unwrap();
-                        let expected_start = format!("{}-{}-", artifact_id, version);
-                        for item in folder.data {
-                            let zipname = item.name.as_str();
-                            if zipname.ends_with("debug.zip")
-                                || zipname.ends_with("debug.jar")
-                                || zipname.ends_with("static.zip")
-                                || zipname.ends_with("static.jar")
-                                || zipname.ends_with("staticdebug.zip")
-                                || zipname.ends_with("staticdebug.jar")
-                                || zipname.ends_with("sources.zip")
-                                || zipname.ends_with("sources.jar")
-                                || zipname.ends_with("headers.zip")
-                                || zipname.ends_with("headers.jar")
-                            {
-                                continue;
-                            }
-                            if zipname.starts_with(&expected_start) {
-                                let ending = &zipname[expected_start.len()..zipname.len() - 4];
-                                support.push(ending.to_string());
-                            }
-                        }
+                ArtifactKind::Java => java.push((artifact_id.to_string(), version)),
+                ArtifactKind::Jni => {
+                    let (support, is_jar) =
+                        list_platforms(client, base, link, &name, artifact_id, &version).await;
+                    if !support.is_empty() {
+                        jni.push((artifact_id.to_string(), version, support, is_jar));
                     }
                 }
-                if !support.is_empty() {
-                    jni.push((artifact_id.to_string(), support));
-                }
             }
         }
         if cpp.is_empty() && java.is_empty() && jni.is_empty() {
-            continue
+            continue;
         }
-        let file_name = format!("wpilib-{}.json", name);
-        let vendordep = vendordeps::VendorDep {
-            file_name: file_name.clone(),
-            version: LATEST_VERSION.to_string(),
-            uuid: uuid::Uuid::new_v4().to_string(),
-            name: name.to_string(),
-            frc_year: YEAR,
-            maven_urls: vec!["https://frcmaven.wpi.edu/artifactory/release/".to_string()],
-            json_url: format!("https://raw.githubusercontent.com/wilsonwatson/vendordeps/main/wpilib/{}", file_name),
-            conflicts_with: vec![],
-            java_dependencies: java.into_iter().map(|x| JavaDependency {
-                group_id: format!("edu.wpi.first.{}", name),
+        let vendordep_version = match version {
+            Some(version) => version.to_string(),
+            None => cpp
+                .iter()
+                .map(|x| &x.1)
+                .chain(java.iter().map(|x| &x.1))
+                .chain(jni.iter().map(|x| &x.1))
+                .next()
+                .unwrap()
+                .clone(),
+        };
+        let year = year
+            .or_else(|| FrcYear::from_version(&vendordep_version).map(|x| x.0))
+            .expect("Could not determine the FRC year; pass --year");
+        let file_name = wpilib::file_name(&name);
+        let path = wpilib_dir.join(&file_name);
+        let previous = previous_vendordep(&path);
+        // The uuid identifies the library in compatibility checks, so it must not change between runs.
+        let uuid = match &previous {
+            Some(previous) => previous.uuid.clone(),
+            None => uuid::Uuid::new_v4().to_string(),
+        };
+        let mut vendordep = VendorDep::builder(&name, uuid)
+            .file_name(&file_name)
+            .version(vendordep_version)
+            .frc_year(year)
+            .add_maven_url(wpilib::RELEASE_MAVEN_REPO)
+            .json_url(format!(
+                "{}/{}",
+                json_url_base.trim_end_matches('/'),
+                file_name
+            ));
+        for (x, v) in java {
+            vendordep = vendordep.add_java_dependency(JavaDependency {
+                group_id: wpilib::group_id(&name),
                 artifact_id: x,
-                version: LATEST_VERSION.to_string(),
-            }).collect(),
-            cpp_dependencies: cpp.into_iter().map(|(x, d)| CppDependency {
-                group_id: format!("edu.wpi.first.{}", name),
+                version: v,
+                classifier: None,
+                packaging: "jar".to_string(),
+                maven_urls: None,
+            });
+        }
+        for (x, v, d) in cpp {
+            vendordep = vendordep.add_cpp_dependency(CppDependency {
+                group_id: wpilib::group_id(&name),
                 artifact_id: x,
-                version: LATEST_VERSION.to_string(),
-                header_classifier: "headers".to_string(),
-                binary_platforms: d
-            }).collect(),
-            jni_dependencies: jni.into_iter().map(|(x, d)| JniDependency {
-                group_id: format!("edu.wpi.first.{}", name),
+                version: v,
+                header_classifier: wpilib::HEADER_CLASSIFIER.to_string(),
+                binary_platforms: d,
+                classifier_template: None,
+                maven_urls: None,
+                packaging: None,
+            });
+        }
+        for (x, v, d, is_jar) in jni {
+            vendordep = vendordep.add_jni_dependency(JniDependency {
+                group_id: wpilib::group_id(&name),
                 artifact_id: x,
-                version: LATEST_VERSION.to_string(),
-                is_jar: true, /* TODO: detect this */
+                version: v,
+                is_jar,
                 skip_invalid_platforms: true,
                 valid_platforms: d,
                 sim_mode: None,
-            }).collect(),
-        };
-        let vendordep = serde_json::to_string_pretty(&vendordep).unwrap();
-        std::fs::write(wpilib_dir.join(file_name), vendordep).unwrap();
+                classifier_template: None,
+                maven_urls: None,
+            });
+        }
+        let mut vendordep = vendordep.build();
+        vendordep.canonicalize();
+        write_if_changed(&path, &vendordep, previous.as_ref());
     }
 }
 
+// The vendordep an earlier run wrote to `path`, if any.
+fn previous_vendordep(path: &Path) -> Option<VendorDep> {
+    std::fs::read_to_string(path).ok()?.parse().ok()
+}
+
+// Write `vendordep` to `path` unless `previous`, the vendordep already there, declares the same, so unchanged files aren't touched.
+// Returns whether the file was written.
+fn write_if_changed(path: &Path, vendordep: &VendorDep, previous: Option<&VendorDep>) -> bool {
+    if previous.is_some_and(|x| x.content_hash() == vendordep.content_hash()) {
+        return false;
+    }
+    std::fs::write(path, vendordep.to_json(true).unwrap()).unwrap();
+    true
+}
+
+const BASE: &str = "https://frcmaven.wpi.edu/ui/api/v1/ui/v2/nativeBrowser/release";
+const LINK: &str = "edu/wpi/first";
+
 #[tokio::main]
 async fn main() {
+    let args = Args::parse();
     let client = Client::new();
-    index_artifactory(
-        &client,
-        "https://frcmaven.wpi.edu/ui/api/v1/ui/v2/nativeBrowser/release",
-        "edu/wpi/first",
-    )
-    .await;
+    if args.versions.is_empty() {
+        index_artifactory(
+            &client,
+            BASE,
+            LINK,
+            None,
+            args.year,
+            &args.out_dir,
+            &args.json_url_base,
+        )
+        .await;
+        return;
+    }
+    for version in &args.versions {
+        if !version_exists(&client, BASE, LINK, version).await {
+            eprintln!(
+                "WPILib version {} was not found in {}/{}",
+                version, BASE, LINK
+            );
+            std::process::exit(1);
+        }
+    }
+    for version in &args.versions {
+        let out_dir = if args.versions.len() > 1 {
+            args.out_dir.join(version)
+        } else {
+            args.out_dir.clone()
+        };
+        index_artifactory(
+            &client,
+            BASE,
+            LINK,
+            Some(version),
+            args.year,
+            &out_dir,
+            &args.json_url_base,
+        )
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use vendordeps::{ArtifactKind, BinaryPlatform, VendorDep};
+
+    use super::{artifact_kind, artifact_platform, previous_vendordep, write_if_changed};
+
+    #[test]
+    fn artifact_kinds() {
+        // Artifact folders as listed under https://frcmaven.wpi.edu/artifactory/release/edu/wpi/first/
+        assert_eq!(
+            artifact_kind("wpimath", "wpimath-cpp"),
+            Some(ArtifactKind::Cpp)
+        );
+        assert_eq!(
+            artifact_kind("wpimath", "wpimath-java"),
+            Some(ArtifactKind::Java)
+        );
+        assert_eq!(
+            artifact_kind("wpilibNewCommands", "wpilibNewCommands-cpp"),
+            Some(ArtifactKind::Cpp)
+        );
+        assert_eq!(artifact_kind("hal", "hal-jni"), Some(ArtifactKind::Jni));
+        assert_eq!(artifact_kind("wpimath", "wpimathjni"), None);
+        assert_eq!(artifact_kind("wpimath", "WPIMath-cpp"), None);
+        assert_eq!(
+            artifact_kind("wpilibNewCommands", "wpilibnewcommands-cpp"),
+            None
+        );
+        assert_eq!(artifact_kind("wpiutil", "wpiutil-cpp-sources"), None);
+        assert_eq!(artifact_kind("wpi", "wpiutil-cpp"), None);
+    }
+
+    #[test]
+    fn artifact_platforms() {
+        // Files as listed under https://frcmaven.wpi.edu/artifactory/release/edu/wpi/first/
+        let platform = |file_name| artifact_platform(file_name, "wpimath-cpp", "2024.3.2");
+        assert_eq!(
+            platform("wpimath-cpp-2024.3.2-linuxathena.zip"),
+            Some((BinaryPlatform::LinuxAthena, false))
+        );
+        assert_eq!(
+            platform("wpimath-cpp-2024.3.2-linuxx86-64.zip"),
+            Some((BinaryPlatform::LinuxX86_64, false))
+        );
+        assert_eq!(
+            platform("wpimath-cpp-2024.3.2-windowsx86-64.jar"),
+            Some((BinaryPlatform::WindowsX86_64, true))
+        );
+        for file_name in [
+            "wpimath-cpp-2024.3.2-linuxathenadebug.zip",
+            "wpimath-cpp-2024.3.2-linuxathenastatic.zip",
+            "wpimath-cpp-2024.3.2-linuxathenastaticdebug.zip",
+            "wpimath-cpp-2024.3.2-headers.zip",
+            "wpimath-cpp-2024.3.2-sources.zip",
+            "wpimath-cpp-2024.3.2-linuxx86-64.zip.md5",
+            "wpimath-cpp-2024.3.2-linuxx86-64.jar.sha1",
+            "wpimath-cpp-2024.3.2.pom",
+            "wpimath-cpp-2024.3.2-linuxx86-64.tar.gz",
+            "wpimath-cpp-2024.3.1-linuxx86-64.zip",
+            "wpimath-java-2024.3.2-linuxx86-64.jar",
+        ] {
+            assert_eq!(platform(file_name), None, "{}", file_name);
+        }
+    }
+
+    #[test]
+    fn unchanged_files_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wpilib-wpimath.json");
+        assert!(previous_vendordep(&path).is_none());
+        let mut vendordep = VendorDep::wpilib_core(2024, "2024.3.2");
+        vendordep.canonicalize();
+        assert!(write_if_changed(&path, &vendordep, None));
+
+        std::fs::write(&path, vendordep.to_json(false).unwrap()).unwrap();
+        let previous = previous_vendordep(&path).unwrap();
+        assert_eq!(previous.uuid, vendordep.uuid);
+        assert!(!write_if_changed(&path, &vendordep, Some(&previous)));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            vendordep.to_json(false).unwrap()
+        );
+
+        vendordep.version = "2024.3.3".to_string();
+        assert!(write_if_changed(&path, &vendordep, Some(&previous)));
+        assert_eq!(previous_vendordep(&path).unwrap().version, "2024.3.3");
+    }
 }